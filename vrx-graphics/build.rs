@@ -13,7 +13,7 @@ fn compile_shader(filename: &'static str) {
     }
 
     let args = [input.to_str().unwrap(), "-o", output.to_str().unwrap()];
-    let output = Command::new("glslc").args(&args).output().expect("failed");
+    let output = Command::new("glslc").args(args).output().expect("failed");
 
     println!("status: {}", output.status);
     io::stdout().write_all(&output.stdout).unwrap();
@@ -4,17 +4,18 @@ extern crate winit;
 use anyhow::{anyhow, Result};
 use paste::paste;
 use vrx::memory::*;
+use vrx::presentation::*;
 use vrx::*;
 
 use lazy_static::lazy_static;
 use nalgebra_glm as glm;
+use vrx::raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
 
 use winit::{
     dpi::LogicalSize,
     event::{Event, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
     platform::run_return::EventLoopExtRunReturn,
-    platform::windows::WindowExtWindows,
     window::Window,
     window::WindowBuilder,
 };
@@ -28,6 +29,7 @@ struct Presentation<'a> {
     format: VkSurfaceFormatKHR,
     extent: VkExtent2D,
 
+    context: Option<&'a Context>,
     device: Option<&'a VkDevice>,
 }
 
@@ -40,15 +42,32 @@ impl<'a> Default for Presentation<'a> {
             image_views: vec![],
             format: VkSurfaceFormatKHR::default(),
             extent: VkExtent2D::default(),
+            context: None,
             device: None,
         }
     }
 }
 
+impl<'a> Drop for Presentation<'a> {
+    fn drop(&mut self) {
+        self.destroy();
+    }
+}
+
 impl<'a> Presentation<'a> {
-    fn new(device: &'a VkDevice, queue_family_indices: &[u32], window: &Window) -> Self {
-        let surface = Self::new_surface(&window);
-        let support = SwapchainSupport::new(&surface);
+    fn new(
+        context: &'a Context,
+        physical_device: VkPhysicalDevice,
+        device: &'a VkDevice,
+        queue_family_indices: &[u32],
+        window: &Window,
+    ) -> Result<Self> {
+        // a failure drops `presentation`, destroying the surface and what came after it
+        let mut presentation = Self::default();
+        presentation.surface = Self::new_surface(context, window);
+        presentation.context = Some(context);
+        presentation.device = Some(device);
+        let support = SwapchainSupport::new(physical_device, &presentation.surface)?;
 
         let format = support.get_swapchain_surface_format(
             VkFormat::VK_FORMAT_R8G8B8A8_SRGB,
@@ -56,40 +75,30 @@ impl<'a> Presentation<'a> {
         );
         let present_mode = support.get_swapchain_present_mode(VK_PRESENT_MODE_MAILBOX_KHR);
         let extent = support.get_swapchain_extent();
-        let swapchain = Self::new_swapchain(
+        presentation.format = format;
+        presentation.extent = extent;
+        presentation.swapchain = Self::new_swapchain(
             device,
             queue_family_indices,
-            &surface,
+            &presentation.surface,
             support.capabilities,
             format,
             present_mode,
             extent,
-        );
-
-        let images = device.get_swapchain_images_khr(swapchain);
-        let image_views = Self::new_image_views(device, &images, format);
+        )?;
 
-        let device = Some(device);
-        Self {
-            surface,
-            swapchain,
-            images,
-            image_views,
-            format,
-            extent,
-            device,
+        presentation.images = device.get_swapchain_images_khr(presentation.swapchain)?;
+        for image in &presentation.images {
+            let image_view = Self::new_image_view(device, *image, format)?;
+            presentation.image_views.push(image_view);
         }
-    }
-
-    fn new_surface(window: &Window) -> VkSurfaceKHR {
-        let ctx = vulkan_context();
 
-        let win32_surface_create_info = VkWin32SurfaceCreateInfoKHRBuilder::new()
-            .hinstance(window.hinstance() as vrx::HINSTANCE)
-            .hwnd(window.hwnd() as vrx::HWND)
-            .build();
+        Ok(presentation)
+    }
 
-        ctx.create_win32_surface_khr(&win32_surface_create_info, None)
+    fn new_surface(ctx: &Context, window: &Window) -> VkSurfaceKHR {
+        ctx.create_surface(window.raw_display_handle(), window.raw_window_handle())
+            .expect("[vrx-graphics] failed to create the window surface")
     }
 
     fn new_swapchain(
@@ -100,7 +109,7 @@ impl<'a> Presentation<'a> {
         surface_format: VkSurfaceFormatKHR,
         present_mode: VkPresentModeKHR,
         extent: VkExtent2D,
-    ) -> VkSwapchainKHR {
+    ) -> Result<VkSwapchainKHR> {
         let swapchain_create_info = VkSwapchainCreateInfoKHRBuilder::new()
             .surface(*surface)
             .min_image_count(capabilities.minImageCount)
@@ -108,7 +117,7 @@ impl<'a> Presentation<'a> {
             .image_color_space(surface_format.colorSpace)
             .image_extent(extent)
             .image_array_layers(1)
-            .image_usage(VK_IMAGE_USAGE_COLOR_ATTACHMENT_BIT as u32)
+            .image_usage(VK_IMAGE_USAGE_COLOR_ATTACHMENT_BIT)
             .image_sharing_mode(VkSharingMode::VK_SHARING_MODE_EXCLUSIVE)
             .queue_family_index_count(queue_family_indices.len() as u32)
             .p_queue_family_indices(queue_family_indices.as_ptr())
@@ -117,78 +126,121 @@ impl<'a> Presentation<'a> {
             .present_mode(present_mode)
             .clipped(VK_TRUE)
             .build();
-        device.create_swapchain(&swapchain_create_info, None)
+        Ok(device.create_swapchain(&swapchain_create_info, None)?)
     }
 
-    fn new_image_views(
+    fn new_image_view(
         device: &VkDevice,
-        images: &Vec<VkImage>,
+        image: VkImage,
         format: VkSurfaceFormatKHR,
-    ) -> Vec<VkImageView> {
-        let image_views: Vec<_> = images
-            .iter()
-            .map(|image| {
-                let components = VkComponentMapping {
-                    r: VkComponentSwizzle::VK_COMPONENT_SWIZZLE_IDENTITY,
-                    g: VkComponentSwizzle::VK_COMPONENT_SWIZZLE_IDENTITY,
-                    b: VkComponentSwizzle::VK_COMPONENT_SWIZZLE_IDENTITY,
-                    a: VkComponentSwizzle::VK_COMPONENT_SWIZZLE_IDENTITY,
-                };
+    ) -> Result<VkImageView> {
+        let components = VkComponentMapping {
+            r: VkComponentSwizzle::VK_COMPONENT_SWIZZLE_IDENTITY,
+            g: VkComponentSwizzle::VK_COMPONENT_SWIZZLE_IDENTITY,
+            b: VkComponentSwizzle::VK_COMPONENT_SWIZZLE_IDENTITY,
+            a: VkComponentSwizzle::VK_COMPONENT_SWIZZLE_IDENTITY,
+        };
 
-                let subresource_range = VkImageSubresourceRange {
-                    aspectMask: VK_IMAGE_ASPECT_COLOR_BIT as u32,
-                    baseMipLevel: 0,
-                    levelCount: 1,
-                    baseArrayLayer: 0,
-                    layerCount: 1,
-                };
+        let subresource_range = VkImageSubresourceRange {
+            aspectMask: VK_IMAGE_ASPECT_COLOR_BIT,
+            baseMipLevel: 0,
+            levelCount: 1,
+            baseArrayLayer: 0,
+            layerCount: 1,
+        };
 
-                let image_view_create_info = VkImageViewCreateInfoBuilder::new()
-                    .image(*image)
-                    .view_type(VkImageViewType::VK_IMAGE_VIEW_TYPE_2D)
-                    .format(format.format)
-                    .components(components)
-                    .subresource_range(subresource_range)
-                    .build();
-                device.create_image_view(&image_view_create_info, None)
-            })
-            .collect::<Vec<VkImageView>>();
+        let image_view_create_info = VkImageViewCreateInfoBuilder::new()
+            .image(image)
+            .view_type(VkImageViewType::VK_IMAGE_VIEW_TYPE_2D)
+            .format(format.format)
+            .components(components)
+            .subresource_range(subresource_range)
+            .build();
+        Ok(device.create_image_view(&image_view_create_info, None)?)
+    }
+}
 
-        image_views
+impl<'a> Present for Presentation<'a> {
+    fn images(&self) -> &[VkImage] {
+        &self.images
     }
 
-    pub fn destroy(&mut self) {
-        // self.images
-        //     .iter()
-        //     .for_each(|i| self.device.unwrap().destroy_image(*i, None));
+    fn image_views(&self) -> &[VkImageView] {
+        &self.image_views
+    }
 
-        self.image_views
-            .iter()
-            .for_each(|iv| self.device.unwrap().destroy_image_view(*iv, None));
+    fn format(&self) -> VkSurfaceFormatKHR {
+        self.format
+    }
 
-        self.device.unwrap().destroy_swapchain(self.swapchain, None);
+    fn extent(&self) -> VkExtent2D {
+        self.extent
     }
-}
 
-#[derive(Debug)]
-struct ShaderModules<'a> {
-    shader_modules: Vec<VkShaderModule>,
-    shader_stages: Vec<VkShaderStageFlagBits>,
-    create_infos: Vec<VkPipelineShaderStageCreateInfo>,
+    fn final_layout(&self) -> VkImageLayout {
+        VkImageLayout::VK_IMAGE_LAYOUT_PRESENT_SRC_KHR
+    }
 
-    device: Option<&'a VkDevice>,
-}
+    fn acquire(&mut self, semaphore: VkSemaphore) -> Result<Option<u32>> {
+        let result = self.device.unwrap().acquire_next_image_khr(
+            self.swapchain,
+            u64::MAX,
+            semaphore,
+            std::ptr::null_mut(),
+        );
 
-impl<'a> Default for ShaderModules<'a> {
-    fn default() -> Self {
-        Self {
-            shader_modules: vec![],
-            shader_stages: vec![],
-            create_infos: vec![],
+        match result {
+            Ok((image_index, _)) => Ok(Some(image_index)),
+            Err(VkError::OutOfDateKHR) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
 
-            device: None,
+    fn present(
+        &mut self,
+        queue: VkQueue,
+        wait_semaphores: &[VkSemaphore],
+        image_index: u32,
+    ) -> Result<bool> {
+        let present_info = VkPresentInfoKHRBuilder::new()
+            .wait_semaphore_count(wait_semaphores.len() as u32)
+            .p_wait_semaphores(wait_semaphores.as_ptr())
+            .swapchain_count(1)
+            .p_swapchains(&self.swapchain)
+            .p_image_indices(&image_index)
+            .build();
+
+        match queue.present_khr(0, &present_info) {
+            Ok(result) => Ok(result == VkResult::VK_SUBOPTIMAL_KHR),
+            Err(VkError::OutOfDateKHR) => Ok(true),
+            Err(e) => Err(e.into()),
         }
     }
+
+    fn destroy(&mut self) {
+        let (context, device) = match (self.context, self.device) {
+            (Some(context), Some(device)) => (context, device),
+            _ => return,
+        };
+
+        self.image_views
+            .drain(..)
+            .for_each(|iv| device.destroy_image_view(iv, None));
+        // the images belong to the swapchain
+        self.images.clear();
+
+        device.destroy_swapchain(self.swapchain, None);
+        context.destroy_surface_khr(self.surface, None);
+        self.swapchain = std::ptr::null_mut();
+        self.surface = std::ptr::null_mut();
+    }
+}
+
+#[derive(Debug, Default)]
+struct ShaderModules<'a> {
+    shader_modules: Vec<Owned<'a, VkShaderModule>>,
+    shader_stages: Vec<VkShaderStageFlagBits>,
+    create_infos: Vec<VkPipelineShaderStageCreateInfo>,
 }
 
 impl<'a> ShaderModules<'a> {
@@ -196,28 +248,21 @@ impl<'a> ShaderModules<'a> {
         device: &'a VkDevice,
         shader_bytes: &[&[u8]],
         shader_stages: &[VkShaderStageFlagBits],
-    ) -> Self {
+    ) -> Result<Self> {
         let shader_modules = shader_bytes
             .iter()
             .enumerate()
-            .map(|(i, bytes)| device.create_shader_module(bytes, None))
-            .collect::<Vec<VkShaderModule>>();
+            .map(|(i, bytes)| device.create_shader_module_owned(bytes, None))
+            .collect::<std::result::Result<Vec<_>, VkError>>()?;
 
         let mut shader_modules = Self {
-            device: Some(device),
-            shader_modules: shader_modules,
+            shader_modules,
             shader_stages: shader_stages.to_vec(),
             create_infos: vec![],
         };
 
         shader_modules.create_shader_stage_create_info();
-        shader_modules
-    }
-
-    fn destroy(&mut self) {
-        self.shader_modules
-            .iter()
-            .for_each(|m| self.device.unwrap().destroy_shader_module(*m, None));
+        Ok(shader_modules)
     }
 
     fn create_shader_stage_create_info(&mut self) {
@@ -228,8 +273,8 @@ impl<'a> ShaderModules<'a> {
             .map(|(i, module)| {
                 VkPipelineShaderStageCreateInfoBuilder::new()
                     .stage(self.shader_stages[i])
-                    .module(*module)
-                    .p_name(b"main\0".as_ptr() as *const i8)
+                    .module(**module)
+                    .p_name(c"main".as_ptr())
                     .build()
             })
             .collect::<Vec<VkPipelineShaderStageCreateInfo>>();
@@ -261,7 +306,7 @@ struct GraphicsPipelineProperties {
 }
 
 impl GraphicsPipelineProperties {
-    fn new(presentation: &Presentation) -> Self {
+    fn new(presentation: &dyn Present) -> Self {
         //
         // 2. fixed function
         //
@@ -305,14 +350,14 @@ impl GraphicsPipelineProperties {
 
         let scissor = VkRect2D {
             offset: VkOffset2D { x: 0, y: 0 },
-            extent: presentation.extent,
+            extent: presentation.extent(),
         };
 
         let viewports = VkViewport {
             x: 0.0,
             y: 0.0,
-            width: presentation.extent.width as f32,
-            height: presentation.extent.height as f32,
+            width: presentation.extent().width as f32,
+            height: presentation.extent().height as f32,
             minDepth: 0.0,
             maxDepth: 1.0,
         };
@@ -331,7 +376,7 @@ impl GraphicsPipelineProperties {
             .rasterizer_discard_enable(VK_FALSE)
             .polygon_mode(VkPolygonMode::VK_POLYGON_MODE_FILL)
             .line_width(1.0)
-            .cull_mode(VK_CULL_MODE_NONE as u32)
+            .cull_mode(VK_CULL_MODE_NONE)
             .front_face(VkFrontFace::VK_FRONT_FACE_CLOCKWISE)
             .depth_bias_enable(VK_FALSE)
             .build();
@@ -342,7 +387,7 @@ impl GraphicsPipelineProperties {
             .build();
 
         let color_blend_attachment_state = VkPipelineColorBlendAttachmentStateBuilder::new()
-            .color_write_mask(VK_COLOR_COMPONENT_ALL_BIT as u32)
+            .color_write_mask(VK_COLOR_COMPONENT_ALL_BIT)
             .blend_enable(VK_FALSE)
             .src_color_blend_factor(VkBlendFactor::VK_BLEND_FACTOR_ONE)
             .dst_color_blend_factor(VkBlendFactor::VK_BLEND_FACTOR_ZERO)
@@ -377,70 +422,60 @@ impl GraphicsPipelineProperties {
     }
 }
 
+// the fields are dropped in this order, the pipeline first
 struct GraphicsPipeline<'a> {
-    render_pass: VkRenderPass,
-    pipeline_layout: VkPipelineLayout,
-    pipeline: VkPipeline,
-
-    device: Option<&'a VkDevice>,
-}
-
-impl<'a> Default for GraphicsPipeline<'a> {
-    fn default() -> Self {
-        Self {
-            render_pass: vk_instantiate!(VkRenderPass),
-            pipeline_layout: vk_instantiate!(VkPipelineLayout),
-            pipeline: vk_instantiate!(VkPipeline),
-            device: None,
-        }
-    }
+    pipeline: Owned<'a, VkPipeline>,
+    pipeline_cache: Owned<'a, VkPipelineCache>,
+    pipeline_layout: Owned<'a, VkPipelineLayout>,
+    render_pass: Owned<'a, VkRenderPass>,
 }
 
 impl<'a> GraphicsPipeline<'a> {
     fn new(
         device: &'a VkDevice,
-        presentation: &Presentation,
+        presentation: &dyn Present,
         properties: &GraphicsPipelineProperties,
         shader_stages: &ShaderModules,
         set_layouts: &[VkDescriptorSetLayout],
-    ) -> Self {
-        let mut instance = Self::default();
-        instance.set_device(device);
-
+    ) -> Result<Self> {
         // real create
-        instance.create_render_pass(presentation);
-        instance.create_pipeline_layout(set_layouts);
-        instance.create_pipeline(shader_stages, properties);
-
-        instance
-    }
-
-    fn set_device(&mut self, device: &'a VkDevice) {
-        self.device = Some(device);
-    }
-
-    pub fn destroy(&mut self) {
-        self.device.unwrap().destroy_pipeline(self.pipeline, None);
-        self.device
-            .unwrap()
-            .destroy_pipeline_layout(self.pipeline_layout, None);
-        self.device
-            .unwrap()
-            .destroy_render_pass(self.render_pass, None);
+        let render_pass = Self::create_render_pass(device, presentation)?;
+        let pipeline_layout = Self::create_pipeline_layout(device, set_layouts)?;
+        let pipeline_cache_create_info = VkPipelineCacheCreateInfoBuilder::new().build();
+        let pipeline_cache =
+            device.create_pipeline_cache_owned(&pipeline_cache_create_info, None)?;
+        let pipeline = Self::create_pipeline(
+            device,
+            *render_pass,
+            *pipeline_layout,
+            *pipeline_cache,
+            shader_stages,
+            properties,
+        )?;
+
+        Ok(Self {
+            pipeline,
+            pipeline_cache,
+            pipeline_layout,
+            render_pass,
+        })
     }
 
-    fn create_render_pass(&mut self, presentation: &Presentation) {
+    fn create_render_pass(
+        device: &'a VkDevice,
+        presentation: &dyn Present,
+    ) -> Result<Owned<'a, VkRenderPass>> {
         // render pass
         // attachments
         let color_attachment_description = VkAttachmentDescriptionBuilder::new()
-            .format(presentation.format.format)
+            .format(presentation.format().format)
             .samples(VK_SAMPLE_COUNT_1_BIT)
             .load_op(VkAttachmentLoadOp::VK_ATTACHMENT_LOAD_OP_CLEAR)
             .store_op(VkAttachmentStoreOp::VK_ATTACHMENT_STORE_OP_STORE)
             .stencil_load_op(VkAttachmentLoadOp::VK_ATTACHMENT_LOAD_OP_CLEAR)
             .stencil_store_op(VkAttachmentStoreOp::VK_ATTACHMENT_STORE_OP_DONT_CARE)
             .initial_layout(VkImageLayout::VK_IMAGE_LAYOUT_UNDEFINED)
-            .final_layout(VkImageLayout::VK_IMAGE_LAYOUT_PRESENT_SRC_KHR)
+            .final_layout(presentation.final_layout())
             .build();
 
         // subpass
@@ -459,10 +494,10 @@ impl<'a> GraphicsPipeline<'a> {
         let dependency = VkSubpassDependencyBuilder::new()
             .src_subpass(VK_SUBPASS_EXTERNAL as u32)
             .dst_subpass(0)
-            .src_stage_mask(VK_PIPELINE_STAGE_COLOR_ATTACHMENT_OUTPUT_BIT as u32)
+            .src_stage_mask(VK_PIPELINE_STAGE_COLOR_ATTACHMENT_OUTPUT_BIT)
             .src_access_mask(0)
-            .dst_stage_mask(VK_PIPELINE_STAGE_COLOR_ATTACHMENT_OUTPUT_BIT as u32)
-            .dst_access_mask(VK_ACCESS_COLOR_ATTACHMENT_WRITE_BIT as u32)
+            .dst_stage_mask(VK_PIPELINE_STAGE_COLOR_ATTACHMENT_OUTPUT_BIT)
+            .dst_access_mask(VK_ACCESS_COLOR_ATTACHMENT_WRITE_BIT)
             .build();
 
         let attachments = &[color_attachment_description];
@@ -477,28 +512,28 @@ impl<'a> GraphicsPipeline<'a> {
             .p_dependencies(dependencies.as_ptr())
             .build();
 
-        self.render_pass = self
-            .device
-            .unwrap()
-            .create_render_pass(&render_pass_create_info, None);
+        Ok(device.create_render_pass_owned(&render_pass_create_info, None)?)
     }
 
-    fn create_pipeline_layout(&mut self, set_layouts: &[VkDescriptorSetLayout]) {
+    fn create_pipeline_layout(
+        device: &'a VkDevice,
+        set_layouts: &[VkDescriptorSetLayout],
+    ) -> Result<Owned<'a, VkPipelineLayout>> {
         let pipeline_layout_create_info = VkPipelineLayoutCreateInfoBuilder::new()
             .set_layout_count(set_layouts.len() as u32)
             .p_set_layouts(set_layouts.as_ptr())
             .build();
-        self.pipeline_layout = self
-            .device
-            .unwrap()
-            .create_pipeline_layout(&pipeline_layout_create_info, None);
+        Ok(device.create_pipeline_layout_owned(&pipeline_layout_create_info, None)?)
     }
 
     fn create_pipeline(
-        &mut self,
+        device: &'a VkDevice,
+        render_pass: VkRenderPass,
+        pipeline_layout: VkPipelineLayout,
+        pipeline_cache: VkPipelineCache,
         shader_stages: &ShaderModules,
         properties: &GraphicsPipelineProperties,
-    ) {
+    ) -> Result<Owned<'a, VkPipeline>> {
         let pipeline_create_info = VkGraphicsPipelineCreateInfoBuilder::new()
             .stage_count(shader_stages.len() as u32)
             .p_stages(shader_stages.create_infos_ptr())
@@ -508,35 +543,20 @@ impl<'a> GraphicsPipeline<'a> {
             .p_rasterization_state(&properties.rasterization_state)
             .p_multisample_state(&properties.multisample_state)
             .p_color_blend_state(&properties.color_blend_state)
-            .layout(self.pipeline_layout)
-            .render_pass(self.render_pass)
+            .layout(pipeline_layout)
+            .render_pass(render_pass)
             .subpass(0)
             .build();
 
-        let pipeline_cache_create_info = VkPipelineCacheCreateInfoBuilder::new().build();
-        let pipeline_cache = self
-            .device
-            .unwrap()
-            .create_pipeline_cache(&pipeline_cache_create_info, None);
-
-        self.pipeline = self.device.unwrap().create_graphics_pipelines(
+        let mut pipelines = device.create_graphics_pipelines_owned(
             pipeline_cache,
             &[pipeline_create_info],
             None,
-        )[0];
+        )?;
+        Ok(pipelines.remove(0))
     }
 }
 
-// impl Drop for App {
-//     fn drop(&mut self) {
-//         // presentation.drop();
-
-//         self.framebuffers
-//             .iter()
-//             .for_each(|buf| self.device.destroy_framebuffer(*buf, None));
-//     }
-// }
-
 const VERT_SPV: &[u8] = include_bytes!("./shader/vertex.spv");
 const FRAG_SPV: &[u8] = include_bytes!("./shader/fragment.spv");
 
@@ -588,7 +608,6 @@ lazy_static! {
 }
 
 // use shader::descriptor;
-use shader;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
@@ -602,38 +621,52 @@ pub struct UniformBufferObject {
 #[shader::uniform_buffer(set = 0, binding = 0)]
 pub struct TempObject {}
 
+// the fields are dropped in this order, what uses an object goes before it
 struct App<'a> {
     start: std::time::Instant,
 
-    presentation: Presentation<'a>,
-    shader_stages: ShaderModules<'a>,
-    graphics_pipeline_properties: GraphicsPipelineProperties,
-    graphics_pipeline: GraphicsPipeline<'a>,
-    framebuffers: Vec<VkFramebuffer>,
     command_buffers: Vec<VkCommandBuffer>,
-    image_available_semaphores: Vec<VkSemaphore>,
-    render_finished_semaphores: Vec<VkSemaphore>,
-    in_flight_fences: Vec<VkFence>,
+    framebuffers: Vec<Owned<'a, VkFramebuffer>>,
+    image_available_semaphores: Vec<Owned<'a, VkSemaphore>>,
+    render_finished_semaphores: Vec<Owned<'a, VkSemaphore>>,
+    in_flight_fences: Vec<Owned<'a, VkFence>>,
+    // the fences of `in_flight_fences` again
     images_in_flight: Vec<VkFence>,
     frame: usize,
     resized: bool,
 
-    resource_binding: ResourceBinding<'a>,
-    uniform_buffer: Buffer<'a, UniformBufferObject>,
+    graphics_pipeline: GraphicsPipeline<'a>,
+    graphics_pipeline_properties: GraphicsPipelineProperties,
+    shader_stages: ShaderModules<'a>,
+
+    sampler: Option<Owned<'a, VkSampler>>,
+    texture_view: Option<Owned<'a, VkImageView>>,
+    texture: Option<TextureImpl<'a, u8, 2>>,
+
     descriptors: Vec<Descriptor<'a>>,
     vertex_and_index: Vec<(Buffer<'a, Vertex>, Buffer<'a, u16>)>,
+    uniform_buffer: Buffer<'a, UniformBufferObject>,
+    resource_binding: ResourceBinding<'a>,
 
+    presentation: Box<dyn Present + 'a>,
     handler: &'a VulkanHandler,
 }
 
+// the command buffers are the only thing the fields don't free
+impl<'a> Drop for App<'a> {
+    fn drop(&mut self) {
+        let _ = self.handler.device.wait_idle();
+        self.free_command_buffers();
+    }
+}
+
 impl<'a> App<'a> {
-    pub fn new(handler: &'a VulkanHandler, window: &Window) -> App<'a> {
+    pub fn new(handler: &'a VulkanHandler, presentation: Box<dyn Present + 'a>) -> Result<App<'a>> {
         let shader_stages = ShaderModules::new(
             &handler.device,
             &[VERT_SPV, FRAG_SPV],
             &[VK_SHADER_STAGE_VERTEX_BIT, VK_SHADER_STAGE_FRAGMENT_BIT],
-        );
-        let presentation = Presentation::new(&handler.device, &[0], window);
+        )?;
 
         let binding = VkDescriptorSetLayoutBindingBuilder::new()
             .binding(0)
@@ -642,55 +675,47 @@ impl<'a> App<'a> {
             .stage_flags(VK_SHADER_STAGE_VERTEX_BIT as VkShaderStageFlags)
             .build();
 
-        let resource_binding = handler.create_resource_binding(&[binding]);
+        let resource_binding = handler.create_resource_binding(&[binding])?;
 
-        let graphics_pipeline_properties = GraphicsPipelineProperties::new(&presentation);
+        let graphics_pipeline_properties = GraphicsPipelineProperties::new(presentation.as_ref());
         let graphics_pipeline = GraphicsPipeline::new(
             &handler.device,
-            &presentation,
+            presentation.as_ref(),
             &graphics_pipeline_properties,
             &shader_stages,
             &[resource_binding.descriptor_set_layouts],
-        );
+        )?;
 
-        let uniform_buffer: Buffer<UniformBufferObject> = handler
-            .create_buffer(
-                (None, 1),
-                VK_BUFFER_USAGE_UNIFORM_BUFFER_BIT,
-                0,
-                VK_MEMORY_PROPERTY_HOST_COHERENT_BIT | VK_MEMORY_PROPERTY_HOST_VISIBLE_BIT,
-            )
-            .unwrap();
-
-        let object0: Buffer<UniformBufferObject> = handler
-            .create_buffer(
-                (None, 1),
-                VK_BUFFER_USAGE_UNIFORM_BUFFER_BIT,
-                0,
-                VK_MEMORY_PROPERTY_HOST_COHERENT_BIT | VK_MEMORY_PROPERTY_HOST_VISIBLE_BIT,
-            )
-            .unwrap();
-
-        let object1: Buffer<TempObject> = handler
-            .create_buffer(
-                (None, 1),
-                VK_BUFFER_USAGE_UNIFORM_BUFFER_BIT,
-                0,
-                VK_MEMORY_PROPERTY_HOST_COHERENT_BIT | VK_MEMORY_PROPERTY_HOST_VISIBLE_BIT,
-            )
-            .unwrap();
-
-        let mut desc: Vec<Descriptor> = vec![];
-        desc.push(Box::new(object0));
-        desc.push(Box::new(object1));
+        let uniform_buffer: Buffer<UniformBufferObject> = handler.create_buffer(
+            (None, 1),
+            VK_BUFFER_USAGE_UNIFORM_BUFFER_BIT,
+            0,
+            VK_MEMORY_PROPERTY_HOST_COHERENT_BIT | VK_MEMORY_PROPERTY_HOST_VISIBLE_BIT,
+        )?;
+
+        let object0: Buffer<UniformBufferObject> = handler.create_buffer(
+            (None, 1),
+            VK_BUFFER_USAGE_UNIFORM_BUFFER_BIT,
+            0,
+            VK_MEMORY_PROPERTY_HOST_COHERENT_BIT | VK_MEMORY_PROPERTY_HOST_VISIBLE_BIT,
+        )?;
+
+        let object1: Buffer<TempObject> = handler.create_buffer(
+            (None, 1),
+            VK_BUFFER_USAGE_UNIFORM_BUFFER_BIT,
+            0,
+            VK_MEMORY_PROPERTY_HOST_COHERENT_BIT | VK_MEMORY_PROPERTY_HOST_VISIBLE_BIT,
+        )?;
+
+        let desc: Vec<Descriptor> = vec![Box::new(object0), Box::new(object1)];
 
         let mut app = Self {
             start: std::time::Instant::now(),
-            handler: handler,
-            shader_stages: shader_stages,
-            presentation: presentation,
-            graphics_pipeline_properties: graphics_pipeline_properties,
-            graphics_pipeline: graphics_pipeline,
+            handler,
+            shader_stages,
+            presentation,
+            graphics_pipeline_properties,
+            graphics_pipeline,
             framebuffers: vec![],
             command_buffers: vec![],
             image_available_semaphores: vec![],
@@ -700,20 +725,24 @@ impl<'a> App<'a> {
             frame: 0,
             resized: false,
 
-            resource_binding: resource_binding,
+            texture: None,
+            texture_view: None,
+            sampler: None,
+
+            resource_binding,
             vertex_and_index: vec![],
             descriptors: vec![],
-            uniform_buffer: uniform_buffer,
+            uniform_buffer,
         };
 
-        app.create_framebuffers();
-        app.prepare_render_resources();
-        app.create_texture();
+        app.create_framebuffers()?;
+        app.prepare_render_resources()?;
+        app.create_texture()?;
         app.create_and_update_descriptor_set();
-        app.create_command_buffers();
-        app.create_sync_objects();
+        app.create_command_buffers()?;
+        app.create_sync_objects()?;
 
-        app
+        Ok(app)
     }
 
     fn prepare_static_render_resources(&self) {}
@@ -723,54 +752,62 @@ impl<'a> App<'a> {
         ptr: *const T,
         len: usize,
         usage: VkBufferUsageFlagBits,
-    ) -> (Buffer<T>, Buffer<T>) {
-        let staging_buffer = handler.create_transfer_src_buffer(ptr, len).unwrap();
-        staging_buffer.map_to_gpu_and_unmap();
+    ) -> Result<(Buffer<'_, T>, Buffer<'_, T>)> {
+        let staging_buffer = handler.create_transfer_src_buffer(ptr, len)?;
+        staging_buffer.map_to_gpu_and_unmap()?;
 
-        let target_buffer = handler.create_transfer_dst_buffer(len, usage).unwrap();
+        let target_buffer = handler.create_transfer_dst_buffer(len, usage)?;
 
-        (staging_buffer, target_buffer)
+        Ok((staging_buffer, target_buffer))
     }
 
-    fn create_texture(&mut self) {
+    fn create_texture(&mut self) -> Result<()> {
         let texture_builder = self.handler.texture_builder_from_path("400x400.png");
         let texture = texture_builder
             .format(VkFormat::VK_FORMAT_R8G8B8A8_SRGB)
             .usage(VK_IMAGE_USAGE_SAMPLED_BIT | VK_IMAGE_USAGE_TRANSFER_DST_BIT)
             .samples(VK_SAMPLE_COUNT_1_BIT)
-            .build();
+            .build()?;
 
-        let command_pool = self.handler.get_command_pool(0);
-        texture.cmd_copy_buffer_to_image(command_pool);
+        let command_pool = self.handler.command_pool(QueueType::graphics).unwrap();
+        texture.cmd_copy_buffer_to_image(command_pool)?;
 
-        let image_view = texture.make_view();
+        let image_view = texture.make_view()?;
 
         let sampler_info = VkSamplerCreateInfoBuilder::new()
             .mag_filter(VkFilter::VK_FILTER_LINEAR)
             .min_filter(VkFilter::VK_FILTER_LINEAR)
             .build();
 
-        let sampler = self.handler.device.create_sampler(&sampler_info, None);
+        let sampler = self
+            .handler
+            .device
+            .create_sampler_owned(&sampler_info, None)?;
+
+        self.sampler = Some(sampler);
+        self.texture_view = Some(image_view);
+        self.texture = Some(texture);
+        Ok(())
     }
 
-    fn prepare_render_resources(&mut self) {
+    fn prepare_render_resources(&mut self) -> Result<()> {
         let (stg_vert, trg_vert) = Self::create_render_buffer(
-            &self.handler,
+            self.handler,
             VERTICES.as_ptr(),
             VERTICES.len(),
             VK_BUFFER_USAGE_VERTEX_BUFFER_BIT,
-        );
+        )?;
 
         let (stg_indx, trg_indx) = Self::create_render_buffer(
-            &self.handler,
+            self.handler,
             INDICES.as_ptr(),
             INDICES.len(),
             VK_BUFFER_USAGE_INDEX_BUFFER_BIT,
-        );
+        )?;
 
         let cmds = self
             .handler
-            .allocate_command_buffers(0, VkCommandBufferLevel(0), 2);
+            .allocate_command_buffers(0, VkCommandBufferLevel(0), 2)?;
 
         let copy_cmd = |cmd: VkCommandBuffer, size: VkDeviceSize, stg: VkBuffer, trg: VkBuffer| {
             vkCmdBlock! {
@@ -778,7 +815,7 @@ impl<'a> App<'a> {
 
                 let copy_info = VkBufferCopy {
                     dstOffset: 0,
-                    size: size,
+                    size,
                     srcOffset: 0,
                 };
 
@@ -796,79 +833,70 @@ impl<'a> App<'a> {
             stg_vert.vksize(),
             stg_vert.into_raw_vk(),
             trg_vert.into_raw_vk(),
-        );
+        )?;
         copy_cmd(
             cmds[1],
             stg_indx.vksize(),
             stg_indx.into_raw_vk(),
             trg_indx.into_raw_vk(),
-        );
+        )?;
 
         let submit_info = util::submit_info(&[], &[], &cmds, &[]);
 
-        let queue = self.handler.get_queue(0, 0);
-        queue.submit(&[submit_info], None);
-        queue.wait_idle();
+        let queue = self.handler.queue(QueueType::graphics, 0).unwrap();
+        queue.submit(&[submit_info], None)?;
+        queue.wait_idle()?;
 
         self.vertex_and_index.push((trg_vert, trg_indx));
+        Ok(())
     }
 
-    pub fn render(&mut self, window: &Window) -> Result<()> {
+    // `window` is None for offscreen presentations, which never go out of date
+    pub fn render(&mut self, window: Option<&Window>) -> Result<()> {
         // syn cpu gpu
         let device = &self.handler.device;
-        let in_flight_fence = self.in_flight_fences[self.frame];
+        let in_flight_fence = *self.in_flight_fences[self.frame];
 
-        device.wait_for_fence(&[in_flight_fence], true, u64::MAX);
-        let result = device.acquire_next_image_khr(
-            self.presentation.swapchain,
-            u64::MAX,
-            self.image_available_semaphores[self.frame],
-            std::ptr::null_mut(),
-        );
-
-        let image_index = match result {
-            Ok(image_index) => image_index,
-            Err(VkResult::VK_ERROR_OUT_OF_DATE_KHR) => return self.recreate_presentation(window),
-            Err(e) => return Err(anyhow!("{:?}", e)),
+        device.wait_for_fence(&[in_flight_fence], true, u64::MAX)?;
+        let image_index = match self
+            .presentation
+            .acquire(*self.image_available_semaphores[self.frame])?
+        {
+            Some(image_index) => image_index,
+            None => return self.recreate_presentation(window),
         };
 
         let image_in_flight = self.images_in_flight[image_index as usize];
         // println!("{:?}, {:?}", self.frame, self.images_in_flight[image_index as usize]);
         if !image_in_flight.is_null() {
-            device.wait_for_fence(&[image_in_flight], true, u64::MAX);
+            device.wait_for_fence(&[image_in_flight], true, u64::MAX)?;
         }
         self.images_in_flight[image_index as usize] = in_flight_fence;
 
-        self.update_uniform_buffers();
+        self.update_uniform_buffers()?;
 
-        let signal_semaphores = &[self.image_available_semaphores[self.frame]];
+        let signal_semaphores = &[*self.image_available_semaphores[self.frame]];
 
         let submit_info = util::submit_info(
-            &[self.image_available_semaphores[self.frame]],
-            &[VK_PIPELINE_STAGE_COLOR_ATTACHMENT_OUTPUT_BIT as u32],
+            &[*self.image_available_semaphores[self.frame]],
+            &[VK_PIPELINE_STAGE_COLOR_ATTACHMENT_OUTPUT_BIT],
             &[self.command_buffers[image_index as usize]],
-            &[self.image_available_semaphores[self.frame]],
+            &[*self.image_available_semaphores[self.frame]],
         );
-        device.reset_fence(self.in_flight_fences.as_slice());
+        let in_flight_fences: Vec<VkFence> =
+            self.in_flight_fences.iter().map(|fence| **fence).collect();
+        device.reset_fence(&in_flight_fences)?;
 
-        let queue = self.handler.get_queue(0, 0);
-        queue.submit(&[submit_info], Some(self.in_flight_fences[self.frame]));
+        let queue = self.handler.queue(QueueType::graphics, 0).unwrap();
+        queue.submit(&[submit_info], Some(in_flight_fence))?;
 
         // presenting queue
-        let present_info = VkPresentInfoKHRBuilder::new()
-            .wait_semaphore_count(signal_semaphores.len() as u32)
-            .p_wait_semaphores(signal_semaphores.as_ptr())
-            .swapchain_count(1)
-            .p_swapchains(&self.presentation.swapchain)
-            .p_image_indices(&image_index)
-            .build();
-        let result = queue.present_khr(0, &present_info);
-
-        let changed =
-            result == VkResult::VK_SUBOPTIMAL_KHR || result == VkResult::VK_ERROR_OUT_OF_DATE_KHR;
+        let changed = self
+            .presentation
+            .present(queue, signal_semaphores, image_index)?;
 
         if changed {
-            self.recreate_presentation(window);
+            self.recreate_presentation(window)?;
         } else {
             return Ok(());
         }
@@ -899,7 +927,7 @@ impl<'a> App<'a> {
         self.resource_binding.update(&[ubo_write]);
     }
 
-    fn update_uniform_buffers(&mut self) {
+    fn update_uniform_buffers(&mut self) -> Result<()> {
         let time = self.start.elapsed().as_secs_f32();
 
         let model = glm::rotate(
@@ -915,7 +943,7 @@ impl<'a> App<'a> {
         );
 
         let mut proj = glm::perspective(
-            self.presentation.extent.width as f32 / self.presentation.extent.height as f32,
+            self.presentation.extent().width as f32 / self.presentation.extent().height as f32,
             glm::radians(&glm::vec1(45.0))[0],
             0.1,
             10.0,
@@ -924,158 +952,197 @@ impl<'a> App<'a> {
         proj[(1, 1)] *= -1.0;
 
         let ubo = UniformBufferObject { model, view, proj };
-        self.uniform_buffer.map(1, &ubo);
+        self.uniform_buffer.map(1, &ubo)?;
+        Ok(())
     }
 
-    fn create_framebuffers(&mut self) {
-        let device = &self.handler.device;
+    fn create_framebuffers(&mut self) -> Result<()> {
+        let handler = self.handler;
+        let device = &handler.device;
 
         self.framebuffers = self
             .presentation
-            .image_views
+            .image_views()
             .iter()
             .map(|image| {
                 let framebuffer_create_info = VkFramebufferCreateInfoBuilder::new()
-                    .render_pass(self.graphics_pipeline.render_pass)
+                    .render_pass(*self.graphics_pipeline.render_pass)
                     .attachment_count(1)
                     .p_attachments(image)
-                    .width(self.presentation.extent.width)
-                    .height(self.presentation.extent.height)
+                    .width(self.presentation.extent().width)
+                    .height(self.presentation.extent().height)
                     .layers(1)
                     .build();
-                device.create_framebuffer(&framebuffer_create_info, None)
+                device.create_framebuffer_owned(&framebuffer_create_info, None)
             })
-            .collect();
+            .collect::<std::result::Result<Vec<_>, VkError>>()?;
+        Ok(())
     }
 
-    fn create_command_buffers(&mut self) {
+    fn create_command_buffers(&mut self) -> Result<()> {
         let command_buffers = self.handler.allocate_command_buffers(
             0,
             VkCommandBufferLevel(0),
             self.framebuffers.len() as u32,
-        );
+        )?;
 
-        command_buffers.iter().enumerate().for_each(|(i, &cmd)| {
+        command_buffers.iter().enumerate().try_for_each(|(i, &cmd)| {
             vkCmdBlock! {
                 THIS cmd;
 
-                let render_area = VkRect2D { offset: VkOffset2D { x: 0, y: 0 }, extent: self.presentation.extent };
+                let render_area = VkRect2D { offset: VkOffset2D { x: 0, y: 0 }, extent: self.presentation.extent() };
                 let color_clear_value = VkClearValue { color: VkClearColorValue { float32:[0.0, 0.0, 1.0, 0.0] } };
                 let clear_values = &[color_clear_value];
 
                 let render_pass_begin_info = VkRenderPassBeginInfoBuilder::new()
-                    .render_pass(self.graphics_pipeline.render_pass)
+                    .render_pass(*self.graphics_pipeline.render_pass)
                     .render_area(render_area)
                     .clear_value_count(clear_values.len() as u32)
                     .p_clear_values(clear_values.as_ptr())
-                    .framebuffer(self.framebuffers[i])
+                    .framebuffer(*self.framebuffers[i])
                     .build();
 
                 BEGIN_RENDER_PASS(&render_pass_begin_info, VkSubpassContents::VK_SUBPASS_CONTENTS_INLINE);
                 BIND_PIPELINE(
-                    VkPipelineBindPoint::VK_PIPELINE_BIND_POINT_GRAPHICS, self.graphics_pipeline.pipeline
+                    VkPipelineBindPoint::VK_PIPELINE_BIND_POINT_GRAPHICS, *self.graphics_pipeline.pipeline
                 );
-                BIND_DESCRIPTOR_SETS(VkPipelineBindPoint::VK_PIPELINE_BIND_POINT_GRAPHICS, self.graphics_pipeline.pipeline_layout, 0, 1, self.resource_binding.descriptor_sets.as_ptr(), 0, &0);
-                BIND_VERTEX_BUFFERS(0, 1, &self.vertex_and_index[0].0.into_raw_vk(), (&[0]).as_ptr());
+                BIND_DESCRIPTOR_SETS(VkPipelineBindPoint::VK_PIPELINE_BIND_POINT_GRAPHICS, *self.graphics_pipeline.pipeline_layout, 0, 1, self.resource_binding.descriptor_sets.as_ptr(), 0, &0);
+                BIND_VERTEX_BUFFERS(0, 1, &self.vertex_and_index[0].0.into_raw_vk(), [0].as_ptr());
                 BIND_INDEX_BUFFER(self.vertex_and_index[0].1.into_raw_vk(), 0, VkIndexType::VK_INDEX_TYPE_UINT16);
                 DRAW_INDEXED(INDICES.len() as u32, 1, 0, 0, 0);
                 END_RENDER_PASS();
-            };
-        });
+            }
+        })?;
 
         self.command_buffers = command_buffers;
+        Ok(())
     }
 
-    fn create_sync_objects(&mut self) {
-        let device = &self.handler.device;
+    fn create_sync_objects(&mut self) -> Result<()> {
+        let handler = self.handler;
+        let device = &handler.device;
 
         let semaphore_create_info = VkSemaphoreCreateInfoBuilder::new().build();
         let fence_create_info = VkFenceCreateInfoBuilder::new()
-            .flags(VK_FENCE_CREATE_SIGNALED_BIT as u32)
+            .flags(VK_FENCE_CREATE_SIGNALED_BIT)
             .build();
         self.image_available_semaphores = vec![];
         self.render_finished_semaphores = vec![];
         self.in_flight_fences = vec![];
         for _ in 0..2 {
             self.image_available_semaphores
-                .push(device.create_semaphore(&semaphore_create_info, None));
+                .push(device.create_semaphore_owned(&semaphore_create_info, None)?);
             self.render_finished_semaphores
-                .push(device.create_semaphore(&semaphore_create_info, None));
+                .push(device.create_semaphore_owned(&semaphore_create_info, None)?);
             self.in_flight_fences
-                .push(device.create_fence(&fence_create_info, None));
+                .push(device.create_fence_owned(&fence_create_info, None)?);
         }
 
         self.images_in_flight = self
             .presentation
-            .images
+            .images()
             .iter()
             .map(|_| std::ptr::null_mut())
             .collect();
+        Ok(())
     }
 
-    fn recreate_presentation(&mut self, window: &Window) -> Result<()> {
-        let device = &self.handler.device;
+    fn recreate_presentation(&mut self, window: Option<&Window>) -> Result<()> {
+        let handler = self.handler;
+        let device = &handler.device;
+        let window = match window {
+            Some(window) => window,
+            None => return Ok(()),
+        };
 
-        device.wait_idle();
+        device.wait_idle()?;
+        self.destroy_sync_objects();
+        self.free_command_buffers();
+        self.framebuffers.clear();
+        // the old surface has to go before the window gets a new one
         self.presentation.destroy();
-        self.graphics_pipeline.destroy();
 
-        self.presentation = Presentation::new(&device, &[0], window);
-        self.graphics_pipeline_properties = GraphicsPipelineProperties::new(&self.presentation);
+        self.presentation = Box::new(Presentation::new(
+            &handler.context,
+            handler.physical_device,
+            device,
+            &handler.get_queue_familly_indices(&QueueType::graphics),
+            window,
+        )?);
+        self.graphics_pipeline_properties =
+            GraphicsPipelineProperties::new(self.presentation.as_ref());
         self.graphics_pipeline = GraphicsPipeline::new(
-            &device,
-            &self.presentation,
+            device,
+            self.presentation.as_ref(),
             &self.graphics_pipeline_properties,
             &self.shader_stages,
             &[self.resource_binding.descriptor_set_layouts],
-        );
+        )?;
 
-        self.create_framebuffers();
-        self.create_command_buffers();
-        self.create_sync_objects();
+        self.create_framebuffers()?;
+        self.create_command_buffers()?;
+        self.create_sync_objects()?;
 
         self.images_in_flight
-            .resize(self.presentation.images.len(), std::ptr::null_mut());
+            .resize(self.presentation.images().len(), std::ptr::null_mut());
 
         Ok(())
     }
 
     fn destroy_sync_objects(&mut self) {
-        let device = &self.handler.device;
-
-        self.in_flight_fences.iter().for_each(|fence| {
-            device.destroy_fence(*fence, None);
-        });
-        self.images_in_flight.iter().for_each(|fence| {
-            device.destroy_fence(*fence, None);
-        });
-        self.image_available_semaphores
-            .iter()
-            .for_each(|semaphore| {
-                device.destroy_semaphore(*semaphore, None);
-            });
-        self.render_finished_semaphores
-            .iter()
-            .for_each(|semaphore| {
-                device.destroy_semaphore(*semaphore, None);
-            });
+        self.images_in_flight.clear();
+        self.in_flight_fences.clear();
+        self.image_available_semaphores.clear();
+        self.render_finished_semaphores.clear();
     }
 
-    pub fn destroy(&mut self) {
-        self.handler.device.wait_idle();
-
-        self.presentation.destroy();
+    fn free_command_buffers(&mut self) {
+        if !self.command_buffers.is_empty() {
+            self.handler.free_commands_buffers(
+                self.handler.get_command_pool(0),
+                self.command_buffers.len() as u32,
+                self.command_buffers.as_ptr(),
+            );
+        }
+        self.command_buffers.clear();
+    }
+}
 
-        // self.shader_stages.destroy();
-        // self.graphics_pipeline.destroy();
-        // self.destroy_sync_objects();
-        // self.device.destroy();
+// renders `frames` frames without a window into `output_dir/frame_NNNN.png`
+fn run_headless(frames: usize, output_dir: &str) -> Result<()> {
+    let context = std::sync::Arc::new(Context::headless()?);
+    let handler = VulkanHandler::with_context(context, &[(QueueType::graphics, &[1.0, 1.0])])?;
+    let extent = VkExtent2D {
+        width: 1024,
+        height: 768,
+    };
+    let presentation = OffscreenPresentation::new(
+        handler.physical_device,
+        &handler.device,
+        handler.get_queue_familly_indices(&QueueType::graphics)[0],
+        extent,
+        2,
+    )?
+    .output_dir(output_dir);
+
+    let mut app = App::new(&handler, Box::new(presentation))?;
+    for _ in 0..frames {
+        app.render(None)?;
     }
+    Ok(())
 }
 
 fn main() {
     println!("Hello, world!");
 
+    // vrx-graphics --headless [frames] [output dir]
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("--headless") {
+        let frames = args.get(2).and_then(|n| n.parse().ok()).unwrap_or(1);
+        let output_dir = args.get(3).map(String::as_str).unwrap_or("frames");
+        return run_headless(frames, output_dir).unwrap();
+    }
+
     let mut event_loop = EventLoop::new();
     let window = WindowBuilder::new()
         .with_title("Hello triangles")
@@ -1083,8 +1150,20 @@ fn main() {
         .build(&event_loop)
         .unwrap();
 
-    let handler = VulkanHandler::new(&[(QueueType::graphics, &[1.0, 1.0])]);
-    let mut app = App::new(&handler, &window);
+    let handler = DeviceBuilder::new(vulkan_context())
+        .queue(QueueType::graphics, &[1.0, 1.0])
+        .extension("VK_KHR_swapchain")
+        .build()
+        .unwrap();
+    let presentation = Presentation::new(
+        &handler.context,
+        handler.physical_device,
+        &handler.device,
+        &handler.get_queue_familly_indices(&QueueType::graphics),
+        &window,
+    )
+    .unwrap();
+    let mut app = App::new(&handler, Box::new(presentation)).unwrap();
 
     // non static event loop
     let mut destroying = false;
@@ -1095,7 +1174,7 @@ fn main() {
         control_flow.set_poll();
         match event {
             // Render a frame if our Vulkan app is not being destroyed.
-            Event::MainEventsCleared => { app.render(&window) }.unwrap(),
+            Event::MainEventsCleared => { app.render(Some(&window)) }.unwrap(),
             // resize
             Event::WindowEvent {
                 event: WindowEvent::Resized(size),
@@ -1114,11 +1193,9 @@ fn main() {
                 event: WindowEvent::CloseRequested,
                 ..
             } => {
+                // `app` goes with the closure once the loop returns
                 destroying = true;
                 *control_flow = ControlFlow::Exit;
-                unsafe {
-                    app.destroy();
-                }
             }
             _ => {}
         }
@@ -0,0 +1,35 @@
+//
+// `vrx-graphics --headless` end to end, on lavapipe or any driver with VK_EXT_headless_surface
+//
+// VK_DRIVER_FILES=/usr/share/vulkan/icd.d/lvp_icd.x86_64.json cargo test -p vrx-graphics -- --ignored
+//
+use std::fs::File;
+use std::process::Command;
+
+#[test]
+#[ignore = "needs a vulkan driver, set VK_DRIVER_FILES to the lavapipe ICD manifest"]
+fn headless_loop_writes_every_frame() {
+    if std::env::var_os("VK_DRIVER_FILES").is_none() {
+        eprintln!("VK_DRIVER_FILES is not set, skipping");
+        return;
+    }
+
+    let output_dir = std::env::temp_dir().join(format!("vrx-headless-{}", std::process::id()));
+    let status = Command::new(env!("CARGO_BIN_EXE_vrx-graphics"))
+        .args(["--headless", "3"])
+        .arg(&output_dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    for frame in 0..3 {
+        let path = output_dir.join(format!("frame_{:04}.png", frame));
+        let reader = png::Decoder::new(File::open(&path).unwrap())
+            .read_info()
+            .unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (1024, 768));
+    }
+    assert!(!output_dir.join("frame_0003.png").exists());
+
+    std::fs::remove_dir_all(output_dir).unwrap();
+}
@@ -0,0 +1,38 @@
+//
+// input of `--features regenerate-bindings`
+//
+// window system types are declared opaque here instead of pulling in
+// windows.h / Xlib.h / xcb.h / wayland-client.h, so the generated bindings
+// are the same on every host
+//
+#include <stdint.h>
+#include <stddef.h>
+
+// win32
+typedef struct HINSTANCE__ *HINSTANCE;
+typedef struct HWND__ *HWND;
+typedef struct HMONITOR__ *HMONITOR;
+typedef void *HANDLE;
+typedef unsigned long DWORD;
+typedef const unsigned short *LPCWSTR;
+typedef struct _SECURITY_ATTRIBUTES SECURITY_ATTRIBUTES;
+
+// xlib
+typedef struct _XDisplay Display;
+typedef unsigned long Window;
+typedef unsigned long VisualID;
+
+// xcb
+typedef struct xcb_connection_t xcb_connection_t;
+typedef uint32_t xcb_window_t;
+typedef uint32_t xcb_visualid_t;
+
+// wayland
+struct wl_display;
+struct wl_surface;
+
+#include <vulkan/vulkan_core.h>
+#include <vulkan/vulkan_win32.h>
+#include <vulkan/vulkan_xlib.h>
+#include <vulkan/vulkan_xcb.h>
+#include <vulkan/vulkan_wayland.h>
@@ -0,0 +1,271 @@
+//
+// builder generator
+//
+// reads the Khronos registry and writes one `impl_builder_for_vk_*` invocation
+// per struct of the vendored headers, with the sType constant taken from the
+// `values` attribute of the sType member and the valid pNext bases from `structextends`
+//
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use roxmltree::{Document, Node};
+
+pub const REGISTRY: &str = "registry/vk.xml";
+
+// window systems declared in bindings/wrapper.h
+const PLATFORMS: &[&str] = &["win32", "xlib", "xcb", "wayland"];
+
+// bindgen appends `_` to rust keywords
+const KEYWORDS: &[&str] = &[
+    "type", "ref", "mod", "fn", "impl", "in", "match", "loop", "move",
+];
+
+pub fn generate(out_path: &Path) {
+    println!("cargo:rerun-if-changed={}", REGISTRY);
+
+    let xml = fs::read_to_string(REGISTRY).unwrap_or_else(|_| {
+        panic!(
+            "[vrx] {} is missing, it must match the vendored bindings (Vulkan-Headers 1.3.{})",
+            REGISTRY,
+            super::VK_HEADER_VERSION
+        )
+    });
+    let registry = Document::parse(&xml).expect("[vrx] Couldn't parse vk.xml");
+
+    let out_dir = out_path.parent().unwrap();
+    crate::flags::generate(&registry, &out_dir.join("vk_flags.rs"));
+
+    let typed = crate::flags::typed_flags(&registry);
+    crate::commands::generate(&registry, &typed, &out_dir.join("vk_commands.rs"));
+    fs::write(out_path, builders(&registry, &typed)).expect("Couldn't write builders!");
+}
+
+pub fn builders(registry: &Document, typed: &HashMap<&str, String>) -> String {
+    let required = required_types(registry);
+    let mut emitted = HashSet::new();
+    let mut out =
+        String::from("// generated by build/builders.rs from registry/vk.xml, do not edit\n");
+
+    let structs: Vec<Node> = registry
+        .descendants()
+        .filter(|n| n.has_tag_name("type") && n.attribute("category") == Some("struct"))
+        .filter(|n| for_vulkan(n.attribute("api")))
+        .collect();
+
+    // structs whose builders take a lifetime and can be extended through pNext
+    let chainable: HashSet<&str> = structs
+        .iter()
+        .filter(|n| n.attribute("alias").is_none() && s_type(**n).is_some())
+        .filter_map(|n| n.attribute("name"))
+        .filter(|name| required.contains(name))
+        .collect();
+
+    for node in structs {
+        let name = match node.attribute("name") {
+            Some(name) if required.contains(name) => name,
+            _ => continue,
+        };
+
+        if let Some(alias) = node.attribute("alias") {
+            if chainable.contains(alias) {
+                writeln!(
+                    out,
+                    "\npub type {}Builder<'a> = {}Builder<'a>;",
+                    name, alias
+                )
+                .unwrap();
+            } else if required.contains(alias) {
+                writeln!(out, "\npub type {}Builder = {}Builder;", name, alias).unwrap();
+            }
+            continue;
+        }
+
+        if !emitted.insert(name) {
+            continue;
+        }
+
+        let members = members(node, typed);
+        let s_type = s_type(node);
+
+        let has_s_type = members.iter().any(|(field, _, _)| field == "sType");
+        let macro_name = match (has_s_type, s_type) {
+            (true, Some(_)) => "impl_builder_for_vk_structure_t",
+            // VkBaseInStructure / VkBaseOutStructure
+            (true, None) => continue,
+            (false, _) => "impl_builder_for_vk_none_structure_t",
+        };
+
+        writeln!(out, "\n{}!(", macro_name).unwrap();
+        if let Some(s_type) = s_type {
+            writeln!(out, "    {},", s_type).unwrap();
+
+            let extends: Vec<&str> = node
+                .attribute("structextends")
+                .map(|bases| bases.split(',').filter(|b| chainable.contains(b)).collect())
+                .unwrap_or_default();
+            if !extends.is_empty() {
+                writeln!(out, "    extends [{}],", extends.join(", ")).unwrap();
+            }
+        }
+        writeln!(out, "    pub struct {} {{", name).unwrap();
+        for (field, ty, flags) in members {
+            match flags {
+                Some(flags) => {
+                    writeln!(out, "        pub {}: {} as {},", field, ty, flags).unwrap()
+                }
+                None => writeln!(out, "        pub {}: {},", field, ty).unwrap(),
+            }
+        }
+        writeln!(out, "    }}\n);").unwrap();
+    }
+
+    out
+}
+
+pub fn for_vulkan(api: Option<&str>) -> bool {
+    api.is_none_or(|api| api.split(',').any(|a| a == "vulkan"))
+}
+
+// core versions and the extensions that end up in the vendored headers
+pub fn included<'a, 'input>(
+    registry: &'a Document<'input>,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    registry.descendants().filter(|n| {
+        (n.has_tag_name("feature") && for_vulkan(n.attribute("api")))
+            || (n.has_tag_name("extension")
+                && n.attribute("supported")
+                    .is_some_and(|s| s.split(',').any(|s| s == "vulkan"))
+                && n.attribute("provisional") != Some("true")
+                && n.attribute("platform")
+                    .is_none_or(|p| PLATFORMS.contains(&p)))
+    })
+}
+
+// types declared by the included versions and extensions
+pub fn required_types<'a>(registry: &'a Document) -> HashSet<&'a str> {
+    included(registry)
+        .flat_map(|n| n.children().filter(|r| r.has_tag_name("require")))
+        .filter(|r| for_vulkan(r.attribute("api")))
+        .flat_map(|r| r.children().filter(|t| t.has_tag_name("type")))
+        .filter_map(|t| t.attribute("name"))
+        .collect()
+}
+
+fn s_type<'a>(node: Node<'a, '_>) -> Option<&'a str> {
+    node.children()
+        .filter(|m| m.has_tag_name("member") && member_name(*m) == Some("sType"))
+        .find_map(|m| m.attribute("values"))
+}
+
+fn member_name<'a>(member: Node<'a, '_>) -> Option<&'a str> {
+    member
+        .children()
+        .find(|c| c.has_tag_name("name"))
+        .and_then(|c| c.text())
+}
+
+fn members(node: Node, typed: &HashMap<&str, String>) -> Vec<(String, String, Option<String>)> {
+    node.children()
+        .filter(|m| m.has_tag_name("member") && for_vulkan(m.attribute("api")))
+        .filter_map(|m| member(m, typed))
+        .collect()
+}
+
+// translates a C member declaration into the field name and type bindgen generates,
+// plain flag members also get the `vk_flags!` type their setter accepts
+fn member(node: Node, typed: &HashMap<&str, String>) -> Option<(String, String, Option<String>)> {
+    let mut prefix = String::new();
+    let mut base = None;
+    let mut declarator = String::new();
+    let mut name = None;
+    let mut dims = String::new();
+
+    for child in node.children() {
+        match child.tag_name().name() {
+            "type" => base = child.text(),
+            "name" => name = child.text(),
+            "enum" => dims.push_str(&format!("{} as usize", child.text()?)),
+            "comment" => {}
+            _ if child.is_text() => {
+                let text = child.text().unwrap_or("");
+                if name.is_some() {
+                    dims.push_str(text);
+                } else if base.is_some() {
+                    declarator.push_str(text);
+                } else {
+                    prefix.push_str(text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // bitfields are packed into `_bitfield_N` by bindgen
+    if dims.contains(':') {
+        return None;
+    }
+
+    let base = base?;
+    let flags = match declarator.contains('*') || !dims.trim().is_empty() {
+        true => None,
+        false => typed.get(base).cloned(),
+    };
+
+    let mut ty = rust_type(base);
+
+    // `const T* const* p` -> `*const *const T`
+    let mut pointee_const = prefix.contains("const");
+    for token in declarator.replace('*', " * ").split_whitespace() {
+        match token {
+            "*" => {
+                ty = format!("{} {}", if pointee_const { "*const" } else { "*mut" }, ty);
+                pointee_const = false;
+            }
+            "const" => pointee_const = true,
+            _ => {}
+        }
+    }
+
+    // `float m[3][4]` -> `[[f32; 4]; 3]`
+    let dims: Vec<String> = dims
+        .split(['[', ']'])
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(String::from)
+        .collect();
+    for dim in dims.iter().rev() {
+        ty = format!("[{}; {}]", ty, dim);
+    }
+
+    let name = name?;
+    let field = if KEYWORDS.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    };
+
+    Some((field, ty, flags))
+}
+
+fn rust_type(c: &str) -> String {
+    match c {
+        "void" => "::std::os::raw::c_void",
+        "char" => "::std::os::raw::c_char",
+        "int" => "::std::os::raw::c_int",
+        "float" => "f32",
+        "double" => "f64",
+        "uint8_t" => "u8",
+        "int8_t" => "i8",
+        "uint16_t" => "u16",
+        "int16_t" => "i16",
+        "uint32_t" => "u32",
+        "int32_t" => "i32",
+        "uint64_t" => "u64",
+        "int64_t" => "i64",
+        "size_t" => "usize",
+        other => other,
+    }
+    .to_string()
+}
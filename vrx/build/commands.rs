@@ -0,0 +1,162 @@
+//
+// command generator
+//
+// reads the Khronos registry and writes one `vkCmdCall!` arm per core vkCmd*,
+// `vkCmdCopyImage` -> `COPY_IMAGE(src_image, src_image_layout, ...)` with the parameters after the command buffer.
+// flags parameters go through their typed flags, so raw and typed values both work.
+// `table` writes the same commands for the checks of vkCmdBlock!, shader/build.rs includes this file
+//
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use roxmltree::{Document, Node};
+
+use crate::builders::for_vulkan;
+use crate::flags::screaming;
+
+// every version with its vkCmd* in the DeviceTable of src/loader.rs
+const FEATURES: &[&str] = &[
+    "VK_VERSION_1_0",
+    "VK_VERSION_1_1",
+    "VK_VERSION_1_2",
+    "VK_VERSION_1_3",
+];
+
+pub fn generate(registry: &Document, typed: &HashMap<&str, String>, out_path: &Path) {
+    fs::write(out_path, commands(registry, typed)).expect("Couldn't write commands!");
+}
+
+pub fn commands(registry: &Document, typed: &HashMap<&str, String>) -> String {
+    let mut out =
+        String::from("// generated by build/commands.rs from registry/vk.xml, do not edit\n");
+    out.push_str("\n/// `vkCmdBlock!` commands, one arm per core vkCmd*\n");
+    out.push_str("#[doc(hidden)]\n#[macro_export]\nmacro_rules! vkCmdCall {\n");
+
+    for (name, command) in core_commands(registry) {
+        let params = params(command, typed);
+        let matchers: Vec<String> = params
+            .iter()
+            .map(|(arg, _)| format!("${}:expr", arg))
+            .collect();
+        let values: String = params
+            .iter()
+            .map(|(_, value)| format!(", {}", value))
+            .collect();
+
+        writeln!(
+            out,
+            "    ($cmd:expr, {}({})) => {{\n        {}($cmd{});\n    }};",
+            screaming(name.trim_start_matches("vkCmd")),
+            matchers.join(", "),
+            name,
+            values
+        )
+        .unwrap();
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// `COMMANDS` of vkCmdBlock!, (name, parameters after the command buffer, renderpass)
+/// with the `renderpass` attribute of the registry: "inside", "outside" or "both"
+// called by shader/build.rs only
+#[allow(dead_code)]
+pub fn table(registry: &Document) -> String {
+    let mut out =
+        String::from("// generated by build/commands.rs from registry/vk.xml, do not edit\n\n");
+    out.push_str("pub const COMMANDS: &[(&str, &[&str], &str)] = &[\n");
+
+    for (name, command) in core_commands(registry) {
+        let params: Vec<String> = params(command, &HashMap::new())
+            .into_iter()
+            .map(|(arg, _)| format!("{:?}", arg))
+            .collect();
+
+        writeln!(
+            out,
+            "    ({:?}, &[{}], {:?}),",
+            screaming(name.trim_start_matches("vkCmd")),
+            params.join(", "),
+            command.attribute("renderpass").unwrap_or("both")
+        )
+        .unwrap();
+    }
+
+    out.push_str("];\n");
+    out
+}
+
+// (name, definition) of the vkCmd* of `FEATURES`, in registry order
+fn core_commands<'a, 'input>(registry: &'a Document<'input>) -> Vec<(&'a str, Node<'a, 'input>)> {
+    let definitions: HashMap<&str, Node> = registry
+        .descendants()
+        .filter(|n| n.has_tag_name("command") && for_vulkan(n.attribute("api")))
+        .filter_map(|n| Some((proto_name(n)?, n)))
+        .collect();
+
+    required_commands(registry)
+        .into_iter()
+        .filter_map(|name| Some((name, *definitions.get(name)?)))
+        .collect()
+}
+
+// vkCmd* of `FEATURES`, in registry order
+fn required_commands<'a>(registry: &'a Document) -> Vec<&'a str> {
+    registry
+        .descendants()
+        .filter(|n| {
+            n.has_tag_name("feature")
+                && for_vulkan(n.attribute("api"))
+                && n.attribute("name")
+                    .is_some_and(|name| FEATURES.contains(&name))
+        })
+        .flat_map(|n| n.children().filter(|r| r.has_tag_name("require")))
+        .filter(|r| for_vulkan(r.attribute("api")))
+        .flat_map(|r| r.children().filter(|c| c.has_tag_name("command")))
+        .filter_map(|c| c.attribute("name"))
+        .filter(|name| name.starts_with("vkCmd"))
+        .collect()
+}
+
+fn proto_name<'a>(command: Node<'a, '_>) -> Option<&'a str> {
+    command
+        .children()
+        .find(|c| c.has_tag_name("proto"))?
+        .children()
+        .find(|c| c.has_tag_name("name"))?
+        .text()
+}
+
+// (metavariable, value passed on) of every parameter but the command buffer
+fn params(command: Node, typed: &HashMap<&str, String>) -> Vec<(String, String)> {
+    command
+        .children()
+        .filter(|p| p.has_tag_name("param") && for_vulkan(p.attribute("api")))
+        .skip(1)
+        .filter_map(|p| {
+            let name = p.children().find(|c| c.has_tag_name("name"))?.text()?;
+            let ty = p.children().find(|c| c.has_tag_name("type"))?.text()?;
+            let arg = screaming(name).to_lowercase();
+
+            // `const VkBufferCopy* pRegions`, `float blendConstants[4]`
+            let text: String = p
+                .descendants()
+                .filter(|d| d.is_text())
+                .filter_map(|d| d.text())
+                .collect();
+            let indirect = text.contains('*') || text.contains('[');
+
+            let value = match typed.get(ty) {
+                Some(flags) if !indirect && ty.contains("FlagBits") => {
+                    format!("{}::from(${}).bits() as {}", flags, arg, ty)
+                }
+                Some(flags) if !indirect => format!("{}::from(${}).bits()", flags, arg),
+                _ => format!("${}", arg),
+            };
+            Some((arg, value))
+        })
+        .collect()
+}
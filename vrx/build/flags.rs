@@ -0,0 +1,229 @@
+//
+// flags generator
+//
+// every `Vk*FlagBits` enum of the registry becomes a `vk_flags!` type
+// (`VkImageUsageFlagBits` -> `ImageUsageFlags`, `VK_IMAGE_USAGE_STORAGE_BIT` -> `STORAGE`)
+//
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use roxmltree::{Document, Node};
+
+use crate::builders::{for_vulkan, included, required_types};
+
+struct Flags<'a> {
+    name: String,
+    bits: &'a str,
+    wide: bool,
+}
+
+pub fn generate(registry: &Document, out_path: &Path) {
+    fs::write(out_path, flags(registry)).expect("Couldn't write flags!");
+}
+
+/// `VkImageUsageFlags` and `VkImageUsageFlagBits` -> `ImageUsageFlags`
+pub fn typed_flags<'a>(registry: &'a Document) -> HashMap<&'a str, String> {
+    let mut typed = HashMap::new();
+    for flags in flag_types(registry) {
+        typed.insert(flags.bits, flags.name.clone());
+    }
+
+    let required = required_types(registry);
+    for node in bitmasks(registry) {
+        let name = match node
+            .descendants()
+            .find(|n| n.has_tag_name("name"))
+            .and_then(|n| n.text())
+        {
+            Some(name) if required.contains(name) => name,
+            _ => continue,
+        };
+        let bits = node.attribute("requires").or(node.attribute("bitvalues"));
+        if let Some(flags) = bits.and_then(|bits| typed.get(bits).cloned()) {
+            typed.insert(name, flags);
+        }
+    }
+
+    // `VkPipelineStageFlags2KHR` ...
+    for node in registry
+        .descendants()
+        .filter(|n| n.has_tag_name("type") && n.attribute("category") == Some("bitmask"))
+    {
+        if let (Some(name), Some(alias)) = (node.attribute("name"), node.attribute("alias")) {
+            if let Some(flags) = typed.get(alias).cloned() {
+                typed.insert(name, flags);
+            }
+        }
+    }
+
+    typed
+}
+
+fn bitmasks<'a, 'input>(registry: &'a Document<'input>) -> impl Iterator<Item = Node<'a, 'input>> {
+    registry.descendants().filter(|n| {
+        n.has_tag_name("type")
+            && n.attribute("category") == Some("bitmask")
+            && n.attribute("alias").is_none()
+            && for_vulkan(n.attribute("api"))
+    })
+}
+
+fn flag_types<'a>(registry: &'a Document) -> Vec<Flags<'a>> {
+    let required = required_types(registry);
+
+    registry
+        .descendants()
+        .filter(|n| n.has_tag_name("enums") && n.attribute("type") == Some("bitmask"))
+        .filter_map(|n| {
+            n.attribute("name")
+                .map(|name| (name, n.attribute("bitwidth")))
+        })
+        .filter(|(bits, _)| required.contains(bits))
+        .map(|(bits, bitwidth)| Flags {
+            name: bits
+                .trim_start_matches("Vk")
+                .replacen("FlagBits", "Flags", 1),
+            bits,
+            wide: bitwidth == Some("64"),
+        })
+        .collect()
+}
+
+pub fn flags(registry: &Document) -> String {
+    let mut out =
+        String::from("// generated by build/flags.rs from registry/vk.xml, do not edit\n");
+
+    // bits added by the included versions and extensions
+    let mut extended: HashMap<&str, Vec<Node>> = HashMap::new();
+    for node in included(registry)
+        .flat_map(|n| n.children().filter(|r| r.has_tag_name("require")))
+        .filter(|r| for_vulkan(r.attribute("api")))
+        .flat_map(|r| r.children().filter(|e| e.has_tag_name("enum")))
+    {
+        if let Some(bits) = node.attribute("extends") {
+            extended.entry(bits).or_default().push(node);
+        }
+    }
+
+    for flags in flag_types(registry) {
+        let declared = registry
+            .descendants()
+            .find(|n| n.has_tag_name("enums") && n.attribute("name") == Some(flags.bits))
+            .into_iter()
+            .flat_map(|n| n.children().filter(|e| e.has_tag_name("enum")))
+            .filter(|e| for_vulkan(e.attribute("api")));
+        let enums: Vec<Node> = declared
+            .chain(extended.remove(flags.bits).unwrap_or_default())
+            .collect();
+
+        // aliases are resolved to the value of the bit they name
+        let mut values: HashMap<&str, u64> = HashMap::new();
+        for e in &enums {
+            if let (Some(name), Some(value)) = (e.attribute("name"), value(*e)) {
+                values.insert(name, value);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let mut members = vec![];
+        for e in &enums {
+            let name = match e.attribute("name") {
+                Some(name) => name,
+                None => continue,
+            };
+            let value = match value(*e)
+                .or_else(|| e.attribute("alias").and_then(|a| values.get(a).copied()))
+            {
+                Some(value) => value,
+                None => continue,
+            };
+            let flag = flag_name(flags.bits, name);
+            if seen.insert(flag.clone()) {
+                members.push((flag, value));
+            }
+        }
+
+        let raw = if flags.wide { "VkFlags64" } else { "VkFlags" };
+        let from = if flags.wide { "" } else { " from i32" };
+        writeln!(
+            out,
+            "\nvk_flags! {{\n    pub struct {}({}){} {{",
+            flags.name, raw, from
+        )
+        .unwrap();
+        for (flag, value) in members {
+            writeln!(out, "        {} = {:#x},", flag, value).unwrap();
+        }
+        writeln!(out, "    }}\n}}").unwrap();
+    }
+
+    out
+}
+
+fn value(e: Node) -> Option<u64> {
+    if let Some(bitpos) = e.attribute("bitpos") {
+        return bitpos.parse::<u32>().ok().map(|b| 1u64 << b);
+    }
+    let value = e.attribute("value")?;
+    match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex.trim_end_matches(['U', 'L']), 16).ok(),
+        None => value.trim_end_matches(['U', 'L']).parse().ok(),
+    }
+}
+
+// `VkImageUsageFlagBits`, `VK_IMAGE_USAGE_STORAGE_BIT` -> `STORAGE`
+fn flag_name(bits: &str, name: &str) -> String {
+    let (body, rest) = bits
+        .trim_start_matches("Vk")
+        .split_once("FlagBits")
+        .unwrap_or((bits, ""));
+    let digits: String = rest.chars().filter(|c| c.is_ascii_digit()).collect();
+    let tag: String = rest.chars().filter(|c| c.is_ascii_uppercase()).collect();
+
+    let mut prefix = format!("VK_{}_", screaming(body));
+    if !digits.is_empty() {
+        prefix = format!("{}{}_", prefix, digits);
+    }
+
+    let mut flag = name
+        .strip_prefix(&prefix)
+        .or_else(|| name.strip_prefix("VK_"))
+        .unwrap_or(name)
+        .to_string();
+
+    if !tag.is_empty() {
+        if let Some(stripped) = flag.strip_suffix(&format!("_{}", tag)) {
+            flag = stripped.to_string();
+        }
+    }
+
+    if let Some(stripped) = flag.strip_suffix("_BIT") {
+        flag = stripped.to_string();
+    } else if let Some((head, vendor)) = flag.rsplit_once("_BIT_") {
+        if vendor.chars().all(|c| c.is_ascii_uppercase()) {
+            flag = format!("{}_{}", head, vendor);
+        }
+    }
+
+    if flag.starts_with(|c: char| c.is_ascii_digit()) {
+        flag = format!("TYPE_{}", flag);
+    }
+    flag
+}
+
+pub fn screaming(camel: &str) -> String {
+    let mut out = String::new();
+    let mut prev: Option<char> = None;
+    for c in camel.chars() {
+        if c.is_ascii_uppercase()
+            && prev.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit())
+        {
+            out.push('_');
+        }
+        out.push(c.to_ascii_uppercase());
+        prev = Some(c);
+    }
+    out
+}
@@ -0,0 +1,134 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod builders;
+mod commands;
+mod flags;
+
+// Vulkan-Headers release the vendored bindings were generated from
+const VK_HEADER_VERSION: u32 = 251;
+const VENDORED_BINDINGS: &str = "bindings/vk_bindings.rs";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build");
+    println!("cargo:rerun-if-changed={}", VENDORED_BINDINGS);
+
+    // libvulkan is opened at runtime by src/loader.rs,
+    // so nothing is linked and bindgen only emits types and PFN_ pointers
+
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("vk_bindings.rs");
+
+    #[cfg(feature = "regenerate-bindings")]
+    regenerate::generate(&out_path);
+
+    #[cfg(not(feature = "regenerate-bindings"))]
+    {
+        let vendored = Path::new(VENDORED_BINDINGS);
+        if !vendored.exists() {
+            panic!(
+                "[vrx] {} is missing, build once with `--features regenerate-bindings` and commit the output",
+                VENDORED_BINDINGS
+            );
+        }
+        fs::copy(vendored, &out_path).expect("[vrx] Couldn't copy the vendored bindings");
+    }
+
+    // builders for every struct, flags for every FlagBits and the vkCmdBlock! commands of the registry,
+    // see src/vkstruct.rs, src/vkflags.rs and src/lib.rs
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    builders::generate(&out_dir.join("vk_builders.rs"));
+}
+
+#[cfg(feature = "regenerate-bindings")]
+mod regenerate {
+    extern crate bindgen;
+
+    use super::*;
+
+    // headers are taken from VULKAN_HEADERS (a Vulkan-Headers checkout) or VULKAN_SDK
+    fn include_dir() -> PathBuf {
+        println!("cargo:rerun-if-env-changed=VULKAN_HEADERS");
+        println!("cargo:rerun-if-env-changed=VULKAN_SDK");
+
+        if let Ok(headers) = env::var("VULKAN_HEADERS") {
+            return Path::new(&headers).join("include");
+        }
+        let vulkan_sdk = env::var("VULKAN_SDK")
+            .expect("[vrx] regenerate-bindings needs VULKAN_HEADERS or VULKAN_SDK. Please check");
+        Path::new(&vulkan_sdk).join("include")
+    }
+
+    pub fn generate(out_path: &Path) {
+        println!("cargo:rerun-if-changed=bindings/wrapper.h");
+
+        let include_dir = include_dir();
+
+        // the wrapper declares the window system types itself,
+        // so one set of bindings covers every platform
+        let bind_builder = bindgen::Builder::default()
+            .header("bindings/wrapper.h")
+            .clang_arg(format!("-I{}", include_dir.to_str().unwrap()))
+            .prepend_enum_name(false)
+            .derive_default(true)
+            .no_default("[^V]*")
+            .size_t_is_usize(true)
+            .ignore_functions()
+            .layout_tests(false)
+            //
+            // Vulkan C type macro to rust enum
+            //
+            .newtype_enum("VkImageLayout")
+            .newtype_enum("VkResult")
+            .newtype_enum("VkFormat")
+            .newtype_enum("VkImageTiling")
+            .newtype_enum("VkImageType")
+            .newtype_enum("VkPhysicalDeviceType")
+            .newtype_enum("VkQueryType")
+            .newtype_enum("VkSharingMode")
+            .newtype_enum("VkComponentSwizzle")
+            .newtype_enum("VkImageViewType")
+            .newtype_enum("VkBlendFactor")
+            .newtype_enum("VkBlendOp")
+            .newtype_enum("VkCompareOp")
+            .newtype_enum("VkDynamicState")
+            .newtype_enum("VkFrontFace")
+            .newtype_enum("VkVertexInputRate")
+            .newtype_enum("VkPrimitiveTopology")
+            .newtype_enum("VkPolygonMode")
+            .newtype_enum("VkStencilOp")
+            .newtype_enum("VkLogicOp")
+            .newtype_enum("VkBorderColor")
+            .newtype_enum("VkFilter")
+            .newtype_enum("VkSamplerAddressMode")
+            .newtype_enum("VkSamplerMipmapMode")
+            .newtype_enum("VkDescriptorType")
+            .newtype_enum("VkAttachmentLoadOp")
+            .newtype_enum("VkAttachmentStoreOp")
+            .newtype_enum("VkPipelineBindPoint")
+            .newtype_enum("VkCommandBufferLevel")
+            .newtype_enum("VkIndexType")
+            .newtype_enum("VkSubpassContents");
+        // FlagBits stay constified, the typed wrappers are generated into vk_flags.rs
+
+        let bindings = bind_builder
+            .generate()
+            .expect("Unable to generate bindings")
+            .to_string();
+
+        let pinned = format!("pub const VK_HEADER_VERSION: u32 = {};", VK_HEADER_VERSION);
+        if !bindings.contains(&pinned) {
+            println!(
+                "cargo:warning=[vrx] bindings were generated from headers other than 1.3.{}",
+                VK_HEADER_VERSION
+            );
+        }
+
+        fs::write(out_path, bindings).expect("Couldn't write bindings!");
+        println!(
+            "cargo:warning=[vrx] regenerated bindings: {}, copy to {} to update the vendored ones",
+            out_path.display(),
+            VENDORED_BINDINGS
+        );
+    }
+}
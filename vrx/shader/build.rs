@@ -0,0 +1,40 @@
+//
+// command table of vkCmdBlock!
+//
+// the table comes from the generators of vrx (vrx/build), the same code that writes the `vkCmdCall!` arms,
+// `vkCmdBlitImage` -> ("BLIT_IMAGE", &["src_image", "src_image_layout", ...], "outside") without the command buffer
+//
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use roxmltree::Document;
+
+#[allow(dead_code)]
+#[path = "../build/builders.rs"]
+mod builders;
+#[allow(dead_code)]
+#[path = "../build/commands.rs"]
+mod commands;
+#[allow(dead_code)]
+#[path = "../build/flags.rs"]
+mod flags;
+
+const REGISTRY: &str = "../registry/vk.xml";
+
+// read by builders::generate, which only vrx/build runs
+#[allow(dead_code)]
+const VK_HEADER_VERSION: u32 = 251;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=../build");
+    println!("cargo:rerun-if-changed={}", REGISTRY);
+
+    let xml =
+        fs::read_to_string(REGISTRY).unwrap_or_else(|_| panic!("[shader] {} is missing", REGISTRY));
+    let registry = Document::parse(&xml).expect("[shader] Couldn't parse vk.xml");
+
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("commands.rs");
+    fs::write(out_path, commands::table(&registry)).expect("Couldn't write commands!");
+}
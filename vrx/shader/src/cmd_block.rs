@@ -0,0 +1,710 @@
+//
+// vkCmdBlock!
+//
+// the command block DSL of vrx. Misspelled commands, wrong argument counts and commands
+// recorded where vulkan does not allow them are reported at the tokens at fault,
+// the commands themselves expand to the `vkCmdCall!` arms vrx generates from the registry
+//
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{braced, parenthesized, token, Expr, Ident, Lifetime, Pat, Stmt, Token};
+
+// COMMANDS: (name, parameters after the command buffer, renderpass) of every vkCmd* of the block
+include!(concat!(env!("OUT_DIR"), "/commands.rs"));
+
+// commands of VK_EXT_debug_utils, (name, parameters, required count)
+const LABELS: &[(&str, &[&str], usize)] = &[
+    ("BEGIN_LABEL", &["name", "color"], 1),
+    ("END_LABEL", &[], 0),
+    ("INSERT_LABEL", &["name", "color"], 1),
+];
+
+// the former name of PUSH_CONSTANTS
+const ALIASES: &[(&str, &str)] = &[("PUSH_CONSTANT", "PUSH_CONSTANTS")];
+
+const SCOPED: &[&str] = &["RENDER_PASS", "LABEL"];
+
+// commands that begin and end a render pass instance, dynamic rendering included
+const PASSES: &[(&str, &str)] = &[
+    ("BEGIN_RENDER_PASS", "END_RENDER_PASS"),
+    ("BEGIN_RENDER_PASS2", "END_RENDER_PASS2"),
+    ("BEGIN_RENDERING", "END_RENDERING"),
+];
+
+// where the commands are recorded
+#[derive(Clone, Copy)]
+enum Scope {
+    Outside,
+    // begun at `span` by one of PASSES
+    Inside {
+        begin: &'static str,
+        end: &'static str,
+        span: Span,
+    },
+    // ended by someone else, a RENDER_PASS block or the primary of a secondary buffer
+    Pass,
+    // INTO, not checked
+    Any,
+}
+
+// the body the statements are recorded in
+#[derive(Clone, Copy)]
+enum Nesting {
+    Top,
+    // `{ }`, LABEL and RENDER_PASS bodies
+    Block,
+    // bodies of `for` and `if`, also when nested in a block
+    Flow(&'static str),
+}
+
+impl Nesting {
+    fn within(self, inner: Nesting) -> Nesting {
+        match self {
+            Nesting::Flow(_) => self,
+            _ => inner,
+        }
+    }
+}
+
+pub struct Block {
+    header: Header,
+    body: Vec<Statement>,
+}
+
+enum Header {
+    This {
+        cmd: Expr,
+        usage: Vec<Ident>,
+        // SECONDARY, with (render_pass, subpass, framebuffer) when it continues a render pass
+        secondary: Option<Option<Box<[Expr; 3]>>>,
+    },
+    Into {
+        cmd: Expr,
+    },
+}
+
+enum Statement {
+    Command {
+        name: Ident,
+        args: Vec<Expr>,
+        span: Span,
+    },
+    // RENDER_PASS(...) { } and LABEL(...) { }
+    Scoped {
+        name: Ident,
+        args: Vec<Expr>,
+        span: Span,
+        body: Vec<Statement>,
+    },
+    For {
+        pat: Pat,
+        iter: Expr,
+        body: Vec<Statement>,
+    },
+    If {
+        cond: Expr,
+        then: Vec<Statement>,
+        // `else if` is an `If` alone in the vec
+        otherwise: Option<Vec<Statement>>,
+    },
+    Block(Vec<Statement>),
+    Rust(Stmt),
+}
+
+impl Parse for Block {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let header = input.parse()?;
+        let body = parse_body(input)?;
+        Ok(Self { header, body })
+    }
+}
+
+impl Parse for Header {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let keyword: Ident = input.parse()?;
+        let cmd: Expr = input.parse()?;
+
+        if keyword == "INTO" {
+            input.parse::<Token![;]>()?;
+            return Ok(Header::Into { cmd });
+        }
+        if keyword != "THIS" {
+            return Err(syn::Error::new(keyword.span(), "expected THIS or INTO"));
+        }
+
+        let mut usage = Vec::new();
+        let mut secondary = None;
+        while !input.peek(Token![;]) {
+            let clause: Ident = input.parse().map_err(|e| {
+                syn::Error::new(
+                    e.span(),
+                    "expected USAGE, SECONDARY or `;` after the command buffer",
+                )
+            })?;
+
+            if clause == "USAGE" {
+                let flags = Punctuated::<Ident, Token![|]>::parse_separated_nonempty(input)?;
+                usage.extend(flags);
+            } else if clause == "SECONDARY" {
+                secondary = Some(None);
+                if input.peek(token::Paren) {
+                    let content;
+                    let paren = parenthesized!(content in input);
+                    let args: Vec<Expr> =
+                        Punctuated::<Expr, Token![,]>::parse_terminated(&content)?
+                            .into_iter()
+                            .collect();
+                    let args: [Expr; 3] = args.try_into().map_err(|_| {
+                        syn::Error::new(
+                            paren.span.join(),
+                            "SECONDARY expects (render_pass, subpass, framebuffer)",
+                        )
+                    })?;
+                    secondary = Some(Some(Box::new(args)));
+                }
+            } else {
+                return Err(syn::Error::new(
+                    clause.span(),
+                    format!("unknown clause {}, expected USAGE or SECONDARY", clause),
+                ));
+            }
+        }
+        input.parse::<Token![;]>()?;
+
+        Ok(Header::This {
+            cmd,
+            usage,
+            secondary,
+        })
+    }
+}
+
+fn parse_body(input: ParseStream) -> syn::Result<Vec<Statement>> {
+    let mut body = Vec::new();
+    while !input.is_empty() {
+        body.push(parse_statement(input)?);
+    }
+    Ok(body)
+}
+
+fn parse_braced(input: ParseStream) -> syn::Result<Vec<Statement>> {
+    let content;
+    braced!(content in input);
+    parse_body(&content)
+}
+
+fn parse_statement(input: ParseStream) -> syn::Result<Statement> {
+    if input.peek(Token![for]) {
+        input.parse::<Token![for]>()?;
+        let pat = Pat::parse_multi_with_leading_vert(input)?;
+        input.parse::<Token![in]>()?;
+        let iter = Expr::parse_without_eager_brace(input)?;
+        let body = parse_braced(input)?;
+        return Ok(Statement::For { pat, iter, body });
+    }
+
+    if input.peek(Token![if]) {
+        return parse_if(input);
+    }
+
+    if input.peek(token::Brace) {
+        return Ok(Statement::Block(parse_braced(input)?));
+    }
+
+    // commands are SCREAMING_CASE calls, anything else is rust
+    if input.peek(Ident) && input.peek2(token::Paren) {
+        let name: Ident = input.fork().parse()?;
+        if is_command(&name) {
+            input.parse::<Ident>()?;
+            let content;
+            let paren = parenthesized!(content in input);
+            let args = Punctuated::<Expr, Token![,]>::parse_terminated(&content)?
+                .into_iter()
+                .collect();
+            let span = paren.span.join();
+
+            if input.peek(token::Brace) {
+                let body = parse_braced(input)?;
+                return Ok(Statement::Scoped {
+                    name,
+                    args,
+                    span,
+                    body,
+                });
+            }
+            input.parse::<Token![;]>()?;
+            return Ok(Statement::Command { name, args, span });
+        }
+    }
+
+    Ok(Statement::Rust(input.parse()?))
+}
+
+fn parse_if(input: ParseStream) -> syn::Result<Statement> {
+    input.parse::<Token![if]>()?;
+    let cond = Expr::parse_without_eager_brace(input)?;
+    let then = parse_braced(input)?;
+
+    let mut otherwise = None;
+    if input.peek(Token![else]) {
+        input.parse::<Token![else]>()?;
+        otherwise = Some(if input.peek(Token![if]) {
+            vec![parse_if(input)?]
+        } else {
+            parse_braced(input)?
+        });
+    }
+
+    Ok(Statement::If {
+        cond,
+        then,
+        otherwise,
+    })
+}
+
+fn is_command(name: &Ident) -> bool {
+    let name = name.to_string();
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+impl Block {
+    pub fn expand(&self) -> syn::Result<TokenStream> {
+        // locals of the block are not visible to (nor shadowed by) the statements of the caller
+        let command_buffer = Ident::new("command_buffer", Span::mixed_site());
+
+        match &self.header {
+            Header::Into { cmd } => {
+                let body = expand_body(&command_buffer, &self.body, &mut Scope::Any, Nesting::Top)?;
+                Ok(quote! {{
+                    let #command_buffer: VkCommandBuffer = #cmd;
+                    #body
+                }})
+            }
+            Header::This {
+                cmd,
+                usage,
+                secondary,
+            } => {
+                let begin_info = Ident::new("begin_info", Span::mixed_site());
+                let inheritance_info = Ident::new("inheritance_info", Span::mixed_site());
+                let render_pass = Ident::new("render_pass", Span::mixed_site());
+                let flags = Ident::new("usage", Span::mixed_site());
+
+                let usage = if usage.is_empty() {
+                    quote!(CommandBufferUsageFlags::empty())
+                } else {
+                    let flags = usage
+                        .iter()
+                        .map(|flag| quote_spanned!(flag.span()=> CommandBufferUsageFlags::#flag));
+                    quote!(#(#flags)|*)
+                };
+
+                let (begin, mut scope) = match secondary {
+                    None => (
+                        quote! {
+                            let #begin_info = VkCommandBufferBeginInfoBuilder::new()
+                                .flags(#usage)
+                                .build();
+                        },
+                        Scope::Outside,
+                    ),
+                    Some(None) => (
+                        quote! {
+                            let #inheritance_info = VkCommandBufferInheritanceInfoBuilder::new().build();
+                            let #begin_info = VkCommandBufferBeginInfoBuilder::new()
+                                .flags(#usage)
+                                .p_inheritance_info(&#inheritance_info)
+                                .build();
+                        },
+                        Scope::Outside,
+                    ),
+                    Some(Some(inheritance)) => {
+                        let [pass, subpass, framebuffer] = &**inheritance;
+                        (
+                            quote! {
+                                let #render_pass: VkRenderPass = #pass;
+                                let #inheritance_info = VkCommandBufferInheritanceInfoBuilder::new()
+                                    .render_pass(#render_pass)
+                                    .subpass(#subpass)
+                                    .framebuffer(#framebuffer)
+                                    .build();
+
+                                let mut #flags = #usage;
+                                if !#render_pass.is_null() {
+                                    #flags |= CommandBufferUsageFlags::RENDER_PASS_CONTINUE;
+                                }
+                                let #begin_info = VkCommandBufferBeginInfoBuilder::new()
+                                    .flags(#flags)
+                                    .p_inheritance_info(&#inheritance_info)
+                                    .build();
+                            },
+                            Scope::Pass,
+                        )
+                    }
+                };
+
+                let body = expand_body(&command_buffer, &self.body, &mut scope, Nesting::Top)?;
+                let record = Lifetime::new("'record", Span::mixed_site());
+                // evaluates to Result<(), VkError>, a labeled block rather than a closure
+                // so that `return` and `?` in the statements still leave the caller
+                Ok(quote! {{
+                    #begin
+                    let #command_buffer: VkCommandBuffer = #cmd;
+                    #record: {
+                        if let Err(error) = vk_check(unsafe { vkBeginCommandBuffer(#command_buffer, &#begin_info) }) {
+                            break #record Err(error);
+                        }
+
+                        #body
+
+                        vk_check(unsafe { vkEndCommandBuffer(#command_buffer) }).map(|_| ())
+                    }
+                }})
+            }
+        }
+    }
+}
+
+// a render pass begun by a command is ended in the same body, the block itself or a `{ }`,
+// and never inside `for` or `if`
+fn expand_body(
+    cmd: &Ident,
+    body: &[Statement],
+    scope: &mut Scope,
+    nesting: Nesting,
+) -> syn::Result<TokenStream> {
+    let entry = *scope;
+    let mut out = TokenStream::new();
+    for statement in body {
+        out.extend(expand_statement(cmd, statement, scope, nesting)?);
+
+        if let (Scope::Inside { .. }, Scope::Outside, Statement::Command { name, .. }) =
+            (entry, *scope, statement)
+        {
+            return Err(syn::Error::new(
+                name.span(),
+                format!("{} ends a render pass begun outside of this block", name),
+            ));
+        }
+    }
+
+    match (entry, *scope) {
+        (Scope::Inside { .. }, _) => {}
+        (_, Scope::Inside { begin, end, span }) => {
+            let message = match nesting {
+                Nesting::Top => format!("{} without {}", begin, end),
+                _ => format!("{} without {} in this block", begin, end),
+            };
+            return Err(syn::Error::new(span, message));
+        }
+        _ => {}
+    }
+    Ok(out)
+}
+
+fn expand_statement(
+    cmd: &Ident,
+    statement: &Statement,
+    scope: &mut Scope,
+    nesting: Nesting,
+) -> syn::Result<TokenStream> {
+    Ok(match statement {
+        Statement::Command { name, args, span } => {
+            let command = name.to_string();
+            let pass = PASSES
+                .iter()
+                .find(|(begin, end)| *begin == command || *end == command);
+
+            if let (Some(_), Nesting::Flow(keyword)) = (pass, nesting) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!(
+                        "{} inside `{}`, record the render pass with a RENDER_PASS(...) {{ }} block or outside of `{}`",
+                        command, keyword, keyword
+                    ),
+                ));
+            }
+            check_arguments(name, args, *span)?;
+            check_scope(name, *scope)?;
+
+            match (*scope, pass) {
+                (Scope::Outside, Some((begin, end))) if *begin == command => {
+                    *scope = Scope::Inside {
+                        begin,
+                        end,
+                        span: name.span(),
+                    };
+                }
+                (Scope::Inside { end, .. }, _) if end == command => *scope = Scope::Outside,
+                _ => {}
+            }
+            expand_command(cmd, name, args)
+        }
+
+        Statement::Scoped {
+            name,
+            args,
+            span,
+            body,
+        } => {
+            if name == "RENDER_PASS" {
+                check_count(
+                    name,
+                    &["render_pass_begin_info", "contents"],
+                    2,
+                    args.len(),
+                    *span,
+                )?;
+                check_scope(&Ident::new("BEGIN_RENDER_PASS", name.span()), *scope)?;
+
+                let info = &args[0];
+                let begin = call(
+                    cmd,
+                    &Ident::new("BEGIN_RENDER_PASS", name.span()),
+                    &[quote!(#info), subpass_contents(&args[1])],
+                );
+                let end = call(cmd, &Ident::new("END_RENDER_PASS", name.span()), &[]);
+                let body =
+                    expand_body(cmd, body, &mut Scope::Pass, nesting.within(Nesting::Block))?;
+                quote! {
+                    #begin
+                    {
+                        #body
+                    }
+                    #end
+                }
+            } else if name == "LABEL" {
+                check_count(name, &["name", "color"], 1, args.len(), *span)?;
+
+                let begin = expand_command(cmd, &Ident::new("BEGIN_LABEL", name.span()), args);
+                let end = expand_command(cmd, &Ident::new("END_LABEL", name.span()), &[]);
+                let body = expand_body(cmd, body, scope, nesting.within(Nesting::Block))?;
+                quote! {
+                    #begin
+                    {
+                        #body
+                    }
+                    #end
+                }
+            } else {
+                return Err(unknown(name, "block", SCOPED.iter().copied()));
+            }
+        }
+
+        Statement::For { pat, iter, body } => {
+            let body = expand_body(cmd, body, scope, nesting.within(Nesting::Flow("for")))?;
+            quote! {
+                for #pat in #iter {
+                    #body
+                }
+            }
+        }
+
+        Statement::If {
+            cond,
+            then,
+            otherwise,
+        } => {
+            let nesting = nesting.within(Nesting::Flow("if"));
+            let then = expand_body(cmd, then, scope, nesting)?;
+            let otherwise = match otherwise {
+                Some(otherwise) => {
+                    let otherwise = expand_body(cmd, otherwise, scope, nesting)?;
+                    quote!(else { #otherwise })
+                }
+                None => quote!(),
+            };
+            quote! {
+                if #cond {
+                    #then
+                } #otherwise
+            }
+        }
+
+        Statement::Block(body) => {
+            let body = expand_body(cmd, body, scope, nesting.within(Nesting::Block))?;
+            quote!({ #body })
+        }
+
+        Statement::Rust(stmt) => quote!(#stmt),
+    })
+}
+
+fn expand_command(cmd: &Ident, name: &Ident, args: &[Expr]) -> TokenStream {
+    let color = args
+        .get(1)
+        .map_or_else(|| quote!([0.0; 4]), |color| quote!(#color));
+
+    match name.to_string().as_str() {
+        "BEGIN_LABEL" => {
+            let label = &args[0];
+            quote_spanned!(name.span()=> cmd_begin_label(#cmd, #label, #color);)
+        }
+        "END_LABEL" => quote_spanned!(name.span()=> cmd_end_label(#cmd);),
+        "INSERT_LABEL" => {
+            let label = &args[0];
+            quote_spanned!(name.span()=> cmd_insert_label(#cmd, #label, #color);)
+        }
+        command => {
+            let command = ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == command)
+                .map_or(name.clone(), |(_, to)| Ident::new(to, name.span()));
+            let args: Vec<TokenStream> = args.iter().map(|arg| quote!(#arg)).collect();
+            call(cmd, &command, &args)
+        }
+    }
+}
+
+// only the vk* call is unsafe, the arguments are evaluated before it
+// and the match keeps their temporaries (`&region`, `[0].as_ptr()`) alive for the call
+fn call(cmd: &Ident, command: &Ident, args: &[TokenStream]) -> TokenStream {
+    let values: Vec<Ident> = (0..args.len())
+        .map(|i| format_ident!("arg{}", i, span = Span::mixed_site()))
+        .collect();
+    quote_spanned! {command.span()=>
+        match (#(#args,)*) {
+            (#(#values,)*) => unsafe {
+                vkCmdCall!(#cmd, #command(#(#values),*));
+            }
+        }
+    }
+}
+
+// `INLINE` -> `VkSubpassContents::VK_SUBPASS_CONTENTS_INLINE`, any other expression as it is
+fn subpass_contents(contents: &Expr) -> TokenStream {
+    match contents {
+        Expr::Path(path) if path.qself.is_none() => match path.path.get_ident() {
+            Some(ident) if is_command(ident) => {
+                let variant = format_ident!("VK_SUBPASS_CONTENTS_{}", ident, span = ident.span());
+                quote_spanned!(ident.span()=> VkSubpassContents::#variant)
+            }
+            _ => quote!(#contents),
+        },
+        _ => quote!(#contents),
+    }
+}
+
+fn check_arguments(name: &Ident, args: &[Expr], span: Span) -> syn::Result<()> {
+    let command = name.to_string();
+    let command = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == command)
+        .map_or(command.as_str(), |(_, to)| to);
+
+    if let Some((_, params, required)) = LABELS.iter().find(|(label, ..)| *label == command) {
+        return check_count(name, params, *required, args.len(), span);
+    }
+    match COMMANDS.iter().find(|(known, ..)| *known == command) {
+        Some((_, params, _)) => check_count(name, params, params.len(), args.len(), span),
+        None => Err(unknown(
+            name,
+            "command",
+            COMMANDS
+                .iter()
+                .map(|(known, ..)| *known)
+                .chain(LABELS.iter().map(|(label, ..)| *label))
+                .chain(ALIASES.iter().map(|(alias, _)| *alias)),
+        )),
+    }
+}
+
+// `required` up to `params.len()` arguments
+fn check_count(
+    name: &Ident,
+    params: &[&str],
+    required: usize,
+    found: usize,
+    span: Span,
+) -> syn::Result<()> {
+    if (required..=params.len()).contains(&found) {
+        return Ok(());
+    }
+
+    let expected = match (required, params.len()) {
+        (1, 1) => String::from("1 argument"),
+        (required, most) if required == most => format!("{} arguments", most),
+        (required, most) => format!("{} or {} arguments", required, most),
+    };
+    Err(syn::Error::new(
+        span,
+        format!(
+            "{} expects {} ({}), found {}",
+            name,
+            expected,
+            params.join(", "),
+            found
+        ),
+    ))
+}
+
+fn check_scope(name: &Ident, scope: Scope) -> syn::Result<()> {
+    let command = name.to_string();
+    let begun_by = PASSES
+        .iter()
+        .find(|(_, end)| *end == command)
+        .map(|(begin, _)| *begin);
+    let renderpass = COMMANDS
+        .iter()
+        .find(|(known, ..)| *known == command)
+        .map_or("both", |(.., renderpass)| *renderpass);
+
+    let message = match (scope, begun_by) {
+        (Scope::Any, _) => None,
+        (Scope::Outside, Some(begin)) => Some(format!("{} without {}", command, begin)),
+        (Scope::Inside { end, .. }, Some(_)) if end != command => Some(format!(
+            "{} does not end this render pass, expected {}",
+            command, end
+        )),
+        (Scope::Pass, Some(_)) => Some(format!(
+            "{}: the render pass is ended by the RENDER_PASS block or the primary command buffer",
+            command
+        )),
+        (Scope::Outside, None) if renderpass == "inside" => {
+            Some(format!("{} is only valid inside a render pass", command))
+        }
+        (Scope::Inside { .. } | Scope::Pass, None) if renderpass == "outside" => {
+            Some(format!("{} is not valid inside a render pass", command))
+        }
+        _ => None,
+    };
+
+    match message {
+        Some(message) => Err(syn::Error::new(name.span(), message)),
+        None => Ok(()),
+    }
+}
+
+fn unknown<'a>(name: &Ident, what: &str, known: impl Iterator<Item = &'a str>) -> syn::Error {
+    let name_str = name.to_string();
+    let closest = known
+        .map(|known| (distance(&name_str, known), known))
+        .min_by_key(|(distance, _)| *distance)
+        .filter(|(distance, _)| *distance <= (name_str.len() / 3).max(1));
+
+    let message = match closest {
+        Some((_, known)) => format!("unknown {} {}, did you mean {}?", what, name_str, known),
+        None => format!("unknown {} {}", what, name_str),
+    };
+    syn::Error::new(name.span(), message)
+}
+
+// levenshtein distance
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
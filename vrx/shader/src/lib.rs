@@ -7,10 +7,10 @@ use syn::punctuated::Punctuated;
 use syn::{Expr, ItemStruct, Lit, Meta, Token};
 
 use std::collections::HashMap;
-use std::fmt::Display;
-use std::str::FromStr;
 use std::string::String;
 
+mod cmd_block;
+
 type Metas = Punctuated<Meta, Token![,]>;
 
 fn parse_lits_from_metas(metas: Metas) -> HashMap<String, Lit> {
@@ -42,14 +42,6 @@ fn get_u32_from_lit(lit: &Lit) -> u32 {
     }
 }
 
-fn get_str_from_lit(lit: &Lit) -> String {
-    if let Lit::Str(l) = lit {
-        l.value()
-    } else {
-        unreachable!()
-    }
-}
-
 macro_rules! base {
     ($real: tt) => {
         paste! {
@@ -106,4 +98,28 @@ base!(storage_buffer_dynamic);
 base!(input_attachment);
 base!(inline_uniform_block);
 base!(acceleration_structure_block);
-// base!(acceleration_structure_khr);
\ No newline at end of file
+// base!(acceleration_structure_khr);
+
+///
+/// Command block of vrx, records the commands into a command buffer
+///
+/// ```ignore
+/// vkCmdBlock! {
+///     THIS cmd USAGE ONE_TIME_SUBMIT;
+///     RENDER_PASS(&render_pass_begin_info, INLINE) {
+///         for mesh in &meshes {
+///             DRAW_INDEXED(mesh.index_count, 1, 0, 0, 0);
+///         }
+///     }
+/// }
+/// ```
+///
+#[proc_macro]
+#[allow(non_snake_case)]
+pub fn vkCmdBlock(input: TokenStream) -> TokenStream {
+    let block = syn::parse_macro_input!(input as cmd_block::Block);
+    block
+        .expand()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
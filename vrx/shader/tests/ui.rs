@@ -0,0 +1,9 @@
+//
+// compile errors of vkCmdBlock!, the messages and the tokens they point at
+// TRYBUILD=overwrite updates tests/ui/*.stderr
+//
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
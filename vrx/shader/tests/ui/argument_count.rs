@@ -0,0 +1,8 @@
+use shader::vkCmdBlock;
+
+fn main() {
+    vkCmdBlock! {
+        INTO command_buffer;
+        BLIT_IMAGE(src, src_layout, dst, dst_layout, 1, &blit);
+    }
+}
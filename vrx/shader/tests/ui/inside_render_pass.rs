@@ -0,0 +1,11 @@
+use shader::vkCmdBlock;
+
+fn main() {
+    vkCmdBlock! {
+        THIS command_buffer;
+        RENDER_PASS(&render_pass_begin_info, INLINE) {
+            DRAW(3, 1, 0, 0);
+            COPY_BUFFER(src, dst, 1, &region);
+        }
+    }
+}
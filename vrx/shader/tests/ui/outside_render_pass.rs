@@ -0,0 +1,9 @@
+use shader::vkCmdBlock;
+
+fn main() {
+    vkCmdBlock! {
+        THIS command_buffer;
+        BIND_PIPELINE(VK_PIPELINE_BIND_POINT_GRAPHICS, pipeline);
+        DRAW(3, 1, 0, 0);
+    }
+}
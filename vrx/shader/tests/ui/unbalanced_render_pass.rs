@@ -0,0 +1,9 @@
+use shader::vkCmdBlock;
+
+fn main() {
+    vkCmdBlock! {
+        THIS command_buffer;
+        BEGIN_RENDER_PASS(&render_pass_begin_info, INLINE);
+        DRAW(3, 1, 0, 0);
+    }
+}
@@ -0,0 +1,8 @@
+use shader::vkCmdBlock;
+
+fn main() {
+    vkCmdBlock! {
+        INTO command_buffer;
+        COPY_BUFER(src, dst, 1, &region);
+    }
+}
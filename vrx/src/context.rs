@@ -0,0 +1,485 @@
+//
+// context
+// instance creation: application info, api version, layers and extensions
+//
+use crate::*;
+
+#[derive(Debug)]
+pub enum ContextError {
+    MissingLayer(String),
+    MissingExtension(String),
+    UnsupportedApiVersion { requested: u32, available: u32 },
+}
+
+impl std::fmt::Display for ContextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContextError::MissingLayer(name) => {
+                write!(f, "[vrx] required instance layer {} is not available", name)
+            }
+            ContextError::MissingExtension(name) => {
+                write!(
+                    f,
+                    "[vrx] required instance extension {} is not available",
+                    name
+                )
+            }
+            ContextError::UnsupportedApiVersion {
+                requested,
+                available,
+            } => write!(
+                f,
+                "[vrx] vulkan {} was requested but the loader only supports {}",
+                version_string(*requested),
+                version_string(*available)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ContextError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiVersion {
+    V1_0,
+    V1_1,
+    V1_2,
+    V1_3,
+}
+
+impl ApiVersion {
+    pub const fn raw(self) -> u32 {
+        match self {
+            ApiVersion::V1_0 => make_version(1, 0, 0),
+            ApiVersion::V1_1 => make_version(1, 1, 0),
+            ApiVersion::V1_2 => make_version(1, 2, 0),
+            ApiVersion::V1_3 => make_version(1, 3, 0),
+        }
+    }
+}
+
+pub(crate) fn version_string(version: u32) -> String {
+    format!("{}.{}", (version >> 22) & 0x7f, (version >> 12) & 0x3ff)
+}
+
+// window system extensions of the compiled-in surface constructors
+#[cfg(feature = "graphics")]
+fn surface_extensions() -> Vec<&'static str> {
+    let mut extensions = vec!["VK_KHR_surface"];
+    #[cfg(target_os = "windows")]
+    extensions.push("VK_KHR_win32_surface");
+    #[cfg(feature = "xlib")]
+    extensions.push("VK_KHR_xlib_surface");
+    #[cfg(feature = "xcb")]
+    extensions.push("VK_KHR_xcb_surface");
+    #[cfg(feature = "wayland")]
+    extensions.push("VK_KHR_wayland_surface");
+    extensions
+}
+
+#[cfg(not(feature = "graphics"))]
+fn surface_extensions() -> Vec<&'static str> {
+    vec![]
+}
+
+fn c_name(name: &[c_char]) -> String {
+    unsafe { CStr::from_ptr(name.as_ptr()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+pub fn available_instance_layers() -> Result<Vec<String>, VkError> {
+    let mut count = 0;
+    unsafe {
+        vk_check(vkEnumerateInstanceLayerProperties(&mut count, null_mut()))?;
+        let mut properties = vec![std::mem::zeroed::<VkLayerProperties>(); count as usize];
+        vk_check(vkEnumerateInstanceLayerProperties(
+            &mut count,
+            properties.as_mut_ptr(),
+        ))?;
+        properties.truncate(count as usize);
+        Ok(properties.iter().map(|p| c_name(&p.layerName)).collect())
+    }
+}
+
+// extensions of the implementation and the implicit layers, then those only `layers` provide
+pub fn available_instance_extensions(layers: &[String]) -> Result<Vec<String>, VkError> {
+    let mut extensions = layer_extensions(None)?;
+    for layer in layers {
+        let layer = CString::new(layer.as_str()).map_err(|_| VkError::LayerNotPresent)?;
+        for extension in layer_extensions(Some(&layer))? {
+            if !extensions.contains(&extension) {
+                extensions.push(extension);
+            }
+        }
+    }
+    Ok(extensions)
+}
+
+fn layer_extensions(layer: Option<&CStr>) -> Result<Vec<String>, VkError> {
+    let layer_name = layer.map_or(null(), |name| name.as_ptr());
+    let mut count = 0;
+    unsafe {
+        vk_check(vkEnumerateInstanceExtensionProperties(
+            layer_name,
+            &mut count,
+            null_mut(),
+        ))?;
+        let mut properties = vec![std::mem::zeroed::<VkExtensionProperties>(); count as usize];
+        vk_check(vkEnumerateInstanceExtensionProperties(
+            layer_name,
+            &mut count,
+            properties.as_mut_ptr(),
+        ))?;
+        properties.truncate(count as usize);
+        Ok(properties
+            .iter()
+            .map(|p| c_name(&p.extensionName))
+            .collect())
+    }
+}
+
+// vkEnumerateInstanceVersion only exists on 1.1 loaders
+pub fn available_api_version() -> anyhow::Result<u32> {
+    let entry = loader::vulkan_entry()?;
+    if entry.table.vkEnumerateInstanceVersion.is_none() {
+        return Ok(ApiVersion::V1_0.raw());
+    }
+
+    let mut version = 0;
+    unsafe {
+        vk_check(vkEnumerateInstanceVersion(&mut version))?;
+    }
+    Ok(version)
+}
+
+///
+/// Builder of `Context`
+///
+/// Required layers and extensions fail `build` when they are missing,
+/// optional ones are skipped. `Context::enabled_layers` and `Context::enabled_extensions`
+/// tell what ended up enabled.
+///
+/// ```ignore
+/// let ctx = ContextBuilder::new()
+///     .app_name("thumbnails")
+///     .api_version(ApiVersion::V1_2)
+///     .optional_layer("VK_LAYER_KHRONOS_validation")
+///     .extension("VK_EXT_debug_utils")
+///     .build()?;
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct ContextBuilder {
+    app_name: String,
+    app_version: u32,
+    engine_name: String,
+    engine_version: u32,
+    api_version: ApiVersion,
+    required_layers: Vec<String>,
+    optional_layers: Vec<String>,
+    required_extensions: Vec<String>,
+    optional_extensions: Vec<String>,
+    headless: bool,
+    debug_messenger: Option<DebugMessengerBuilder>,
+}
+
+impl Default for ContextBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContextBuilder {
+    pub fn new() -> Self {
+        Self {
+            app_name: "vrx".to_string(),
+            app_version: make_version(1, 0, 0),
+            engine_name: "vrx".to_string(),
+            engine_version: make_version(1, 0, 0),
+            api_version: ApiVersion::V1_0,
+            required_layers: vec![],
+            optional_layers: vec![],
+            required_extensions: vec![],
+            optional_extensions: vec![],
+            headless: false,
+            debug_messenger: None,
+        }
+    }
+
+    pub fn app_name(mut self, app_name: &str) -> Self {
+        self.app_name = app_name.to_string();
+        self
+    }
+
+    pub fn app_version(mut self, major: u32, minor: u32, patch: u32) -> Self {
+        self.app_version = make_version(major, minor, patch);
+        self
+    }
+
+    pub fn engine_name(mut self, engine_name: &str) -> Self {
+        self.engine_name = engine_name.to_string();
+        self
+    }
+
+    pub fn engine_version(mut self, major: u32, minor: u32, patch: u32) -> Self {
+        self.engine_version = make_version(major, minor, patch);
+        self
+    }
+
+    pub fn api_version(mut self, api_version: ApiVersion) -> Self {
+        self.api_version = api_version;
+        self
+    }
+
+    // required layer
+    pub fn layer(mut self, name: &str) -> Self {
+        self.required_layers.push(name.to_string());
+        self
+    }
+
+    pub fn optional_layer(mut self, name: &str) -> Self {
+        self.optional_layers.push(name.to_string());
+        self
+    }
+
+    // required extension
+    pub fn extension(mut self, name: &str) -> Self {
+        self.required_extensions.push(name.to_string());
+        self
+    }
+
+    pub fn optional_extension(mut self, name: &str) -> Self {
+        self.optional_extensions.push(name.to_string());
+        self
+    }
+
+    ///
+    /// No window system, `VK_EXT_headless_surface` is required
+    /// instead of the optional win32/xlib/xcb/wayland extensions
+    ///
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        self
+    }
+
+    ///
+    /// `VK_EXT_debug_utils` messenger, also covering instance creation and destruction
+    ///
+    /// Skipped when the extension is not available, add `.extension("VK_EXT_debug_utils")` to require it.
+    ///
+    pub fn debug_messenger(mut self, debug_messenger: DebugMessengerBuilder) -> Self {
+        self.debug_messenger = Some(debug_messenger);
+        self
+    }
+
+    pub fn build(self) -> anyhow::Result<Context> {
+        // fail early with the loader error instead of inside the first command
+        loader::vulkan_entry()?;
+
+        let available_version = available_api_version()?;
+        if self.api_version.raw() > available_version {
+            return Err(ContextError::UnsupportedApiVersion {
+                requested: self.api_version.raw(),
+                available: available_version,
+            }
+            .into());
+        }
+
+        let mut required_extensions = self.required_extensions.clone();
+        let mut optional_extensions = self.optional_extensions.clone();
+        if self.headless {
+            required_extensions.push("VK_KHR_surface".to_string());
+            required_extensions.push("VK_EXT_headless_surface".to_string());
+        } else {
+            // a window system missing from the driver is skipped, its surface constructor fails instead
+            optional_extensions.extend(surface_extensions().into_iter().map(String::from));
+        }
+        if self.debug_messenger.is_some() {
+            optional_extensions.push("VK_EXT_debug_utils".to_string());
+        }
+
+        let enabled_layers = select(
+            &available_instance_layers()?,
+            &self.required_layers,
+            &self.optional_layers,
+            ContextError::MissingLayer,
+        )?;
+        let enabled_extensions = select(
+            &available_instance_extensions(&enabled_layers)?,
+            &required_extensions,
+            &optional_extensions,
+            ContextError::MissingExtension,
+        )?;
+
+        // careful to CString lifetime
+        let app_name = CString::new(self.app_name.as_str())?;
+        let engine_name = CString::new(self.engine_name.as_str())?;
+        let layers = enabled_layers
+            .iter()
+            .map(|name| CString::new(name.as_str()))
+            .collect::<Result<Vec<CString>, _>>()?;
+        let extensions = enabled_extensions
+            .iter()
+            .map(|name| CString::new(name.as_str()))
+            .collect::<Result<Vec<CString>, _>>()?;
+        let pp_layers: Vec<*const c_char> = layers.iter().map(|l| l.as_ptr()).collect();
+        let pp_extensions: Vec<*const c_char> = extensions.iter().map(|e| e.as_ptr()).collect();
+
+        let app_info = VkApplicationInfoBuilder::new()
+            .p_application_name(app_name.as_ptr())
+            .application_version(self.app_version)
+            .p_engine_name(engine_name.as_ptr())
+            .engine_version(self.engine_version)
+            .api_version(self.api_version.raw())
+            .build();
+
+        // the state is boxed so the callback's user data stays put
+        let debug_messenger = self
+            .debug_messenger
+            .filter(|_| enabled_extensions.iter().any(|e| e == "VK_EXT_debug_utils"))
+            .map(|builder| {
+                let state = builder.state();
+                (builder, state)
+            });
+        let mut messenger_create_info = debug_messenger
+            .as_ref()
+            .map(|(builder, state)| builder.create_info(state));
+
+        let mut instance_create_info = VkInstanceCreateInfoBuilder::new()
+            .p_application_info(&app_info)
+            .enabled_layer_count(pp_layers.len() as u32)
+            .pp_enabled_layer_names(pp_layers.as_ptr())
+            .enabled_extension_count(pp_extensions.len() as u32)
+            .pp_enabled_extension_names(pp_extensions.as_ptr());
+        if let Some(info) = messenger_create_info.as_mut() {
+            instance_create_info = instance_create_info.push_next(info);
+        }
+        let instance_create_info = instance_create_info.build_chained();
+
+        let mut instance = vk_instantiate!(VkInstance);
+        unsafe {
+            vk_check(vkCreateInstance(
+                &*instance_create_info,
+                null(),
+                &mut instance,
+            ))?;
+        }
+
+        let debug_messenger = match debug_messenger {
+            Some((builder, state)) => match DebugMessenger::new(instance, &builder, state) {
+                Ok(messenger) => Some(messenger),
+                Err(e) => {
+                    unsafe { vkDestroyInstance(instance, null()) };
+                    return Err(e.into());
+                }
+            },
+            None => None,
+        };
+
+        // from here on a failure drops the context, destroying the messenger and the instance
+        let mut context = Context {
+            instance,
+            physical_devices: vec![],
+            debug_messenger,
+            headless: self.headless,
+            api_version: self.api_version.raw(),
+            enabled_layers,
+            enabled_extensions,
+        };
+        unsafe {
+            let mut device_count = 0u32;
+            vk_check(vkEnumeratePhysicalDevices(
+                instance,
+                &mut device_count,
+                null_mut(),
+            ))?;
+            context.physical_devices =
+                vec![vk_instantiate!(VkPhysicalDevice); device_count as usize];
+            vk_check(vkEnumeratePhysicalDevices(
+                instance,
+                &mut device_count,
+                context.physical_devices.as_mut_ptr(),
+            ))?;
+            context.physical_devices.truncate(device_count as usize);
+        }
+        Ok(context)
+    }
+}
+
+// required names must be available, optional ones are kept when they are
+pub(crate) fn select<E>(
+    available: &[String],
+    required: &[String],
+    optional: &[String],
+    missing: fn(String) -> E,
+) -> Result<Vec<String>, E> {
+    let mut enabled: Vec<String> = vec![];
+    for name in required {
+        if !available.contains(name) {
+            return Err(missing(name.clone()));
+        }
+        if !enabled.contains(name) {
+            enabled.push(name.clone());
+        }
+    }
+    for name in optional {
+        if available.contains(name) && !enabled.contains(name) {
+            enabled.push(name.clone());
+        }
+    }
+    Ok(enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn select_keeps_required_then_available_optional_names() {
+        let available = names(&["VK_KHR_surface", "VK_EXT_debug_utils", "VK_KHR_xcb_surface"]);
+        let enabled = select(
+            &available,
+            &names(&["VK_EXT_debug_utils", "VK_KHR_surface", "VK_EXT_debug_utils"]),
+            &names(&[
+                "VK_KHR_xcb_surface",
+                "VK_KHR_wayland_surface",
+                "VK_KHR_surface",
+            ]),
+            ContextError::MissingExtension,
+        )
+        .unwrap();
+        assert_eq!(
+            enabled,
+            names(&["VK_EXT_debug_utils", "VK_KHR_surface", "VK_KHR_xcb_surface"])
+        );
+    }
+
+    #[test]
+    fn select_fails_on_the_first_missing_required_name() {
+        let available = names(&["VK_LAYER_KHRONOS_validation"]);
+        let missing = select(
+            &available,
+            &names(&[
+                "VK_LAYER_KHRONOS_validation",
+                "VK_LAYER_MESA_overlay",
+                "VK_LAYER_LUNARG_api_dump",
+            ]),
+            &[],
+            ContextError::MissingLayer,
+        );
+        assert!(
+            matches!(missing, Err(ContextError::MissingLayer(name)) if name == "VK_LAYER_MESA_overlay")
+        );
+
+        // optional names never fail
+        assert_eq!(
+            select(&[], &[], &available, ContextError::MissingLayer).unwrap(),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -0,0 +1,476 @@
+//
+// debug
+// VK_EXT_debug_utils messenger calling back into rust
+//
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::*;
+
+#[derive(Debug, Clone)]
+pub struct DebugMessage {
+    pub severity: DebugUtilsMessageSeverityFlagsEXT,
+    pub types: DebugUtilsMessageTypeFlagsEXT,
+    // e.g. "VUID-vkCmdDraw-None-02859"
+    pub id_name: String,
+    pub id_number: i32,
+    pub message: String,
+}
+
+impl DebugMessage {
+    pub fn is_error(&self) -> bool {
+        self.severity
+            .contains(DebugUtilsMessageSeverityFlagsEXT::ERROR)
+    }
+
+    pub fn is_warning(&self) -> bool {
+        self.severity
+            .contains(DebugUtilsMessageSeverityFlagsEXT::WARNING)
+    }
+}
+
+pub type DebugCallback = Arc<dyn Fn(&DebugMessage) + Send + Sync>;
+
+// default callback, vulkan severities to `log` levels
+pub fn log_debug_message(message: &DebugMessage) {
+    log::log!(target: "vrx", log_level(message), "[{}] {}", message.id_name, message.message);
+}
+
+fn log_level(message: &DebugMessage) -> log::Level {
+    if message.is_error() {
+        log::Level::Error
+    } else if message.is_warning() {
+        log::Level::Warn
+    } else if message
+        .severity
+        .contains(DebugUtilsMessageSeverityFlagsEXT::INFO)
+    {
+        log::Level::Info
+    } else {
+        log::Level::Trace
+    }
+}
+
+///
+/// Configuration of the messenger created by `ContextBuilder::debug_messenger`
+///
+/// Warnings and errors of every message type go to `log` unless `callback` replaces it.
+/// With `collect(true)` the messages are kept as well, e.g. to fail a test on validation errors:
+///
+/// ```ignore
+/// let ctx = ContextBuilder::new()
+///     .layer("VK_LAYER_KHRONOS_validation")
+///     .extension("VK_EXT_debug_utils")
+///     .debug_messenger(DebugMessengerBuilder::new().collect(true))
+///     .build()?;
+/// // ... workload
+/// assert!(ctx.debug_messenger.as_ref().unwrap().errors().is_empty());
+/// ```
+///
+#[derive(Clone)]
+pub struct DebugMessengerBuilder {
+    severity: DebugUtilsMessageSeverityFlagsEXT,
+    types: DebugUtilsMessageTypeFlagsEXT,
+    callback: DebugCallback,
+    collect: bool,
+}
+
+impl std::fmt::Debug for DebugMessengerBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DebugMessengerBuilder")
+            .field("severity", &self.severity)
+            .field("types", &self.types)
+            .field("collect", &self.collect)
+            .finish()
+    }
+}
+
+impl Default for DebugMessengerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugMessengerBuilder {
+    pub fn new() -> Self {
+        Self {
+            severity: DebugUtilsMessageSeverityFlagsEXT::WARNING
+                | DebugUtilsMessageSeverityFlagsEXT::ERROR,
+            types: DebugUtilsMessageTypeFlagsEXT::GENERAL
+                | DebugUtilsMessageTypeFlagsEXT::VALIDATION
+                | DebugUtilsMessageTypeFlagsEXT::PERFORMANCE,
+            callback: Arc::new(log_debug_message),
+            collect: false,
+        }
+    }
+
+    pub fn severity(mut self, severity: impl Into<DebugUtilsMessageSeverityFlagsEXT>) -> Self {
+        self.severity = severity.into();
+        self
+    }
+
+    pub fn message_types(mut self, types: impl Into<DebugUtilsMessageTypeFlagsEXT>) -> Self {
+        self.types = types.into();
+        self
+    }
+
+    // replaces the `log` callback
+    pub fn callback(mut self, callback: impl Fn(&DebugMessage) + Send + Sync + 'static) -> Self {
+        self.callback = Arc::new(callback);
+        self
+    }
+
+    pub fn collect(mut self, collect: bool) -> Self {
+        self.collect = collect;
+        self
+    }
+
+    // the state has to outlive the instance, the callback receives a pointer to it
+    pub(crate) fn state(&self) -> Box<MessengerState> {
+        Box::new(MessengerState {
+            callback: self.callback.clone(),
+            collect: self.collect,
+            messages: Mutex::new(vec![]),
+            error_count: AtomicUsize::new(0),
+            warning_count: AtomicUsize::new(0),
+        })
+    }
+
+    pub(crate) fn create_info(&self, state: &MessengerState) -> VkDebugUtilsMessengerCreateInfoEXT {
+        VkDebugUtilsMessengerCreateInfoEXTBuilder::new()
+            .message_severity(self.severity)
+            .message_type(self.types)
+            .pfn_user_callback(Some(debug_utils_callback))
+            .p_user_data(state as *const MessengerState as *mut c_void)
+            .build()
+    }
+}
+
+pub(crate) struct MessengerState {
+    callback: DebugCallback,
+    collect: bool,
+    messages: Mutex<Vec<DebugMessage>>,
+    error_count: AtomicUsize,
+    warning_count: AtomicUsize,
+}
+
+///
+/// Messenger of a `Context`, destroyed right before the instance
+///
+pub struct DebugMessenger {
+    pub messenger: VkDebugUtilsMessengerEXT,
+    state: Box<MessengerState>,
+}
+
+impl std::fmt::Debug for DebugMessenger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DebugMessenger")
+            .field("messenger", &self.messenger)
+            .field("error_count", &self.error_count())
+            .field("warning_count", &self.warning_count())
+            .finish()
+    }
+}
+
+impl DebugMessenger {
+    pub(crate) fn new(
+        instance: VkInstance,
+        builder: &DebugMessengerBuilder,
+        state: Box<MessengerState>,
+    ) -> Result<Self, VkError> {
+        let create_info = builder.create_info(&state);
+        let mut messenger = vk_instantiate!(VkDebugUtilsMessengerEXT);
+        unsafe {
+            vk_check(vkCreateDebugUtilsMessengerEXT(
+                instance,
+                &create_info,
+                null(),
+                &mut messenger,
+            ))?;
+        }
+        Ok(Self { messenger, state })
+    }
+
+    pub(crate) fn destroy(&mut self, instance: VkInstance) {
+        unsafe {
+            vkDestroyDebugUtilsMessengerEXT(instance, self.messenger, null());
+        }
+    }
+
+    // counted whether or not the messages are collected
+    pub fn error_count(&self) -> usize {
+        self.state.error_count.load(Ordering::Relaxed)
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.state.warning_count.load(Ordering::Relaxed)
+    }
+
+    // collected messages, empty unless `collect(true)`
+    pub fn messages(&self) -> Vec<DebugMessage> {
+        self.state.messages.lock().unwrap().clone()
+    }
+
+    pub fn errors(&self) -> Vec<DebugMessage> {
+        self.state
+            .messages
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| m.is_error())
+            .cloned()
+            .collect()
+    }
+
+    // collected messages, the counters start over
+    pub fn take_messages(&self) -> Vec<DebugMessage> {
+        self.state.error_count.store(0, Ordering::Relaxed);
+        self.state.warning_count.store(0, Ordering::Relaxed);
+        std::mem::take(&mut *self.state.messages.lock().unwrap())
+    }
+}
+
+fn c_string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+unsafe extern "C" fn debug_utils_callback(
+    message_severity: VkDebugUtilsMessageSeverityFlagBitsEXT,
+    message_types: VkDebugUtilsMessageTypeFlagsEXT,
+    p_callback_data: *const VkDebugUtilsMessengerCallbackDataEXT,
+    p_user_data: *mut c_void,
+) -> VkBool32 {
+    if p_callback_data.is_null() || p_user_data.is_null() {
+        return VK_FALSE;
+    }
+    let state = &*(p_user_data as *const MessengerState);
+    let data = &*p_callback_data;
+
+    let message = DebugMessage {
+        severity: DebugUtilsMessageSeverityFlagsEXT::from(message_severity),
+        types: DebugUtilsMessageTypeFlagsEXT::from(message_types),
+        id_name: c_string(data.pMessageIdName),
+        id_number: data.messageIdNumber,
+        message: c_string(data.pMessage),
+    };
+
+    if message.is_error() {
+        state.error_count.fetch_add(1, Ordering::Relaxed);
+    } else if message.is_warning() {
+        state.warning_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // a panic must not unwind into the driver
+    let _ = catch_unwind(AssertUnwindSafe(|| (state.callback)(&message)));
+
+    if state.collect {
+        if let Ok(mut messages) = state.messages.lock() {
+            messages.push(message);
+        }
+    }
+
+    // the spec reserves VK_TRUE for layer development
+    VK_FALSE
+}
+
+//
+// command buffer labels, group the commands in captures and validation messages
+// nothing is recorded without VK_EXT_debug_utils
+//
+fn label_commands_loaded(cmd: VkCommandBuffer) -> bool {
+    loader::is_device_command_loaded(cmd, |table| table.vkCmdBeginDebugUtilsLabelEXT.is_some())
+}
+
+fn with_label(name: &str, color: [f32; 4], f: impl FnOnce(&VkDebugUtilsLabelEXT)) {
+    let name = CString::new(name.replace('\0', "")).unwrap();
+    let label = VkDebugUtilsLabelEXTBuilder::new()
+        .p_label_name(name.as_ptr())
+        .color(color)
+        .build();
+    f(&label)
+}
+
+// `cmd` is a handle, only passed on to vulkan
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn cmd_begin_label(cmd: VkCommandBuffer, name: &str, color: [f32; 4]) {
+    if label_commands_loaded(cmd) {
+        with_label(name, color, |label| unsafe {
+            vkCmdBeginDebugUtilsLabelEXT(cmd, label)
+        });
+    }
+}
+
+// `cmd` is a handle, only passed on to vulkan
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn cmd_end_label(cmd: VkCommandBuffer) {
+    if label_commands_loaded(cmd) {
+        unsafe { vkCmdEndDebugUtilsLabelEXT(cmd) };
+    }
+}
+
+// `cmd` is a handle, only passed on to vulkan
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn cmd_insert_label(cmd: VkCommandBuffer, name: &str, color: [f32; 4]) {
+    if label_commands_loaded(cmd) {
+        with_label(name, color, |label| unsafe {
+            vkCmdInsertDebugUtilsLabelEXT(cmd, label)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // what the layers hand to the callback, without an instance
+    fn call(
+        messenger: &DebugMessenger,
+        severity: VkDebugUtilsMessageSeverityFlagBitsEXT,
+        message: &CStr,
+    ) -> VkBool32 {
+        let data = VkDebugUtilsMessengerCallbackDataEXT {
+            pMessageIdName: c"VUID-vkCmdDraw-None-02859".as_ptr(),
+            messageIdNumber: 42,
+            pMessage: message.as_ptr(),
+            ..Default::default()
+        };
+        unsafe {
+            debug_utils_callback(
+                severity,
+                VK_DEBUG_UTILS_MESSAGE_TYPE_VALIDATION_BIT_EXT,
+                &data,
+                &*messenger.state as *const MessengerState as *mut c_void,
+            )
+        }
+    }
+
+    fn messenger(builder: DebugMessengerBuilder) -> DebugMessenger {
+        DebugMessenger {
+            messenger: null_mut(),
+            state: builder.state(),
+        }
+    }
+
+    fn message(severity: DebugUtilsMessageSeverityFlagsEXT) -> DebugMessage {
+        DebugMessage {
+            severity,
+            types: DebugUtilsMessageTypeFlagsEXT::GENERAL,
+            id_name: String::new(),
+            id_number: 0,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn callback_counts_and_collects_the_messages() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = calls.clone();
+        let messenger = messenger(
+            DebugMessengerBuilder::new()
+                .callback(move |_| {
+                    counted.fetch_add(1, Ordering::Relaxed);
+                })
+                .collect(true),
+        );
+
+        for (severity, text) in [
+            (
+                VK_DEBUG_UTILS_MESSAGE_SEVERITY_ERROR_BIT_EXT,
+                c"first error",
+            ),
+            (
+                VK_DEBUG_UTILS_MESSAGE_SEVERITY_WARNING_BIT_EXT,
+                c"a warning",
+            ),
+            (
+                VK_DEBUG_UTILS_MESSAGE_SEVERITY_ERROR_BIT_EXT,
+                c"second error",
+            ),
+            (VK_DEBUG_UTILS_MESSAGE_SEVERITY_INFO_BIT_EXT, c"some info"),
+        ] {
+            assert_eq!(call(&messenger, severity, text), VK_FALSE);
+        }
+
+        assert_eq!(calls.load(Ordering::Relaxed), 4);
+        assert_eq!((messenger.error_count(), messenger.warning_count()), (2, 1));
+        let errors = messenger.errors();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[1].message, "second error");
+        assert_eq!(errors[1].id_name, "VUID-vkCmdDraw-None-02859");
+        assert_eq!(errors[1].id_number, 42);
+        assert!(errors[1]
+            .types
+            .contains(DebugUtilsMessageTypeFlagsEXT::VALIDATION));
+
+        assert_eq!(messenger.take_messages().len(), 4);
+        assert!(messenger.messages().is_empty());
+        assert_eq!((messenger.error_count(), messenger.warning_count()), (0, 0));
+    }
+
+    #[test]
+    fn callback_only_counts_without_collect() {
+        let messenger = messenger(DebugMessengerBuilder::new().callback(|_| {}));
+        call(
+            &messenger,
+            VK_DEBUG_UTILS_MESSAGE_SEVERITY_ERROR_BIT_EXT,
+            c"error",
+        );
+
+        assert_eq!(messenger.error_count(), 1);
+        assert!(messenger.messages().is_empty());
+    }
+
+    #[test]
+    fn callback_catches_panics_of_the_user_callback() {
+        let messenger = messenger(
+            DebugMessengerBuilder::new()
+                .callback(|_| panic!("user callback"))
+                .collect(true),
+        );
+
+        let result = call(
+            &messenger,
+            VK_DEBUG_UTILS_MESSAGE_SEVERITY_ERROR_BIT_EXT,
+            c"error",
+        );
+        assert_eq!(result, VK_FALSE);
+        assert_eq!(messenger.error_count(), 1);
+        assert_eq!(messenger.messages().len(), 1);
+    }
+
+    #[test]
+    fn callback_ignores_missing_data() {
+        let messenger = messenger(DebugMessengerBuilder::new().collect(true));
+        let result = unsafe {
+            debug_utils_callback(
+                VK_DEBUG_UTILS_MESSAGE_SEVERITY_ERROR_BIT_EXT,
+                VK_DEBUG_UTILS_MESSAGE_TYPE_GENERAL_BIT_EXT,
+                null(),
+                &*messenger.state as *const MessengerState as *mut c_void,
+            )
+        };
+        assert_eq!(result, VK_FALSE);
+        assert_eq!(messenger.error_count(), 0);
+    }
+
+    #[test]
+    fn severities_map_to_log_levels() {
+        use DebugUtilsMessageSeverityFlagsEXT as Severity;
+
+        assert_eq!(log_level(&message(Severity::ERROR)), log::Level::Error);
+        assert_eq!(log_level(&message(Severity::WARNING)), log::Level::Warn);
+        assert_eq!(log_level(&message(Severity::INFO)), log::Level::Info);
+        assert_eq!(log_level(&message(Severity::VERBOSE)), log::Level::Trace);
+        // the most severe bit wins
+        assert_eq!(
+            log_level(&message(Severity::WARNING | Severity::ERROR)),
+            log::Level::Error
+        );
+    }
+}
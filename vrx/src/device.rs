@@ -0,0 +1,558 @@
+//
+// device
+// logical device creation: queues, extensions and the core 1.0 - 1.3 features
+//
+use crate::*;
+
+#[derive(Debug)]
+pub enum DeviceError {
+    MissingExtension(String),
+    // structure and member, e.g. ("VkPhysicalDeviceFeatures", "samplerAnisotropy")
+    MissingFeature(&'static str, &'static str),
+}
+
+impl std::fmt::Display for DeviceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeviceError::MissingExtension(name) => {
+                write!(
+                    f,
+                    "[vrx] required device extension {} is not supported",
+                    name
+                )
+            }
+            DeviceError::MissingFeature(structure, member) => {
+                write!(
+                    f,
+                    "[vrx] required feature {}::{} is not supported",
+                    structure, member
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DeviceError {}
+
+// every VkBool32 member of a structure, with its name for the errors
+macro_rules! feature_bits {
+    ($features:expr, [$($member:ident),+ $(,)?]) => {
+        vec![$((stringify!($member), &mut $features.$member)),+]
+    };
+}
+
+///
+/// Features of the core versions, chained behind VkPhysicalDeviceFeatures2
+///
+/// `vulkan11`, `vulkan12` and `vulkan13` are only queried and enabled on 1.2 and 1.3 devices,
+/// below that they stay VK_FALSE.
+///
+/// ```ignore
+/// let mut features = DeviceFeatures::new();
+/// features.core.samplerAnisotropy = VK_TRUE;
+/// features.vulkan12.timelineSemaphore = VK_TRUE;
+/// ```
+///
+#[derive(Debug, Clone, Copy)]
+pub struct DeviceFeatures {
+    pub core: VkPhysicalDeviceFeatures,
+    pub vulkan11: VkPhysicalDeviceVulkan11Features,
+    pub vulkan12: VkPhysicalDeviceVulkan12Features,
+    pub vulkan13: VkPhysicalDeviceVulkan13Features,
+}
+
+impl Default for DeviceFeatures {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeviceFeatures {
+    // everything VK_FALSE
+    pub fn new() -> Self {
+        Self {
+            core: VkPhysicalDeviceFeatures::default(),
+            vulkan11: VkPhysicalDeviceVulkan11FeaturesBuilder::new().build(),
+            vulkan12: VkPhysicalDeviceVulkan12FeaturesBuilder::new().build(),
+            vulkan13: VkPhysicalDeviceVulkan13FeaturesBuilder::new().build(),
+        }
+    }
+
+    // what `physical_device` supports when used at `api_version`
+    // `physical_device` is a handle, only passed on to vulkan
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn supported(physical_device: VkPhysicalDevice, api_version: u32) -> Self {
+        let mut features = Self::new();
+        if api_version < ApiVersion::V1_1.raw() {
+            features.core = physical_device.get_features();
+            return features;
+        }
+
+        features.with_chain(api_version, |features2| unsafe {
+            vkGetPhysicalDeviceFeatures2(physical_device, features2)
+        });
+        features
+    }
+
+    ///
+    /// VkPhysicalDeviceFeatures2 with the structures of `api_version` chained in place,
+    /// what `f` writes to the chain ends up in `self`
+    ///
+    pub fn with_chain<R>(
+        &mut self,
+        api_version: u32,
+        f: impl FnOnce(&mut VkPhysicalDeviceFeatures2) -> R,
+    ) -> R {
+        self.vulkan11.pNext = null_mut();
+        self.vulkan12.pNext = null_mut();
+        self.vulkan13.pNext = null_mut();
+
+        let mut builder = VkPhysicalDeviceFeatures2Builder::new().features(self.core);
+        if api_version >= ApiVersion::V1_3.raw() {
+            builder = builder.push_next(&mut self.vulkan13);
+        }
+        if api_version >= ApiVersion::V1_2.raw() {
+            builder = builder
+                .push_next(&mut self.vulkan12)
+                .push_next(&mut self.vulkan11);
+        }
+        let mut features2 = builder.build_chained();
+
+        let result = f(&mut features2);
+        self.core = features2.features;
+        result
+    }
+
+    // (structure name, (member name, VkBool32 member)) of every structure
+    fn members(&mut self) -> [(&'static str, Vec<(&'static str, &mut VkBool32)>); 4] {
+        [
+            (
+                "VkPhysicalDeviceFeatures",
+                feature_bits!(
+                    self.core,
+                    [
+                        robustBufferAccess,
+                        fullDrawIndexUint32,
+                        imageCubeArray,
+                        independentBlend,
+                        geometryShader,
+                        tessellationShader,
+                        sampleRateShading,
+                        dualSrcBlend,
+                        logicOp,
+                        multiDrawIndirect,
+                        drawIndirectFirstInstance,
+                        depthClamp,
+                        depthBiasClamp,
+                        fillModeNonSolid,
+                        depthBounds,
+                        wideLines,
+                        largePoints,
+                        alphaToOne,
+                        multiViewport,
+                        samplerAnisotropy,
+                        textureCompressionETC2,
+                        textureCompressionASTC_LDR,
+                        textureCompressionBC,
+                        occlusionQueryPrecise,
+                        pipelineStatisticsQuery,
+                        vertexPipelineStoresAndAtomics,
+                        fragmentStoresAndAtomics,
+                        shaderTessellationAndGeometryPointSize,
+                        shaderImageGatherExtended,
+                        shaderStorageImageExtendedFormats,
+                        shaderStorageImageMultisample,
+                        shaderStorageImageReadWithoutFormat,
+                        shaderStorageImageWriteWithoutFormat,
+                        shaderUniformBufferArrayDynamicIndexing,
+                        shaderSampledImageArrayDynamicIndexing,
+                        shaderStorageBufferArrayDynamicIndexing,
+                        shaderStorageImageArrayDynamicIndexing,
+                        shaderClipDistance,
+                        shaderCullDistance,
+                        shaderFloat64,
+                        shaderInt64,
+                        shaderInt16,
+                        shaderResourceResidency,
+                        shaderResourceMinLod,
+                        sparseBinding,
+                        sparseResidencyBuffer,
+                        sparseResidencyImage2D,
+                        sparseResidencyImage3D,
+                        sparseResidency2Samples,
+                        sparseResidency4Samples,
+                        sparseResidency8Samples,
+                        sparseResidency16Samples,
+                        sparseResidencyAliased,
+                        variableMultisampleRate,
+                        inheritedQueries,
+                    ]
+                ),
+            ),
+            (
+                "VkPhysicalDeviceVulkan11Features",
+                feature_bits!(
+                    self.vulkan11,
+                    [
+                        storageBuffer16BitAccess,
+                        uniformAndStorageBuffer16BitAccess,
+                        storagePushConstant16,
+                        storageInputOutput16,
+                        multiview,
+                        multiviewGeometryShader,
+                        multiviewTessellationShader,
+                        variablePointersStorageBuffer,
+                        variablePointers,
+                        protectedMemory,
+                        samplerYcbcrConversion,
+                        shaderDrawParameters,
+                    ]
+                ),
+            ),
+            (
+                "VkPhysicalDeviceVulkan12Features",
+                feature_bits!(
+                    self.vulkan12,
+                    [
+                        samplerMirrorClampToEdge,
+                        drawIndirectCount,
+                        storageBuffer8BitAccess,
+                        uniformAndStorageBuffer8BitAccess,
+                        storagePushConstant8,
+                        shaderBufferInt64Atomics,
+                        shaderSharedInt64Atomics,
+                        shaderFloat16,
+                        shaderInt8,
+                        descriptorIndexing,
+                        shaderInputAttachmentArrayDynamicIndexing,
+                        shaderUniformTexelBufferArrayDynamicIndexing,
+                        shaderStorageTexelBufferArrayDynamicIndexing,
+                        shaderUniformBufferArrayNonUniformIndexing,
+                        shaderSampledImageArrayNonUniformIndexing,
+                        shaderStorageBufferArrayNonUniformIndexing,
+                        shaderStorageImageArrayNonUniformIndexing,
+                        shaderInputAttachmentArrayNonUniformIndexing,
+                        shaderUniformTexelBufferArrayNonUniformIndexing,
+                        shaderStorageTexelBufferArrayNonUniformIndexing,
+                        descriptorBindingUniformBufferUpdateAfterBind,
+                        descriptorBindingSampledImageUpdateAfterBind,
+                        descriptorBindingStorageImageUpdateAfterBind,
+                        descriptorBindingStorageBufferUpdateAfterBind,
+                        descriptorBindingUniformTexelBufferUpdateAfterBind,
+                        descriptorBindingStorageTexelBufferUpdateAfterBind,
+                        descriptorBindingUpdateUnusedWhilePending,
+                        descriptorBindingPartiallyBound,
+                        descriptorBindingVariableDescriptorCount,
+                        runtimeDescriptorArray,
+                        samplerFilterMinmax,
+                        scalarBlockLayout,
+                        imagelessFramebuffer,
+                        uniformBufferStandardLayout,
+                        shaderSubgroupExtendedTypes,
+                        separateDepthStencilLayouts,
+                        hostQueryReset,
+                        timelineSemaphore,
+                        bufferDeviceAddress,
+                        bufferDeviceAddressCaptureReplay,
+                        bufferDeviceAddressMultiDevice,
+                        vulkanMemoryModel,
+                        vulkanMemoryModelDeviceScope,
+                        vulkanMemoryModelAvailabilityVisibilityChains,
+                        shaderOutputViewportIndex,
+                        shaderOutputLayer,
+                        subgroupBroadcastDynamicId,
+                    ]
+                ),
+            ),
+            (
+                "VkPhysicalDeviceVulkan13Features",
+                feature_bits!(
+                    self.vulkan13,
+                    [
+                        robustImageAccess,
+                        inlineUniformBlock,
+                        descriptorBindingInlineUniformBlockUpdateAfterBind,
+                        pipelineCreationCacheControl,
+                        privateData,
+                        shaderDemoteToHelperInvocation,
+                        shaderTerminateInvocation,
+                        subgroupSizeControl,
+                        computeFullSubgroups,
+                        synchronization2,
+                        textureCompressionASTC_HDR,
+                        shaderZeroInitializeWorkgroupMemory,
+                        dynamicRendering,
+                        shaderIntegerDotProduct,
+                        maintenance4,
+                    ]
+                ),
+            ),
+        ]
+    }
+
+    // `required`, plus the `optional` features that are `supported`
+    pub fn negotiate(
+        required: &Self,
+        optional: &Self,
+        supported: &Self,
+    ) -> Result<Self, DeviceError> {
+        let mut enabled = *required;
+        let mut optional = *optional;
+        let mut supported = *supported;
+
+        let members = enabled
+            .members()
+            .into_iter()
+            .zip(optional.members().into_iter().zip(supported.members()));
+        for ((structure, enabled), ((_, optional), (_, supported))) in members {
+            let bits = enabled.into_iter().zip(optional.into_iter().zip(supported));
+            for ((member, bit), ((_, optional), (_, supported))) in bits {
+                if *bit == VK_TRUE && *supported != VK_TRUE {
+                    return Err(DeviceError::MissingFeature(structure, member));
+                }
+                if *optional == VK_TRUE && *supported == VK_TRUE {
+                    *bit = VK_TRUE;
+                }
+            }
+        }
+
+        Ok(enabled)
+    }
+}
+
+///
+/// Builder of `VulkanHandler`, the logical device with its queues and command pools
+///
+/// Required extensions and features fail `build` when the physical device lacks them,
+/// optional ones are skipped. `VulkanHandler::enabled_extensions` and
+/// `VulkanHandler::enabled_features` tell what ended up enabled.
+/// Without `physical_device` the best device with the queues, extensions and core features is picked.
+///
+/// ```ignore
+/// let mut anisotropy = DeviceFeatures::new();
+/// anisotropy.core.samplerAnisotropy = VK_TRUE;
+///
+/// let handler = DeviceBuilder::new(ctx.clone())
+///     .queue(QueueType::computes, &[1.0])
+///     .optional_extension("VK_EXT_memory_budget")
+///     .optional_features(anisotropy)
+///     .build()?;
+/// if handler.enabled_features.core.samplerAnisotropy == VK_TRUE {
+///     // ...
+/// }
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct DeviceBuilder {
+    context: Arc<Context>,
+    physical_device: Option<VkPhysicalDevice>,
+    queues: Vec<(QueueType, Vec<f32>)>,
+    required_extensions: Vec<String>,
+    optional_extensions: Vec<String>,
+    required_features: DeviceFeatures,
+    optional_features: DeviceFeatures,
+}
+
+impl DeviceBuilder {
+    pub fn new(context: Arc<Context>) -> Self {
+        Self {
+            context,
+            physical_device: None,
+            queues: vec![],
+            required_extensions: vec![],
+            optional_extensions: vec![],
+            required_features: DeviceFeatures::new(),
+            optional_features: DeviceFeatures::new(),
+        }
+    }
+
+    // e.g. from a `PhysicalDeviceSelector` with a surface
+    pub fn physical_device(mut self, physical_device: VkPhysicalDevice) -> Self {
+        self.physical_device = Some(physical_device);
+        self
+    }
+
+    // one queue per priority, see `QueuePlan`
+    pub fn queue(mut self, queue_type: QueueType, priorities: &[f32]) -> Self {
+        self.queues.push((queue_type, priorities.to_vec()));
+        self
+    }
+
+    // required extension
+    pub fn extension(mut self, name: &str) -> Self {
+        self.required_extensions.push(name.to_string());
+        self
+    }
+
+    pub fn optional_extension(mut self, name: &str) -> Self {
+        self.optional_extensions.push(name.to_string());
+        self
+    }
+
+    // required features, every VK_TRUE member
+    pub fn features(mut self, features: DeviceFeatures) -> Self {
+        self.required_features = features;
+        self
+    }
+
+    pub fn optional_features(mut self, features: DeviceFeatures) -> Self {
+        self.optional_features = features;
+        self
+    }
+
+    pub fn build(self) -> anyhow::Result<VulkanHandler> {
+        let physical_device = match self.physical_device {
+            Some(physical_device) => physical_device,
+            None => {
+                let selector = self.queues.iter().fold(
+                    PhysicalDeviceSelector::new(&self.context),
+                    |selector, queue| selector.queue(queue.0.queue_flags()),
+                );
+                self.required_extensions
+                    .iter()
+                    .fold(selector, |selector, name| selector.extension(name))
+                    .features(self.required_features.core)
+                    .select()?
+            }
+        };
+
+        // the newer structures need both the instance and the device at their version
+        let api_version = self
+            .context
+            .api_version
+            .min(physical_device.get_properties().apiVersion);
+
+        let enabled_extensions = context::select(
+            &physical_device.enumerate_device_extension_names()?,
+            &self.required_extensions,
+            &self.optional_extensions,
+            DeviceError::MissingExtension,
+        )?;
+        let enabled_features = DeviceFeatures::negotiate(
+            &self.required_features,
+            &self.optional_features,
+            &DeviceFeatures::supported(physical_device, api_version),
+        )?;
+
+        let demands: Vec<(QueueType, &[f32])> = self
+            .queues
+            .iter()
+            .map(|(queue_type, priorities)| (*queue_type, priorities.as_slice()))
+            .collect();
+        let queue_plan = QueuePlan::new(&physical_device.get_queue_family_properties(), &demands)?;
+
+        let device_queue_create_infos: Vec<VkDeviceQueueCreateInfo> = queue_plan
+            .families
+            .iter()
+            .map(|family| {
+                VkDeviceQueueCreateInfoBuilder::new()
+                    .queue_family_index(family.family_index)
+                    .queue_count(family.priorities.len() as u32)
+                    .p_queue_priorities(family.priorities.as_ptr())
+                    .build()
+            })
+            .collect();
+
+        // careful to CString lifetime
+        let extensions = enabled_extensions
+            .iter()
+            .map(|name| CString::new(name.as_str()))
+            .collect::<Result<Vec<CString>, _>>()?;
+        let pp_extensions: Vec<*const c_char> = extensions.iter().map(|e| e.as_ptr()).collect();
+
+        let device_create_info = VkDeviceCreateInfoBuilder::new()
+            .queue_create_info_count(device_queue_create_infos.len() as u32)
+            .p_queue_create_infos(device_queue_create_infos.as_ptr())
+            .enabled_extension_count(pp_extensions.len() as u32)
+            .pp_enabled_extension_names(pp_extensions.as_ptr());
+
+        // 1.0 has no VkPhysicalDeviceFeatures2, the core features go to pEnabledFeatures
+        let mut features = enabled_features;
+        let device = if api_version >= ApiVersion::V1_1.raw() {
+            features.with_chain(api_version, |features2| {
+                physical_device.create_device(
+                    &*device_create_info.push_next(features2).build_chained(),
+                    None,
+                )
+            })?
+        } else {
+            physical_device.create_device(
+                &device_create_info
+                    .p_enabled_features(&features.core)
+                    .build(),
+                None,
+            )?
+        };
+
+        let mut queues = HashMap::new();
+        for family in &queue_plan.families {
+            for i in 0..family.priorities.len() as u32 {
+                queues.insert(
+                    (family.family_index, i),
+                    device.get_queue(family.family_index, i),
+                );
+            }
+        }
+
+        // from here on a failure drops the handler, destroying the pools created so far and the device
+        let mut handler = VulkanHandler {
+            context: self.context,
+            physical_device,
+            device,
+            command_pools: vec![],
+            queues,
+            queue_plan,
+            enabled_extensions,
+            enabled_features,
+        };
+
+        // command pool
+        for family_index in handler
+            .queue_plan
+            .families
+            .iter()
+            .map(|family| family.family_index)
+        {
+            let command_pool_create_info = VkCommandPoolCreateInfoBuilder::new()
+                .queue_family_index(family_index)
+                .build();
+            let command_pool = device.create_command_pool(&command_pool_create_info, None)?;
+            handler.command_pools.push(command_pool);
+        }
+
+        Ok(handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiate_names_the_missing_member() {
+        let mut supported = DeviceFeatures::new();
+        supported.core.samplerAnisotropy = VK_TRUE;
+        supported.vulkan12.timelineSemaphore = VK_TRUE;
+
+        let mut optional = DeviceFeatures::new();
+        optional.core.samplerAnisotropy = VK_TRUE;
+        optional.vulkan13.dynamicRendering = VK_TRUE;
+        let mut required = DeviceFeatures::new();
+        required.vulkan12.timelineSemaphore = VK_TRUE;
+
+        let enabled = DeviceFeatures::negotiate(&required, &optional, &supported).unwrap();
+        assert_eq!(enabled.core.samplerAnisotropy, VK_TRUE);
+        assert_eq!(enabled.vulkan12.timelineSemaphore, VK_TRUE);
+        assert_eq!(enabled.vulkan13.dynamicRendering, VK_FALSE);
+
+        required.vulkan13.maintenance4 = VK_TRUE;
+        let missing = DeviceFeatures::negotiate(&required, &optional, &supported);
+        assert!(matches!(
+            missing,
+            Err(DeviceError::MissingFeature(
+                "VkPhysicalDeviceVulkan13Features",
+                "maintenance4"
+            ))
+        ));
+    }
+}
@@ -4,11 +4,39 @@
 #![allow(unused)]
 
 use std::any::{type_name, Any};
+use std::sync::{Arc, OnceLock};
 
 include!("vkstruct.rs");
+include!("vkerror.rs");
+include!("vkflags.rs");
 include!("vktraits.rs");
+// vkCmdCall!, before the modules so that vkCmdBlock! finds it inside the crate too
+include!(concat!(env!("OUT_DIR"), "/vk_commands.rs"));
 
+pub mod context;
+pub mod debug;
+pub mod device;
+pub mod loader;
 pub mod memory;
+pub mod owned;
+pub mod physical_device;
+pub mod presentation;
+pub mod queue;
+pub mod tracking;
+
+pub use context::*;
+pub use debug::*;
+pub use device::*;
+pub use loader::*;
+pub use owned::*;
+pub use physical_device::*;
+pub use queue::*;
+pub use tracking::*;
+
+#[cfg(feature = "graphics")]
+pub use raw_window_handle;
+#[cfg(feature = "graphics")]
+use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
 
 pub fn vk_assert(result: VkResult) {
     assert!(result == VkResult::VK_SUCCESS, "VkResult: {:?}", result);
@@ -43,583 +71,279 @@ pub mod func_static {
     }
 
     ///
-    /// vulkan command block roles
+    /// vulkan command block roles, parsed by the `shader` proc macro.
+    /// The commands expand to the `vkCmdCall!` arms of the registry (vk_commands.rs)
     ///
-    #[macro_export]
-    macro_rules! vkCmdBlock {
-
-    //
-    // Parse the Vulkan Commands: the top (starting point) of parser
-    //
-    // * 'THIS' - indicator and identificator for starting cmd block
-    // * 'cmd' - command buffer instance
-    // * 'function' - command function for command buffer
-    (THIS $cmd:expr; $function:ident($($args:expr),*);  $($tail:tt)*) => {
-
-        let begin_info = VkCommandBufferBeginInfoBuilder::new()
-        .flags(0)
-        .build();
-
-        unsafe {
-            vk_assert(vkBeginCommandBuffer($cmd, &begin_info));
-
-            vkCmdBlock!(@inner $cmd, $function($($args),*););
-            vkCmdBlock!(@tt_recursion $cmd, $($tail)*);
-
-            vk_assert(vkEndCommandBuffer($cmd));
-        }
-    };
-
-    // * 'cmd' - command buffer instance
-    // * 'let lv0 = rv0' - pre declarations
-    (THIS $cmd:expr; let $lv:ident = $rv:expr; $($tail:tt)* ) => {
-
-        let begin_info = VkCommandBufferBeginInfoBuilder::new()
-            .flags(0)
-            .build();
-
-        unsafe {
-            vk_assert(vkBeginCommandBuffer($cmd, &begin_info));
-
-            let $lv= $rv;
-            vkCmdBlock!(@tt_recursion $cmd, $($tail)*);
-
-            vk_assert(vkEndCommandBuffer($cmd));
-        }
-    };
-
-    //
-    // tt recursive parser for the function call
-    //
-    (@tt_recursion $cmd:expr, $function:ident($($args:expr),*); $($tail:tt)*) => {
-        vkCmdBlock!(@inner $cmd, $function($($args),*););
-        vkCmdBlock!(@tt_recursion $cmd, $($tail)*);
-    };
-
-    // declaration
-    (@tt_recursion $cmd:expr, let $lv0:ident = $rv0:expr; $($tail:tt)*) => {
-        let $lv0 = $rv0;
-        vkCmdBlock!(@tt_recursion $cmd, $($tail)*);
-    };
-
-    // empty
-    (@tt_recursion $cmd:expr,) => {};
-
-    //
-    // Parse the Vulkan All Commands
-    //
-    // * '@inner' - identifier for inner macro
-    // * 'cmd' - command buffer instance
-    // * 'function' - command function for command buffer
-    (@inner $cmd:expr, $function:ident($($args:expr),*);) => {
-
-        macro_rules! inner {
-            (BIND_DESCRIPTOR_SETS(
-                $pipeline_bind_point:expr,
-                $layout:expr,
-                $first_set:expr,
-                $descriptor_set_count:expr,
-                $p_descriptor_sets:expr,
-                $dynamic_offset_count:expr,
-                $p_dynamic_offsets:expr
-            )) => {
-                vkCmdBindDescriptorSets(
-                    $cmd,
-                    $pipeline_bind_point,
-                    $layout,
-                    $first_set,
-                    $descriptor_set_count,
-                    $p_descriptor_sets,
-                    $dynamic_offset_count,
-                    $p_dynamic_offsets
-                );
-            };
-
-            (BIND_PIPELINE($pipeline_bind_point:expr, $pipeline:expr)) => {
-                vkCmdBindPipeline(
-                    $cmd,
-                    $pipeline_bind_point,
-                    $pipeline,
-                );
-            };
-
-            (BIND_VERTEX_BUFFERS($first_binding:expr, $binding_count:expr, $p_buffers:expr, $p_offsets:expr)) => {
-                vkCmdBindVertexBuffers(
-                    $cmd,
-                    $first_binding,
-                    $binding_count,
-                    $p_buffers,
-                    $p_offsets,
-                );
-            };
-
-            (BIND_INDEX_BUFFER($buffer:expr, $size:expr, $index_type:expr)) => {
-                vkCmdBindIndexBuffer(
-                    $cmd,
-                    $buffer,
-                    $size,
-                    $index_type,
-                );
-            };
-
-
-            (COPY_BUFFER($source:expr, $target:expr, $num:expr, $buffer_copy:expr)) => {
-                vkCmdCopyBuffer($cmd, $source, $target, $num, $buffer_copy);
-            };
-
-            (COPY_BUFFER_TO_IMAGE($buffer:expr, $image:expr, $image_layout:expr, $region_count:expr, $p_regions:expr)) => {
-                vkCmdCopyBufferToImage($cmd, $buffer, $image, $image_layout, $region_count, $p_regions);
-            };
-
-            (DISPATCH(
-                $group_count_x:expr,
-                $group_count_y:expr,
-                $group_count_z:expr)) => {
-                    vkCmdDispatch(
-                        $cmd,
-                        $group_count_x, $group_count_y, $group_count_z
-                    );
-            };
-
-            (DRAW(
-                $vertex_count:expr,
-                $instance_count:expr,
-                $first_vertex:expr,
-                $first_instance:expr)) => {
-                    vkCmdDraw(
-                        $cmd,
-                        $vertex_count,
-                        $instance_count,
-                        $first_vertex,
-                        $first_instance
-                    );
-            };
-
-            (DRAW_INDEXED(
-                $index_count:expr,
-                $instance_count:expr,
-                $first_index:expr,
-                $vertex_offset:expr,
-                $first_instance:expr
-            )) => {
-                vkCmdDrawIndexed(
-                    $cmd,
-                    $index_count,
-                    $instance_count,
-                    $first_index,
-                    $vertex_offset,
-                    $first_instance,
-                );
-            };
-
-            (BEGIN_RENDER_PASS(
-                $render_pass_begin_info:expr,
-                $vk_subpass_contents:expr
-            )) => {
-                vkCmdBeginRenderPass(
-                    $cmd,
-                    $render_pass_begin_info,
-                    $vk_subpass_contents
-                );
-            };
-
-            (END_RENDER_PASS()) => {
-                vkCmdEndRenderPass(
-                    $cmd
-                );
-            };
-
-            (PUSH_CONSTANT(
-                $layout: expr,
-                $stageFlags: expr,
-                $offset: expr,
-                $size: expr,
-                $pValues: expr
-            )) => {
-                vkCmdPushConstants(
-                    $cmd, $layout, $stageFlags, $offset, $size, $pValues
-                );
-            };
-
-            (PIPELINE_BARRIER(
-                $src_stage_mask:expr,
-                $dst_stage_mask:expr,
-                $dependency_flags:expr,
-                $memory_barrier_count:expr,
-                $p_memory_barriers:expr,
-                $buffer_memory_barrier_count:expr,
-                $p_buffer_memory_barriers:expr,
-                $image_memory_barrier_count:expr,
-                $p_image_memory_barriers:expr)) => {
-
-                vkCmdPipelineBarrier(
-                    $cmd,
-                    $src_stage_mask.try_into().unwrap(),
-                    $dst_stage_mask.try_into().unwrap(),
-                    $dependency_flags,
-                    $memory_barrier_count,
-                    $p_memory_barriers,
-                    $buffer_memory_barrier_count,
-                    $p_buffer_memory_barriers,
-                    $image_memory_barrier_count,
-                    $p_image_memory_barriers
-                );
-            };
-
-
-        } // the end of macro_rules! "inner"
-        inner!($function($($args),*));
-    };
-}
+    pub use shader::vkCmdBlock;
 
     #[macro_export]
     macro_rules! vkMakeBind {
         () => {};
     }
 
-    pub use vkCmdBlock;
     pub use vkMakeBind;
     pub use vk_instantiate;
 } // the end of module
 
+pub use func_static::vkCmdBlock;
+
 //
 // higher-level wrapper
 //
 
-const EXTENTION: &[u8] = b"VK_EXT_debug_report\nVK_KHR_surface\nVK_KHR_win32_surface";
-
 #[derive(Debug)]
 pub struct Context {
     pub instance: VkInstance,
     pub physical_devices: Vec<VkPhysicalDevice>,
+    // see `ContextBuilder::debug_messenger`
+    pub debug_messenger: Option<DebugMessenger>,
+    // no window system, surfaces come from `create_headless_surface_ext`
+    pub headless: bool,
+    pub api_version: u32,
+    // what `ContextBuilder` actually enabled, optional names included
+    pub enabled_layers: Vec<String>,
+    pub enabled_extensions: Vec<String>,
 }
 
-impl Context {
-    pub fn new() -> Self {
-        let mut instance = vk_instantiate!(VkInstance);
-        let mut physical_devices = vec![];
+// instance level commands are externally synchronized on the VkInstance only at creation and destruction
+unsafe impl Send for Context {}
+unsafe impl Sync for Context {}
 
-        // instance
-        unsafe {
-            let app_name = CString::new("vkcholesky").unwrap();
-            let ref_app_name = &app_name;
-            let eng_name = CString::new("No engine").unwrap();
-            let ref_eng_name = &eng_name;
-
-            let app_info = VkApplicationInfo {
-                sType: VK_STRUCTURE_TYPE_APPLICATION_INFO,
-                pNext: null(),
-                pApplicationName: ref_app_name.as_ptr(),
-                applicationVersion: make_version(1, 0, 0),
-                pEngineName: ref_eng_name.as_ptr(),
-                engineVersion: make_version(1, 0, 0),
-                apiVersion: make_version(1, 0, 0),
-            };
-
-            // careful to CString lifetime
-            let layers = CString::new("VK_LAYER_KHRONOS_validation").unwrap();
-            let lunarg_layers = CString::new("VK_LAYER_LUNARG_standard_validation").unwrap();
-            let ref_layers = &layers;
-            let pp_layers = vec![ref_layers.as_ptr(), lunarg_layers.as_ptr()];
-            //@@todo automatical extension
-            let extensions = CString::new("VK_EXT_debug_report").unwrap();
-            let surf_extensions = CString::new("VK_KHR_surface").unwrap();
-            let win32_extensions = CString::new("VK_KHR_win32_surface").unwrap();
-            let ref_extensions = &extensions;
-            let pp_extensions = vec![
-                ref_extensions.as_ptr(),
-                surf_extensions.as_ptr(),
-                win32_extensions.as_ptr(),
-            ];
-
-            let mut count: u32 = 10;
-            let mut layer_prop = VkLayerProperties {
-                layerName: [0; 256],
-                specVersion: 0,
-                implementationVersion: 0,
-                description: [0; 256],
-            };
-
-            let mut instance_layer_prop = vec![layer_prop; count as usize];
-            vkEnumerateInstanceLayerProperties(&mut count, instance_layer_prop.as_mut_ptr());
-            let layers: Vec<*const i8> = instance_layer_prop
-                .iter()
-                .map(|x| x.layerName.as_ptr())
-                .collect();
-
-            let instance_create_info = VkInstanceCreateInfo {
-                sType: VK_STRUCTURE_TYPE_INSTANCE_CREATE_INFO,
-                pNext: null(),
-                flags: 0,
-                pApplicationInfo: &app_info,
-                enabledLayerCount: 1,
-                ppEnabledLayerNames: pp_layers.as_ptr(),
-                enabledExtensionCount: pp_extensions.len() as u32,
-                ppEnabledExtensionNames: pp_extensions.as_ptr(),
-            };
-            vk_assert(vkCreateInstance(
-                &instance_create_info,
-                null(),
-                &mut instance,
-            ));
-
-            let mut count: u32 = 3;
-            let mut layer_prop = VkLayerProperties {
-                layerName: [0; 256],
-                specVersion: 0,
-                implementationVersion: 0,
-                description: [0; 256],
-            };
-
-            let mut instance_layer_prop = vec![layer_prop; count as usize];
-            vkEnumerateInstanceLayerProperties(&mut count, instance_layer_prop.as_mut_ptr());
-        }
-        // phyiscal device
-        unsafe {
-            let mut device_count = 0 as u32;
-            vk_assert(vkEnumeratePhysicalDevices(
-                instance,
-                &mut device_count,
-                null_mut(),
-            ));
-            physical_devices = vec![vk_instantiate!(VkPhysicalDevice); device_count as usize];
-            vk_assert(vkEnumeratePhysicalDevices(
-                instance,
-                &mut device_count,
-                physical_devices.as_mut_ptr(),
-            ));
-        }
-
-        Self {
-            instance: instance,
-            physical_devices: physical_devices,
-        }
+impl Context {
+    // validation messages into `log` when available, every compiled-in window system
+    pub fn new() -> anyhow::Result<Self> {
+        ContextBuilder::new()
+            .optional_layer("VK_LAYER_KHRONOS_validation")
+            .debug_messenger(DebugMessengerBuilder::new())
+            .build()
     }
 
-    pub fn get_phyiscal_device_properties(&self) -> VkPhysicalDeviceProperties {
-        let mut physical_device_properties = VkPhysicalDeviceProperties::default();
-
-        unsafe {
-            vkGetPhysicalDeviceProperties(
-                self.physical_devices[0],
-                &mut physical_device_properties,
-            );
-        }
+    ///
+    /// Context without any window system, for servers and CI (e.g. lavapipe)
+    ///
+    /// Enables `VK_EXT_headless_surface` instead of the win32/xlib/xcb/wayland extensions
+    /// and fails with `ContextError::MissingExtension` when the driver does not expose it.
+    ///
+    pub fn headless() -> anyhow::Result<Self> {
+        ContextBuilder::new()
+            .optional_layer("VK_LAYER_KHRONOS_validation")
+            .debug_messenger(DebugMessengerBuilder::new())
+            .headless(true)
+            .build()
+    }
 
-        physical_device_properties
+    pub fn builder() -> ContextBuilder {
+        ContextBuilder::new()
     }
 
-    pub fn get_physical_device_memory_properties(&self) -> VkPhysicalDeviceMemoryProperties {
-        let mut mem_prop = VkPhysicalDeviceMemoryProperties {
-            memoryTypeCount: 0,
-            memoryTypes: [VkMemoryType {
-                propertyFlags: 0,
-                heapIndex: 0,
-            }; 32],
-            memoryHeapCount: 0,
-            memoryHeaps: [VkMemoryHeap { size: 0, flags: 0 }; 16],
-        };
+    pub fn is_layer_enabled(&self, name: &str) -> bool {
+        self.enabled_layers.iter().any(|l| l == name)
+    }
 
-        unsafe {
-            vkGetPhysicalDeviceMemoryProperties(self.physical_devices[0], &mut mem_prop);
-        }
-        mem_prop
+    pub fn is_extension_enabled(&self, name: &str) -> bool {
+        self.enabled_extensions.iter().any(|e| e == name)
     }
 
-    pub fn get_physical_device_surface_support_khr(
+    #[cfg(all(target_os = "windows", feature = "graphics"))]
+    // the create info is passed on to vulkan as is
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn create_win32_surface_khr(
         &self,
-        queue_family_index: u32,
-        surface: VkSurfaceKHR,
-    ) -> VkBool32 {
-        let mut supported = VK_FALSE;
+        win32_surface_create_info: *const VkWin32SurfaceCreateInfoKHR,
+        p_allocator: Option<*const VkAllocationCallbacks>,
+    ) -> Result<VkSurfaceKHR, VkError> {
+        let mut surface = vk_instantiate!(VkSurfaceKHR);
         unsafe {
-            vk_assert(vkGetPhysicalDeviceSurfaceSupportKHR(
-                self.physical_devices[0],
-                queue_family_index,
-                surface,
-                &mut supported,
-            ))
+            vk_check(vkCreateWin32SurfaceKHR(
+                self.instance,
+                win32_surface_create_info,
+                p_allocator.unwrap_or(null()),
+                &mut surface,
+            ))?;
         }
-        supported
+        Ok(surface)
     }
 
-    pub fn get_physical_device_surface_capabilities_khr(
+    #[cfg(feature = "xlib")]
+    // the create info is passed on to vulkan as is
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn create_xlib_surface_khr(
         &self,
-        surface: &VkSurfaceKHR,
-    ) -> VkSurfaceCapabilitiesKHR {
-        let null_extent = VkExtent2D {
-            width: 0,
-            height: 0,
-        };
-
-        let mut surface_capabilities_khr = VkSurfaceCapabilitiesKHR {
-            minImageCount: 0,
-            maxImageCount: 0,
-            currentExtent: null_extent,
-            minImageExtent: null_extent,
-            maxImageExtent: null_extent,
-            maxImageArrayLayers: 0,
-            supportedTransforms: 0,
-            currentTransform: 0,
-            supportedCompositeAlpha: 0,
-            supportedUsageFlags: 0,
-        };
-
+        xlib_surface_create_info: *const VkXlibSurfaceCreateInfoKHR,
+        p_allocator: Option<*const VkAllocationCallbacks>,
+    ) -> Result<VkSurfaceKHR, VkError> {
+        let mut surface = vk_instantiate!(VkSurfaceKHR);
         unsafe {
-            vkGetPhysicalDeviceSurfaceCapabilitiesKHR(
-                self.physical_devices[0],
-                *surface,
-                &mut surface_capabilities_khr,
-            );
+            vk_check(vkCreateXlibSurfaceKHR(
+                self.instance,
+                xlib_surface_create_info,
+                p_allocator.unwrap_or(null()),
+                &mut surface,
+            ))?;
         }
-
-        surface_capabilities_khr
+        Ok(surface)
     }
 
-    pub fn get_physical_device_surface_formats_khr(
+    #[cfg(feature = "xcb")]
+    // the create info is passed on to vulkan as is
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn create_xcb_surface_khr(
         &self,
-        surface: &VkSurfaceKHR,
-    ) -> Vec<VkSurfaceFormatKHR> {
-        let mut count = 0;
-        unsafe {
-            vkGetPhysicalDeviceSurfaceFormatsKHR(
-                self.physical_devices[0],
-                *surface,
-                &mut count,
-                null_mut(),
-            );
-        }
-
-        let surface_format_dummy = VkSurfaceFormatKHR {
-            format: VkFormat::VK_FORMAT_UNDEFINED,
-            colorSpace: 0,
-        };
-        let mut surface_formats_khr: Vec<VkSurfaceFormatKHR> =
-            vec![surface_format_dummy.clone(); count as usize];
+        xcb_surface_create_info: *const VkXcbSurfaceCreateInfoKHR,
+        p_allocator: Option<*const VkAllocationCallbacks>,
+    ) -> Result<VkSurfaceKHR, VkError> {
+        let mut surface = vk_instantiate!(VkSurfaceKHR);
         unsafe {
-            vkGetPhysicalDeviceSurfaceFormatsKHR(
-                self.physical_devices[0],
-                *surface,
-                &mut count,
-                surface_formats_khr.as_mut_ptr(),
-            );
+            vk_check(vkCreateXcbSurfaceKHR(
+                self.instance,
+                xcb_surface_create_info,
+                p_allocator.unwrap_or(null()),
+                &mut surface,
+            ))?;
         }
-
-        surface_formats_khr
+        Ok(surface)
     }
 
-    pub fn get_physical_device_surface_present_modes_khr(
+    #[cfg(feature = "wayland")]
+    // the create info is passed on to vulkan as is
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn create_wayland_surface_khr(
         &self,
-        surface: &VkSurfaceKHR,
-    ) -> Vec<VkPresentModeKHR> {
-        let mut count = 0;
-        unsafe {
-            vkGetPhysicalDeviceSurfacePresentModesKHR(
-                self.physical_devices[0],
-                *surface,
-                &mut count,
-                null_mut(),
-            );
-        }
-
-        let mut surface_present_modes_khr: Vec<VkPresentModeKHR> = vec![0; count as usize];
+        wayland_surface_create_info: *const VkWaylandSurfaceCreateInfoKHR,
+        p_allocator: Option<*const VkAllocationCallbacks>,
+    ) -> Result<VkSurfaceKHR, VkError> {
+        let mut surface = vk_instantiate!(VkSurfaceKHR);
         unsafe {
-            vkGetPhysicalDeviceSurfacePresentModesKHR(
-                self.physical_devices[0],
-                *surface,
-                &mut count,
-                surface_present_modes_khr.as_mut_ptr(),
-            );
+            vk_check(vkCreateWaylandSurfaceKHR(
+                self.instance,
+                wayland_surface_create_info,
+                p_allocator.unwrap_or(null()),
+                &mut surface,
+            ))?;
         }
-        surface_present_modes_khr
+        Ok(surface)
     }
 
-    fn get_physical_device_queue_familly_properties(&self) -> Vec<VkQueueFamilyProperties> {
-        let mut queue_family_count = 0;
-
+    // the create info is passed on to vulkan as is
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn create_headless_surface_ext(
+        &self,
+        headless_surface_create_info: *const VkHeadlessSurfaceCreateInfoEXT,
+        p_allocator: Option<*const VkAllocationCallbacks>,
+    ) -> Result<VkSurfaceKHR, VkError> {
+        let mut surface = vk_instantiate!(VkSurfaceKHR);
         unsafe {
-            vkGetPhysicalDeviceQueueFamilyProperties(
-                self.physical_devices[0],
-                &mut queue_family_count,
-                null_mut(),
-            );
+            vk_check(vkCreateHeadlessSurfaceEXT(
+                self.instance,
+                headless_surface_create_info,
+                p_allocator.unwrap_or(null()),
+                &mut surface,
+            ))?;
         }
+        Ok(surface)
+    }
 
-        let queue_family_properties = VkQueueFamilyProperties {
-            queueFlags: 0,
-            queueCount: 0,
-            timestampValidBits: 0,
-            minImageTransferGranularity: VkExtent3D {
-                width: 0,
-                height: 0,
-                depth: 0,
-            },
-        };
-        let mut queue_familly_properties =
-            vec![queue_family_properties; queue_family_count as usize];
+    // surfaces have to be destroyed before the context, the allocator is passed on to vulkan as is
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn destroy_surface_khr(
+        &self,
+        surface: VkSurfaceKHR,
+        p_allocator: Option<*const VkAllocationCallbacks>,
+    ) {
         unsafe {
-            vkGetPhysicalDeviceQueueFamilyProperties(
-                self.physical_devices[0],
-                &mut queue_family_count,
-                queue_familly_properties.as_mut_ptr(),
-            );
+            vkDestroySurfaceKHR(self.instance, surface, p_allocator.unwrap_or(null()));
         }
-        queue_familly_properties
     }
 
-    #[cfg(all(target_os = "windows", feature = "graphics"))]
-    pub fn create_win32_surface_khr(
+    ///
+    /// Surface for any window exposing raw-window-handle (winit, sdl2, ...)
+    ///
+    /// Window systems without a compiled-in constructor (see the xlib/xcb/wayland features)
+    /// fail with `VkError::ExtensionNotPresent`.
+    ///
+    #[cfg(feature = "graphics")]
+    pub fn create_surface(
         &self,
-        win32_surface_create_info: *const VkWin32SurfaceCreateInfoKHR,
-        p_allocator: Option<*const VkAllocationCallbacks>,
-    ) -> VkSurfaceKHR {
-        let mut surface = vk_instantiate!(VkSurfaceKHR);
-
-        unsafe {
-            if let Some(p) = p_allocator {
-                vkCreateWin32SurfaceKHR(
-                    self.instance,
-                    win32_surface_create_info,
-                    p_allocator.unwrap(),
-                    &mut surface,
-                );
-            } else {
-                vkCreateWin32SurfaceKHR(
-                    self.instance,
-                    win32_surface_create_info,
-                    null(),
-                    &mut surface,
-                );
+        display_handle: RawDisplayHandle,
+        window_handle: RawWindowHandle,
+    ) -> Result<VkSurfaceKHR, VkError> {
+        match (display_handle, window_handle) {
+            #[cfg(target_os = "windows")]
+            (_, RawWindowHandle::Win32(window)) => {
+                let info = VkWin32SurfaceCreateInfoKHRBuilder::new()
+                    .hinstance(window.hinstance as HINSTANCE)
+                    .hwnd(window.hwnd as HWND)
+                    .build();
+                self.create_win32_surface_khr(&info, None)
+            }
+            #[cfg(feature = "xlib")]
+            (RawDisplayHandle::Xlib(display), RawWindowHandle::Xlib(window)) => {
+                let info = VkXlibSurfaceCreateInfoKHRBuilder::new()
+                    .dpy(display.display as *mut Display)
+                    .window(window.window as Window)
+                    .build();
+                self.create_xlib_surface_khr(&info, None)
             }
+            #[cfg(feature = "xcb")]
+            (RawDisplayHandle::Xcb(display), RawWindowHandle::Xcb(window)) => {
+                let info = VkXcbSurfaceCreateInfoKHRBuilder::new()
+                    .connection(display.connection as *mut xcb_connection_t)
+                    .window(window.window as xcb_window_t)
+                    .build();
+                self.create_xcb_surface_khr(&info, None)
+            }
+            #[cfg(feature = "wayland")]
+            (RawDisplayHandle::Wayland(display), RawWindowHandle::Wayland(window)) => {
+                let info = VkWaylandSurfaceCreateInfoKHRBuilder::new()
+                    .display(display.display as *mut wl_display)
+                    .surface(window.surface as *mut wl_surface)
+                    .build();
+                self.create_wayland_surface_khr(&info, None)
+            }
+            _ => Err(VkError::ExtensionNotPresent),
         }
-        surface
     }
 }
 
+// VRX_HEADLESS=1 makes the global context headless
 impl Default for Context {
     fn default() -> Self {
-        Self::new()
+        let context = match std::env::var_os("VRX_HEADLESS") {
+            Some(_) => Self::headless(),
+            None => Self::new(),
+        };
+        context.expect("[vrx] failed to create the vulkan context")
+    }
+}
+
+// the device goes before the instance, `context` is dropped after `drop` returns
+impl Drop for VulkanHandler {
+    fn drop(&mut self) {
+        self.destroy();
     }
 }
 
 impl Drop for Context {
     fn drop(&mut self) {
+        if let Some(debug_messenger) = self.debug_messenger.as_mut() {
+            debug_messenger.destroy(self.instance);
+        }
         unsafe {
             vkDestroyInstance(self.instance, null());
         }
     }
 }
 
-// singleton
-pub fn vulkan_context() -> &'static Context {
-    static mut CTX: MaybeUninit<Context> = MaybeUninit::uninit();
-    static ONCE: Once = Once::new();
-
-    ONCE.call_once(|| unsafe {
-        CTX.as_mut_ptr().write(Context::new());
-    });
-
-    unsafe { &*CTX.as_ptr() }
+///
+/// Process wide context, created with `Context::default()` on first use
+///
+/// Only a convenience for `VulkanHandler::new`, it lives until the process exits.
+/// Own a `Context` (or an `Arc<Context>`) and use `VulkanHandler::with_context`
+/// for several instances or a clean teardown.
+///
+pub fn vulkan_context() -> Arc<Context> {
+    static CONTEXT: OnceLock<Arc<Context>> = OnceLock::new();
+    CONTEXT.get_or_init(|| Arc::new(Context::default())).clone()
 }
 
 ///
@@ -636,13 +360,12 @@ pub struct SwapchainSupport {
 }
 
 impl SwapchainSupport {
-    pub fn new(surface: &VkSurfaceKHR) -> Self {
-        let ctx = vulkan_context();
-        Self {
-            capabilities: ctx.get_physical_device_surface_capabilities_khr(&surface),
-            formats: ctx.get_physical_device_surface_formats_khr(&surface),
-            present_modes: ctx.get_physical_device_surface_present_modes_khr(&surface),
-        }
+    pub fn new(physical_device: VkPhysicalDevice, surface: &VkSurfaceKHR) -> Result<Self, VkError> {
+        Ok(Self {
+            capabilities: physical_device.get_surface_capabilities_khr(*surface)?,
+            formats: physical_device.get_surface_formats_khr(*surface)?,
+            present_modes: physical_device.get_surface_present_modes_khr(*surface)?,
+        })
     }
 
     pub fn get_swapchain_surface_format(
@@ -678,115 +401,103 @@ pub enum QueueType {
     none,
 }
 
+impl QueueType {
+    pub fn queue_flags(&self) -> QueueFlags {
+        match self {
+            QueueType::graphics => QueueFlags::GRAPHICS,
+            QueueType::computes => QueueFlags::COMPUTE,
+            QueueType::transfer => QueueFlags::TRANSFER,
+            QueueType::none => QueueFlags::empty(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct VulkanHandler {
+    pub context: Arc<Context>,
+    pub physical_device: VkPhysicalDevice,
     pub device: VkDevice,
     pub queues: HashMap<(u32, u32), VkQueue>,
+    pub queue_plan: QueuePlan,
+    pub enabled_extensions: Vec<String>,
+    pub enabled_features: DeviceFeatures,
 
-    // all sorted by queue familly indices
-    command_pools: Vec<VkCommandPool>, // command pools per queue family indices
-    queue_types: Vec<QueueType>,       // Vec<u32> map to command pools
+    // one per family of `queue_plan.families`, in the same order
+    command_pools: Vec<VkCommandPool>,
 }
 
 impl VulkanHandler {
-    // pub fn new(demands: Vec<(QueueType, u32)>) -> Self {
-    pub fn new(demands: &[(QueueType, &[f32])]) -> Self {
-        let ctx = vulkan_context();
-
-        let queue_type_map = |queue_type: QueueType| -> VkQueueFlagBits {
-            match queue_type {
-                QueueType::graphics => VK_QUEUE_GRAPHICS_BIT,
-                QueueType::computes => VK_QUEUE_COMPUTE_BIT,
-                QueueType::transfer => VK_QUEUE_TRANSFER_BIT,
-                QueueType::none => 0,
-            }
-        };
+    // on the global `vulkan_context()`
+    pub fn new(demands: &[(QueueType, &[f32])]) -> anyhow::Result<Self> {
+        Self::with_context(vulkan_context(), demands)
+    }
 
-        // queue family index
+    ///
+    /// On the best physical device with the demanded queues,
+    /// `VK_KHR_swapchain` is enabled when supported, see `DeviceBuilder` to require it
+    ///
+    pub fn with_context(
+        context: Arc<Context>,
+        demands: &[(QueueType, &[f32])],
+    ) -> anyhow::Result<Self> {
         demands
-            .to_vec()
-            .sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-
-        // 1.
-        let mut queue_family_properties = ctx.get_physical_device_queue_familly_properties();
-        let queue_family_len = queue_family_properties.len();
-        let mut possible_queue_family_indices: Vec<u32> = (0..queue_family_len as u32).collect();
-
-        let mut find_queue_family_index = |queue_type: QueueType| -> u32 {
-            let queue_flag = queue_type_map(queue_type);
-            let qfi = queue_family_properties
-                .iter()
-                .position(|&x| (x.queueFlags & (queue_flag as VkQueueFlags)) != 0)
-                .unwrap();
-            queue_family_properties.remove(qfi);
-            possible_queue_family_indices.remove(qfi)
-        };
-
-        // device and queue
-        let mut queue_types: Vec<QueueType> = vec![QueueType::none; queue_family_len];
-
-        let device_queue_create_infos: Vec<VkDeviceQueueCreateInfo> = demands
             .iter()
-            .map(|demand| {
-                let qfi = find_queue_family_index(demand.0);
-
-                // store queue type
-                queue_types[qfi as usize] = demand.0;
-
-                // real execution
-                VkDeviceQueueCreateInfoBuilder::new()
-                    .queue_family_index(qfi)
-                    .queue_count(demand.1.len() as u32)
-                    .p_queue_priorities(demand.1.as_ptr())
-                    .build()
+            .fold(DeviceBuilder::new(context), |builder, demand| {
+                builder.queue(demand.0, demand.1)
             })
-            .collect();
-
-        let vk_khr_swapchain = b"VK_KHR_swapchain\0".as_ptr() as *const i8;
-        let extensions = [vk_khr_swapchain];
-        let device_create_info = VkDeviceCreateInfoBuilder::new()
-            .queue_create_info_count(device_queue_create_infos.len() as u32)
-            .p_queue_create_infos(device_queue_create_infos.as_ptr())
-            .enabled_extension_count(extensions.len() as u32)
-            .pp_enabled_extension_names(extensions.as_ptr())
-            .build();
-
-        let device = ctx.physical_devices[0].create_device(&device_create_info, None);
-        let mut queues = HashMap::new();
-        device_queue_create_infos.iter().for_each(|info| {
-            let qfi = info.queueFamilyIndex;
-            for i in 0..info.queueCount {
-                queues.insert((qfi, i), device.get_queue(qfi, i));
-            }
-        });
+            .optional_extension("VK_KHR_swapchain")
+            .build()
+    }
 
-        // command pool
-        let command_pools: Vec<VkCommandPool> = device_queue_create_infos
+    ///
+    /// Handler on a physical device of `context`, picked by a `PhysicalDeviceSelector`
+    /// e.g. with `.surface(surface)` so the device can present to a window
+    ///
+    pub fn with_physical_device(
+        context: Arc<Context>,
+        physical_device: VkPhysicalDevice,
+        demands: &[(QueueType, &[f32])],
+    ) -> anyhow::Result<Self> {
+        demands
             .iter()
-            .map(|info| {
-                let command_pool_create_info = VkCommandPoolCreateInfoBuilder::new()
-                    .queue_family_index(info.queueFamilyIndex)
-                    .build();
-                device.create_command_pool(&command_pool_create_info, None)
+            .fold(DeviceBuilder::new(context), |builder, demand| {
+                builder.queue(demand.0, demand.1)
             })
-            .collect();
-
-        // command pool
-        Self {
-            device,
-            command_pools,
-            queues,
-            queue_types,
-        }
+            .physical_device(physical_device)
+            .optional_extension("VK_KHR_swapchain")
+            .build()
+    }
+
+    pub fn is_extension_enabled(&self, name: &str) -> bool {
+        self.enabled_extensions.iter().any(|e| e == name)
     }
 
+    ///
+    /// Waits for the device, destroys the command pools and then the device
+    ///
+    /// Everything created on the device has to be gone by then, debug builds log what is left
+    /// (see `live_objects`). Called on drop, later calls do nothing.
+    ///
     pub fn destroy(&mut self) {
+        if self.device.is_null() {
+            return;
+        }
+
+        let _ = self.device.wait_idle();
+        for command_pool in self.command_pools.drain(..) {
+            self.device.destroy_command_pool(command_pool, None);
+        }
+        tracking::report_leaks(self.device);
         unsafe {
-            // self.command_pools
-            //     .iter()
-            //     .for_each(|(t, commad_pool)| self.device.destroy_command_pool(*commad_pool, None));
             vkDestroyDevice(self.device, null());
         }
+        self.device = null_mut();
+        self.queues.clear();
+    }
+
+    // objects created on the device and not destroyed yet, always empty in release builds
+    pub fn live_objects(&self) -> Vec<LiveObject> {
+        tracking::live_objects(self.device)
     }
 
     //
@@ -794,14 +505,18 @@ impl VulkanHandler {
         self.command_pools[index]
     }
 
-    // currently ok
-    pub fn get_queue_familly_indices(&self, queue_type: &QueueType) -> Vec<u32> {
-        self.queue_types
+    // pool of the family serving the first `queue_type` queue
+    pub fn command_pool(&self, queue_type: QueueType) -> Option<VkCommandPool> {
+        let (family_index, _) = self.queue_plan.queue(queue_type, 0)?;
+        self.queue_plan
+            .families
             .iter()
-            .enumerate()
-            .filter(|(_, type_)| matches!(type_, queue_type))
-            .map(|(i, _)| i as u32)
-            .collect()
+            .position(|family| family.family_index == family_index)
+            .map(|index| self.command_pools[index])
+    }
+
+    pub fn get_queue_familly_indices(&self, queue_type: &QueueType) -> Vec<u32> {
+        self.queue_plan.family_indices(*queue_type)
     }
 
     //
@@ -811,7 +526,7 @@ impl VulkanHandler {
         index: usize,
         level: VkCommandBufferLevel,
         count: u32,
-    ) -> Vec<VkCommandBuffer> {
+    ) -> Result<Vec<VkCommandBuffer>, VkError> {
         let command_pool = self.get_command_pool(index);
 
         let info = VkCommandBufferAllocateInfoBuilder::new()
@@ -823,6 +538,8 @@ impl VulkanHandler {
         self.device.allocate_command_buffers(&info)
     }
 
+    // `p_command_buffers` is passed on to vulkan as is
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
     pub fn free_commands_buffers(
         &self,
         command_pool: VkCommandPool,
@@ -844,6 +561,12 @@ impl VulkanHandler {
         self.queues.get(&(queue_family_index, index)).unwrap()
     }
 
+    // the `n`th demanded queue of `queue_type`
+    pub fn queue(&self, queue_type: QueueType, n: usize) -> Option<VkQueue> {
+        let (family_index, index) = self.queue_plan.queue(queue_type, n)?;
+        self.queues.get(&(family_index, index)).copied()
+    }
+
     // pub fn queue_wait_idle() {
 
     // };
@@ -855,32 +578,33 @@ impl VulkanHandler {
     pub fn create_resource_binding(
         &self,
         bindings: &[VkDescriptorSetLayoutBinding],
-    ) -> memory::ResourceBinding {
+    ) -> Result<memory::ResourceBinding<'_>, VkError> {
         memory::ResourceBinding::new(bindings, &self.device)
     }
 
     // buffer
-    pub fn create_buffer<'a, T>(
-        &'a self,
+    pub fn create_buffer<T>(
+        &self,
         data: (Option<*const T>, usize),
-        usage: VkBufferUsageFlagBits,
+        usage: impl Into<BufferUsageFlags>,
         flags: VkBufferCreateFlagBits,
         mem_prop_flags: VkMemoryPropertyFlagBits,
-    ) -> anyhow::Result<memory::Buffer<T>> {
+    ) -> anyhow::Result<memory::Buffer<'_, T>> {
         Ok(memory::Buffer::<T>::new(
             data,
             flags,
-            usage,
+            usage.into(),
             mem_prop_flags,
+            self.physical_device,
             &self.device,
-        ))
+        )?)
     }
 
     pub fn create_transfer_src_buffer<T>(
         &self,
         ptr: *const T,
         len: usize,
-    ) -> anyhow::Result<memory::Buffer<T>> {
+    ) -> anyhow::Result<memory::Buffer<'_, T>> {
         self.create_buffer(
             (Some(ptr), len),
             VK_BUFFER_USAGE_TRANSFER_SRC_BIT,
@@ -892,11 +616,11 @@ impl VulkanHandler {
     pub fn create_transfer_dst_buffer<T>(
         &self,
         len: usize,
-        usage: VkBufferUsageFlagBits,
-    ) -> anyhow::Result<memory::Buffer<T>> {
+        usage: impl Into<BufferUsageFlags>,
+    ) -> anyhow::Result<memory::Buffer<'_, T>> {
         self.create_buffer(
             (Some(std::ptr::null::<T>()), len),
-            VK_BUFFER_USAGE_TRANSFER_DST_BIT | usage,
+            BufferUsageFlags::TRANSFER_DST | usage.into(),
             0,
             VK_MEMORY_PROPERTY_DEVICE_LOCAL_BIT,
         )
@@ -908,14 +632,14 @@ impl VulkanHandler {
         data: (Option<*const T>, [u32; dim]),
         mem_prop_flags: VkMemoryPropertyFlagBits,
     ) -> memory::TextureBuilder<'a, T, dim> {
-        memory::TextureBuilder::new(data, &self.device)
+        memory::TextureBuilder::new(data, self.physical_device, &self.device)
     }
 
     pub fn texture_builder_from_path<'a>(
         &'a self,
         path: &'static str,
     ) -> memory::TextureBuilder<'a, u8, 2> {
-        memory::texture_builder_from_path(path, &self.device)
+        memory::texture_builder_from_path(path, self.physical_device, &self.device)
     }
 }
 
@@ -0,0 +1,856 @@
+//
+// runtime loader
+//
+// libvulkan is opened at runtime and every command resolves through
+// vkGetInstanceProcAddr / vkGetDeviceProcAddr into a dispatch table.
+// The free `vk*` functions below keep the names and signatures of the C API,
+// so the trait bindings and vkCmdBlock! call them as before. A command that is not loaded
+// (or called on a handle of no known device) is skipped and returns VK_ERROR_EXTENSION_NOT_PRESENT
+// (VK_ERROR_INITIALIZATION_FAILED) when it has a result.
+//
+use crate::*;
+use paste::paste;
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::LocalKey;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoaderError {
+    LibraryNotFound(String),
+    MissingEntryPoint(&'static str),
+    AlreadyLoaded,
+}
+
+impl std::fmt::Display for LoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoaderError::LibraryNotFound(name) => {
+                write!(f, "[vrx] could not open the vulkan loader ({})", name)
+            }
+            LoaderError::MissingEntryPoint(name) => {
+                write!(f, "[vrx] the vulkan loader does not export {}", name)
+            }
+            LoaderError::AlreadyLoaded => {
+                write!(f, "[vrx] a different vulkan loader has already been opened")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoaderError {}
+
+//
+// platform library
+//
+struct Library(*mut c_void);
+
+unsafe impl Send for Library {}
+unsafe impl Sync for Library {}
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn LoadLibraryA(name: *const c_char) -> *mut c_void;
+    fn GetProcAddress(module: *mut c_void, name: *const c_char) -> *mut c_void;
+}
+
+impl Library {
+    #[cfg(unix)]
+    fn open(name: &str) -> Option<Self> {
+        let name = CString::new(name).ok()?;
+        let handle = unsafe { libc::dlopen(name.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+        (!handle.is_null()).then_some(Library(handle))
+    }
+
+    #[cfg(windows)]
+    fn open(name: &str) -> Option<Self> {
+        let name = CString::new(name).ok()?;
+        let handle = unsafe { LoadLibraryA(name.as_ptr()) };
+        (!handle.is_null()).then_some(Library(handle))
+    }
+
+    #[cfg(unix)]
+    fn symbol(&self, name: &CStr) -> *mut c_void {
+        unsafe { libc::dlsym(self.0, name.as_ptr()) }
+    }
+
+    #[cfg(windows)]
+    fn symbol(&self, name: &CStr) -> *mut c_void {
+        unsafe { GetProcAddress(self.0, name.as_ptr()) }
+    }
+}
+
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios"))))]
+const LIBRARY_NAMES: &[&str] = &["libvulkan.so.1", "libvulkan.so"];
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+const LIBRARY_NAMES: &[&str] = &["libvulkan.1.dylib", "libvulkan.dylib", "libMoltenVK.dylib"];
+#[cfg(windows)]
+const LIBRARY_NAMES: &[&str] = &["vulkan-1.dll"];
+
+//
+// dispatch tables
+//
+macro_rules! dispatch_table {
+    (
+        $(#[$meta:meta])*
+        pub struct $table:ident via $dispatch:ident {
+            manual { $( $manual:ident ),* $(,)? }
+            $(
+                $(#[$attr:meta])*
+                fn $name:ident( $first:ident: $first_ty:ty $(, $arg:ident: $ty:ty )* $(,)? ) $( -> $ret:ty )?;
+            )*
+        }
+    ) => {
+        paste! {
+            $(#[$meta])*
+            #[derive(Clone)]
+            pub struct $table {
+                $( pub $manual: [<PFN_ $manual>], )*
+                $(
+                    $(#[$attr])*
+                    pub $name: [<PFN_ $name>],
+                )*
+            }
+
+            impl $table {
+                pub(crate) unsafe fn load(mut load: impl FnMut(&CStr) -> PFN_vkVoidFunction) -> Self {
+                    Self {
+                        $( $manual: std::mem::transmute::<PFN_vkVoidFunction, [<PFN_ $manual>]>(load(proc_name(concat!(stringify!($manual), "\0")))), )*
+                        $(
+                            $(#[$attr])*
+                            $name: std::mem::transmute::<PFN_vkVoidFunction, [<PFN_ $name>]>(load(proc_name(concat!(stringify!($name), "\0")))),
+                        )*
+                    }
+                }
+            }
+        }
+
+        $(
+            $(#[$attr])*
+            /// # Safety
+            ///
+            /// The valid usage of the Vulkan command applies.
+            #[inline]
+            #[allow(clippy::too_many_arguments)]
+            pub unsafe fn $name($first: $first_ty $(, $arg: $ty)*) $( -> $ret )? {
+                match $dispatch($first, |table| table.$name) {
+                    Some(Some(command)) => command($first $(, $arg)*),
+                    Some(None) => not_loaded(stringify!($name)),
+                    None => unknown_handle(stringify!($name)),
+                }
+            }
+        )*
+    };
+}
+
+#[inline]
+unsafe fn proc_name(name: &'static str) -> &'static CStr {
+    CStr::from_bytes_with_nul_unchecked(name.as_bytes())
+}
+
+// dispatchable handles start with the loader's dispatch pointer,
+// which is shared by an instance and its physical devices, and by a device and its queues and command buffers
+#[inline]
+unsafe fn dispatch_key<T>(handle: *mut T) -> Option<usize> {
+    (!handle.is_null()).then(|| *(handle as *const usize))
+}
+
+// what a command returns when there is nothing to call
+trait Fallback {
+    fn fallback(result: VkResult) -> Self;
+}
+
+impl Fallback for VkResult {
+    fn fallback(result: VkResult) -> Self {
+        result
+    }
+}
+
+impl Fallback for () {
+    fn fallback(_: VkResult) {}
+}
+
+#[cold]
+fn not_loaded<R: Fallback>(name: &'static str) -> R {
+    log::error!(target: "vrx", "[vrx] {} is not loaded, the command is skipped", name);
+    R::fallback(VkResult::VK_ERROR_EXTENSION_NOT_PRESENT)
+}
+
+#[cold]
+fn unknown_handle<R: Fallback>(name: &'static str) -> R {
+    log::error!(target: "vrx", "[vrx] {} called on a handle of no known instance or device, the command is skipped", name);
+    R::fallback(VkResult::VK_ERROR_INITIALIZATION_FAILED)
+}
+
+//
+// tables of the created instances and devices by dispatch key.
+// Every thread keeps the last table it used, so recording into one command buffer
+// takes neither the lock nor a reference count
+//
+struct Registry<T: 'static> {
+    tables: RwLock<Vec<(usize, Arc<T>)>>,
+    // changes with every (un)registration, a cached table of an older generation is looked up again
+    generation: AtomicUsize,
+    cache: &'static LocalKey<RefCell<Option<Cached<T>>>>,
+}
+
+struct Cached<T> {
+    key: usize,
+    generation: usize,
+    table: Arc<T>,
+}
+
+thread_local! {
+    static INSTANCE_CACHE: RefCell<Option<Cached<InstanceTable>>> = const { RefCell::new(None) };
+    static DEVICE_CACHE: RefCell<Option<Cached<DeviceTable>>> = const { RefCell::new(None) };
+}
+
+static ENTRY: Mutex<Option<&'static Entry>> = Mutex::new(None);
+static INSTANCES: Registry<InstanceTable> = Registry::new(&INSTANCE_CACHE);
+static DEVICES: Registry<DeviceTable> = Registry::new(&DEVICE_CACHE);
+
+impl<T> Registry<T> {
+    const fn new(cache: &'static LocalKey<RefCell<Option<Cached<T>>>>) -> Self {
+        Self {
+            tables: RwLock::new(Vec::new()),
+            generation: AtomicUsize::new(0),
+            cache,
+        }
+    }
+
+    fn register(&self, key: usize, table: T) {
+        let mut tables = self.tables.write().unwrap();
+        tables.retain(|(k, _)| *k != key);
+        tables.push((key, Arc::new(table)));
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+
+    fn unregister(&self, key: usize) {
+        self.tables.write().unwrap().retain(|(k, _)| *k != key);
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+
+    fn lookup(&self, key: usize) -> Option<Arc<T>> {
+        self.tables
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, table)| table.clone())
+    }
+
+    // `f` runs on the table of `key`, None for a handle of no registered instance or device
+    fn with<R>(&self, key: usize, f: impl FnOnce(&T) -> R) -> Option<R> {
+        // read before the lookup, a registration in between only makes the next call look up again
+        let generation = self.generation.load(Ordering::Acquire);
+        self.cache.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            match &*cache {
+                Some(cached) if cached.key == key && cached.generation == generation => {}
+                _ => {
+                    let table = self.lookup(key)?;
+                    *cache = Some(Cached {
+                        key,
+                        generation,
+                        table,
+                    });
+                }
+            }
+            cache.as_ref().map(|cached| f(&cached.table))
+        })
+    }
+}
+
+fn entry_table<T, R>(_: T, f: impl FnOnce(&EntryTable) -> R) -> Option<R> {
+    vulkan_entry().ok().map(|entry| f(&entry.table))
+}
+
+fn instance_table<T, R>(handle: *mut T, f: impl FnOnce(&InstanceTable) -> R) -> Option<R> {
+    INSTANCES.with(unsafe { dispatch_key(handle) }?, f)
+}
+
+fn device_table<T, R>(handle: *mut T, f: impl FnOnce(&DeviceTable) -> R) -> Option<R> {
+    DEVICES.with(unsafe { dispatch_key(handle) }?, f)
+}
+
+/// Whether a command of the device a VkDevice, VkQueue or VkCommandBuffer belongs to is loaded,
+/// `is_device_command_loaded(cmd, |table| table.vkCmdBeginRendering.is_some())`
+pub fn is_device_command_loaded<T>(
+    handle: *mut T,
+    loaded: impl FnOnce(&DeviceTable) -> bool,
+) -> bool {
+    device_table(handle, loaded).unwrap_or(false)
+}
+
+/// Commands of the device a VkDevice, VkQueue or VkCommandBuffer belongs to,
+/// e.g. to check that an extension command is loaded
+// only the dispatch key at the start of the handle is read, as by the loader itself
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn device_commands<T>(handle: *mut T) -> Option<Arc<DeviceTable>> {
+    DEVICES.lookup(unsafe { dispatch_key(handle) }?)
+}
+
+///
+/// Opened vulkan loader and its global commands
+///
+pub struct Entry {
+    library: Library,
+    get_instance_proc_addr: PFN_vkGetInstanceProcAddr,
+    pub table: EntryTable,
+}
+
+impl Entry {
+    fn open(name: &str) -> Result<Self, LoaderError> {
+        let library =
+            Library::open(name).ok_or_else(|| LoaderError::LibraryNotFound(name.to_string()))?;
+
+        let get_instance_proc_addr: PFN_vkGetInstanceProcAddr = unsafe {
+            std::mem::transmute::<*mut c_void, PFN_vkGetInstanceProcAddr>(
+                library.symbol(proc_name("vkGetInstanceProcAddr\0")),
+            )
+        };
+        let get_instance_proc_addr_fn = get_instance_proc_addr
+            .ok_or(LoaderError::MissingEntryPoint("vkGetInstanceProcAddr"))?;
+
+        let table = unsafe {
+            EntryTable::load(|name| get_instance_proc_addr_fn(null_mut(), name.as_ptr()))
+        };
+
+        Ok(Self {
+            library,
+            get_instance_proc_addr,
+            table,
+        })
+    }
+
+    fn open_default() -> Result<Self, LoaderError> {
+        if let Ok(name) = std::env::var("VRX_VULKAN_LIBRARY") {
+            return Self::open(&name);
+        }
+
+        let mut error = LoaderError::LibraryNotFound(LIBRARY_NAMES.join(", "));
+        for name in LIBRARY_NAMES {
+            match Self::open(name) {
+                Ok(entry) => return Ok(entry),
+                Err(e @ LoaderError::MissingEntryPoint(_)) => error = e,
+                Err(_) => {}
+            }
+        }
+        Err(error)
+    }
+
+    // `instance` is only handed on to the loader
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn get_instance_proc_addr(&self, instance: VkInstance, name: &CStr) -> PFN_vkVoidFunction {
+        unsafe { (self.get_instance_proc_addr.unwrap())(instance, name.as_ptr()) }
+    }
+}
+
+/// Opens the system loader on first use, `VRX_VULKAN_LIBRARY` overrides the library path
+pub fn vulkan_entry() -> Result<&'static Entry, LoaderError> {
+    let mut entry = ENTRY.lock().unwrap();
+    if let Some(opened) = *entry {
+        return Ok(opened);
+    }
+
+    let opened: &'static Entry = Box::leak(Box::new(Entry::open_default()?));
+    *entry = Some(opened);
+    Ok(opened)
+}
+
+/// Opens a specific loader library, must be called before any other vulkan command
+pub fn load_vulkan_library<P: AsRef<Path>>(path: P) -> Result<&'static Entry, LoaderError> {
+    let mut entry = ENTRY.lock().unwrap();
+    if entry.is_some() {
+        return Err(LoaderError::AlreadyLoaded);
+    }
+
+    let opened: &'static Entry =
+        Box::leak(Box::new(Entry::open(&path.as_ref().to_string_lossy())?));
+    *entry = Some(opened);
+    Ok(opened)
+}
+
+///
+/// Restricts the loader to the given ICD manifests (e.g. lavapipe's `lvp_icd.x86_64.json`)
+///
+/// Must be called before the loader is opened.
+///
+/// # Safety
+///
+/// Sets `VK_DRIVER_FILES` and `VK_ICD_FILENAMES`, no other thread may read or write
+/// the environment meanwhile (see `std::env::set_var`), e.g. call it first thing in `main`.
+///
+pub unsafe fn use_icd_manifests<P: AsRef<Path>>(manifests: &[P]) {
+    let separator = if cfg!(windows) { ";" } else { ":" };
+    let manifests = manifests
+        .iter()
+        .map(|m| m.as_ref().to_string_lossy().into_owned())
+        .collect::<Vec<String>>()
+        .join(separator);
+
+    // VK_ICD_FILENAMES for loaders older than 1.3.207
+    unsafe {
+        std::env::set_var("VK_DRIVER_FILES", &manifests);
+        std::env::set_var("VK_ICD_FILENAMES", &manifests);
+    }
+}
+
+dispatch_table! {
+    /// Commands resolved with a null instance
+    pub struct EntryTable via entry_table {
+        manual { vkCreateInstance }
+
+        fn vkEnumerateInstanceExtensionProperties(pLayerName: *const c_char, pPropertyCount: *mut u32, pProperties: *mut VkExtensionProperties) -> VkResult;
+        fn vkEnumerateInstanceLayerProperties(pPropertyCount: *mut u32, pProperties: *mut VkLayerProperties) -> VkResult;
+        fn vkEnumerateInstanceVersion(pApiVersion: *mut u32) -> VkResult;
+    }
+}
+
+dispatch_table! {
+    /// Commands dispatched on a VkInstance or VkPhysicalDevice
+    pub struct InstanceTable via instance_table {
+        manual { vkDestroyInstance, vkCreateDevice, vkGetDeviceProcAddr }
+
+        fn vkEnumeratePhysicalDevices(instance: VkInstance, pPhysicalDeviceCount: *mut u32, pPhysicalDevices: *mut VkPhysicalDevice) -> VkResult;
+        fn vkGetPhysicalDeviceFeatures(physicalDevice: VkPhysicalDevice, pFeatures: *mut VkPhysicalDeviceFeatures);
+        fn vkGetPhysicalDeviceFormatProperties(physicalDevice: VkPhysicalDevice, format: VkFormat, pFormatProperties: *mut VkFormatProperties);
+        fn vkGetPhysicalDeviceImageFormatProperties(physicalDevice: VkPhysicalDevice, format: VkFormat, type_: VkImageType, tiling: VkImageTiling, usage: VkImageUsageFlags, flags: VkImageCreateFlags, pImageFormatProperties: *mut VkImageFormatProperties) -> VkResult;
+        fn vkGetPhysicalDeviceProperties(physicalDevice: VkPhysicalDevice, pProperties: *mut VkPhysicalDeviceProperties);
+        fn vkGetPhysicalDeviceQueueFamilyProperties(physicalDevice: VkPhysicalDevice, pQueueFamilyPropertyCount: *mut u32, pQueueFamilyProperties: *mut VkQueueFamilyProperties);
+        fn vkGetPhysicalDeviceMemoryProperties(physicalDevice: VkPhysicalDevice, pMemoryProperties: *mut VkPhysicalDeviceMemoryProperties);
+        fn vkEnumerateDeviceExtensionProperties(physicalDevice: VkPhysicalDevice, pLayerName: *const c_char, pPropertyCount: *mut u32, pProperties: *mut VkExtensionProperties) -> VkResult;
+        fn vkEnumerateDeviceLayerProperties(physicalDevice: VkPhysicalDevice, pPropertyCount: *mut u32, pProperties: *mut VkLayerProperties) -> VkResult;
+        fn vkGetPhysicalDeviceSparseImageFormatProperties(physicalDevice: VkPhysicalDevice, format: VkFormat, type_: VkImageType, samples: VkSampleCountFlagBits, usage: VkImageUsageFlags, tiling: VkImageTiling, pPropertyCount: *mut u32, pProperties: *mut VkSparseImageFormatProperties);
+
+        // 1.1
+        fn vkGetPhysicalDeviceFeatures2(physicalDevice: VkPhysicalDevice, pFeatures: *mut VkPhysicalDeviceFeatures2);
+        fn vkGetPhysicalDeviceProperties2(physicalDevice: VkPhysicalDevice, pProperties: *mut VkPhysicalDeviceProperties2);
+        fn vkGetPhysicalDeviceMemoryProperties2(physicalDevice: VkPhysicalDevice, pMemoryProperties: *mut VkPhysicalDeviceMemoryProperties2);
+        fn vkGetPhysicalDeviceQueueFamilyProperties2(physicalDevice: VkPhysicalDevice, pQueueFamilyPropertyCount: *mut u32, pQueueFamilyProperties: *mut VkQueueFamilyProperties2);
+
+        // VK_KHR_surface
+        fn vkDestroySurfaceKHR(instance: VkInstance, surface: VkSurfaceKHR, pAllocator: *const VkAllocationCallbacks);
+        fn vkGetPhysicalDeviceSurfaceSupportKHR(physicalDevice: VkPhysicalDevice, queueFamilyIndex: u32, surface: VkSurfaceKHR, pSupported: *mut VkBool32) -> VkResult;
+        fn vkGetPhysicalDeviceSurfaceCapabilitiesKHR(physicalDevice: VkPhysicalDevice, surface: VkSurfaceKHR, pSurfaceCapabilities: *mut VkSurfaceCapabilitiesKHR) -> VkResult;
+        fn vkGetPhysicalDeviceSurfaceFormatsKHR(physicalDevice: VkPhysicalDevice, surface: VkSurfaceKHR, pSurfaceFormatCount: *mut u32, pSurfaceFormats: *mut VkSurfaceFormatKHR) -> VkResult;
+        fn vkGetPhysicalDeviceSurfacePresentModesKHR(physicalDevice: VkPhysicalDevice, surface: VkSurfaceKHR, pPresentModeCount: *mut u32, pPresentModes: *mut VkPresentModeKHR) -> VkResult;
+
+        // VK_EXT_headless_surface
+        fn vkCreateHeadlessSurfaceEXT(instance: VkInstance, pCreateInfo: *const VkHeadlessSurfaceCreateInfoEXT, pAllocator: *const VkAllocationCallbacks, pSurface: *mut VkSurfaceKHR) -> VkResult;
+
+        // VK_EXT_debug_utils
+        fn vkCreateDebugUtilsMessengerEXT(instance: VkInstance, pCreateInfo: *const VkDebugUtilsMessengerCreateInfoEXT, pAllocator: *const VkAllocationCallbacks, pMessenger: *mut VkDebugUtilsMessengerEXT) -> VkResult;
+        fn vkDestroyDebugUtilsMessengerEXT(instance: VkInstance, messenger: VkDebugUtilsMessengerEXT, pAllocator: *const VkAllocationCallbacks);
+        fn vkSubmitDebugUtilsMessageEXT(instance: VkInstance, messageSeverity: VkDebugUtilsMessageSeverityFlagBitsEXT, messageTypes: VkDebugUtilsMessageTypeFlagsEXT, pCallbackData: *const VkDebugUtilsMessengerCallbackDataEXT);
+
+        // VK_KHR_win32_surface
+        #[cfg(all(target_os = "windows", feature = "graphics"))]
+        fn vkCreateWin32SurfaceKHR(instance: VkInstance, pCreateInfo: *const VkWin32SurfaceCreateInfoKHR, pAllocator: *const VkAllocationCallbacks, pSurface: *mut VkSurfaceKHR) -> VkResult;
+
+        // VK_KHR_xlib_surface
+        #[cfg(feature = "xlib")]
+        fn vkCreateXlibSurfaceKHR(instance: VkInstance, pCreateInfo: *const VkXlibSurfaceCreateInfoKHR, pAllocator: *const VkAllocationCallbacks, pSurface: *mut VkSurfaceKHR) -> VkResult;
+
+        // VK_KHR_xcb_surface
+        #[cfg(feature = "xcb")]
+        fn vkCreateXcbSurfaceKHR(instance: VkInstance, pCreateInfo: *const VkXcbSurfaceCreateInfoKHR, pAllocator: *const VkAllocationCallbacks, pSurface: *mut VkSurfaceKHR) -> VkResult;
+
+        // VK_KHR_wayland_surface
+        #[cfg(feature = "wayland")]
+        fn vkCreateWaylandSurfaceKHR(instance: VkInstance, pCreateInfo: *const VkWaylandSurfaceCreateInfoKHR, pAllocator: *const VkAllocationCallbacks, pSurface: *mut VkSurfaceKHR) -> VkResult;
+    }
+}
+
+dispatch_table! {
+    /// Commands dispatched on a VkDevice, VkQueue or VkCommandBuffer
+    pub struct DeviceTable via device_table {
+        manual { vkDestroyDevice, vkGetDeviceProcAddr }
+
+        fn vkGetDeviceQueue(device: VkDevice, queueFamilyIndex: u32, queueIndex: u32, pQueue: *mut VkQueue);
+        fn vkQueueSubmit(queue: VkQueue, submitCount: u32, pSubmits: *const VkSubmitInfo, fence: VkFence) -> VkResult;
+        fn vkQueueWaitIdle(queue: VkQueue) -> VkResult;
+        fn vkDeviceWaitIdle(device: VkDevice) -> VkResult;
+        fn vkAllocateMemory(device: VkDevice, pAllocateInfo: *const VkMemoryAllocateInfo, pAllocator: *const VkAllocationCallbacks, pMemory: *mut VkDeviceMemory) -> VkResult;
+        fn vkFreeMemory(device: VkDevice, memory: VkDeviceMemory, pAllocator: *const VkAllocationCallbacks);
+        fn vkMapMemory(device: VkDevice, memory: VkDeviceMemory, offset: VkDeviceSize, size: VkDeviceSize, flags: VkMemoryMapFlags, ppData: *mut *mut c_void) -> VkResult;
+        fn vkUnmapMemory(device: VkDevice, memory: VkDeviceMemory);
+        fn vkFlushMappedMemoryRanges(device: VkDevice, memoryRangeCount: u32, pMemoryRanges: *const VkMappedMemoryRange) -> VkResult;
+        fn vkInvalidateMappedMemoryRanges(device: VkDevice, memoryRangeCount: u32, pMemoryRanges: *const VkMappedMemoryRange) -> VkResult;
+        fn vkGetDeviceMemoryCommitment(device: VkDevice, memory: VkDeviceMemory, pCommittedMemoryInBytes: *mut VkDeviceSize);
+        fn vkBindBufferMemory(device: VkDevice, buffer: VkBuffer, memory: VkDeviceMemory, memoryOffset: VkDeviceSize) -> VkResult;
+        fn vkBindImageMemory(device: VkDevice, image: VkImage, memory: VkDeviceMemory, memoryOffset: VkDeviceSize) -> VkResult;
+        fn vkGetBufferMemoryRequirements(device: VkDevice, buffer: VkBuffer, pMemoryRequirements: *mut VkMemoryRequirements);
+        fn vkGetImageMemoryRequirements(device: VkDevice, image: VkImage, pMemoryRequirements: *mut VkMemoryRequirements);
+        fn vkGetImageSparseMemoryRequirements(device: VkDevice, image: VkImage, pSparseMemoryRequirementCount: *mut u32, pSparseMemoryRequirements: *mut VkSparseImageMemoryRequirements);
+        fn vkQueueBindSparse(queue: VkQueue, bindInfoCount: u32, pBindInfo: *const VkBindSparseInfo, fence: VkFence) -> VkResult;
+        fn vkCreateFence(device: VkDevice, pCreateInfo: *const VkFenceCreateInfo, pAllocator: *const VkAllocationCallbacks, pFence: *mut VkFence) -> VkResult;
+        fn vkDestroyFence(device: VkDevice, fence: VkFence, pAllocator: *const VkAllocationCallbacks);
+        fn vkResetFences(device: VkDevice, fenceCount: u32, pFences: *const VkFence) -> VkResult;
+        fn vkGetFenceStatus(device: VkDevice, fence: VkFence) -> VkResult;
+        fn vkWaitForFences(device: VkDevice, fenceCount: u32, pFences: *const VkFence, waitAll: VkBool32, timeout: u64) -> VkResult;
+        fn vkCreateSemaphore(device: VkDevice, pCreateInfo: *const VkSemaphoreCreateInfo, pAllocator: *const VkAllocationCallbacks, pSemaphore: *mut VkSemaphore) -> VkResult;
+        fn vkDestroySemaphore(device: VkDevice, semaphore: VkSemaphore, pAllocator: *const VkAllocationCallbacks);
+        fn vkCreateEvent(device: VkDevice, pCreateInfo: *const VkEventCreateInfo, pAllocator: *const VkAllocationCallbacks, pEvent: *mut VkEvent) -> VkResult;
+        fn vkDestroyEvent(device: VkDevice, event: VkEvent, pAllocator: *const VkAllocationCallbacks);
+        fn vkGetEventStatus(device: VkDevice, event: VkEvent) -> VkResult;
+        fn vkSetEvent(device: VkDevice, event: VkEvent) -> VkResult;
+        fn vkResetEvent(device: VkDevice, event: VkEvent) -> VkResult;
+        fn vkCreateQueryPool(device: VkDevice, pCreateInfo: *const VkQueryPoolCreateInfo, pAllocator: *const VkAllocationCallbacks, pQueryPool: *mut VkQueryPool) -> VkResult;
+        fn vkDestroyQueryPool(device: VkDevice, queryPool: VkQueryPool, pAllocator: *const VkAllocationCallbacks);
+        fn vkGetQueryPoolResults(device: VkDevice, queryPool: VkQueryPool, firstQuery: u32, queryCount: u32, dataSize: usize, pData: *mut c_void, stride: VkDeviceSize, flags: VkQueryResultFlags) -> VkResult;
+        fn vkCreateBuffer(device: VkDevice, pCreateInfo: *const VkBufferCreateInfo, pAllocator: *const VkAllocationCallbacks, pBuffer: *mut VkBuffer) -> VkResult;
+        fn vkDestroyBuffer(device: VkDevice, buffer: VkBuffer, pAllocator: *const VkAllocationCallbacks);
+        fn vkCreateBufferView(device: VkDevice, pCreateInfo: *const VkBufferViewCreateInfo, pAllocator: *const VkAllocationCallbacks, pView: *mut VkBufferView) -> VkResult;
+        fn vkDestroyBufferView(device: VkDevice, bufferView: VkBufferView, pAllocator: *const VkAllocationCallbacks);
+        fn vkCreateImage(device: VkDevice, pCreateInfo: *const VkImageCreateInfo, pAllocator: *const VkAllocationCallbacks, pImage: *mut VkImage) -> VkResult;
+        fn vkDestroyImage(device: VkDevice, image: VkImage, pAllocator: *const VkAllocationCallbacks);
+        fn vkGetImageSubresourceLayout(device: VkDevice, image: VkImage, pSubresource: *const VkImageSubresource, pLayout: *mut VkSubresourceLayout);
+        fn vkCreateImageView(device: VkDevice, pCreateInfo: *const VkImageViewCreateInfo, pAllocator: *const VkAllocationCallbacks, pView: *mut VkImageView) -> VkResult;
+        fn vkDestroyImageView(device: VkDevice, imageView: VkImageView, pAllocator: *const VkAllocationCallbacks);
+        fn vkCreateShaderModule(device: VkDevice, pCreateInfo: *const VkShaderModuleCreateInfo, pAllocator: *const VkAllocationCallbacks, pShaderModule: *mut VkShaderModule) -> VkResult;
+        fn vkDestroyShaderModule(device: VkDevice, shaderModule: VkShaderModule, pAllocator: *const VkAllocationCallbacks);
+        fn vkCreatePipelineCache(device: VkDevice, pCreateInfo: *const VkPipelineCacheCreateInfo, pAllocator: *const VkAllocationCallbacks, pPipelineCache: *mut VkPipelineCache) -> VkResult;
+        fn vkDestroyPipelineCache(device: VkDevice, pipelineCache: VkPipelineCache, pAllocator: *const VkAllocationCallbacks);
+        fn vkGetPipelineCacheData(device: VkDevice, pipelineCache: VkPipelineCache, pDataSize: *mut usize, pData: *mut c_void) -> VkResult;
+        fn vkMergePipelineCaches(device: VkDevice, dstCache: VkPipelineCache, srcCacheCount: u32, pSrcCaches: *const VkPipelineCache) -> VkResult;
+        fn vkCreateGraphicsPipelines(device: VkDevice, pipelineCache: VkPipelineCache, createInfoCount: u32, pCreateInfos: *const VkGraphicsPipelineCreateInfo, pAllocator: *const VkAllocationCallbacks, pPipelines: *mut VkPipeline) -> VkResult;
+        fn vkCreateComputePipelines(device: VkDevice, pipelineCache: VkPipelineCache, createInfoCount: u32, pCreateInfos: *const VkComputePipelineCreateInfo, pAllocator: *const VkAllocationCallbacks, pPipelines: *mut VkPipeline) -> VkResult;
+        fn vkDestroyPipeline(device: VkDevice, pipeline: VkPipeline, pAllocator: *const VkAllocationCallbacks);
+        fn vkCreatePipelineLayout(device: VkDevice, pCreateInfo: *const VkPipelineLayoutCreateInfo, pAllocator: *const VkAllocationCallbacks, pPipelineLayout: *mut VkPipelineLayout) -> VkResult;
+        fn vkDestroyPipelineLayout(device: VkDevice, pipelineLayout: VkPipelineLayout, pAllocator: *const VkAllocationCallbacks);
+        fn vkCreateSampler(device: VkDevice, pCreateInfo: *const VkSamplerCreateInfo, pAllocator: *const VkAllocationCallbacks, pSampler: *mut VkSampler) -> VkResult;
+        fn vkDestroySampler(device: VkDevice, sampler: VkSampler, pAllocator: *const VkAllocationCallbacks);
+        fn vkCreateDescriptorSetLayout(device: VkDevice, pCreateInfo: *const VkDescriptorSetLayoutCreateInfo, pAllocator: *const VkAllocationCallbacks, pSetLayout: *mut VkDescriptorSetLayout) -> VkResult;
+        fn vkDestroyDescriptorSetLayout(device: VkDevice, descriptorSetLayout: VkDescriptorSetLayout, pAllocator: *const VkAllocationCallbacks);
+        fn vkCreateDescriptorPool(device: VkDevice, pCreateInfo: *const VkDescriptorPoolCreateInfo, pAllocator: *const VkAllocationCallbacks, pDescriptorPool: *mut VkDescriptorPool) -> VkResult;
+        fn vkDestroyDescriptorPool(device: VkDevice, descriptorPool: VkDescriptorPool, pAllocator: *const VkAllocationCallbacks);
+        fn vkResetDescriptorPool(device: VkDevice, descriptorPool: VkDescriptorPool, flags: VkDescriptorPoolResetFlags) -> VkResult;
+        fn vkAllocateDescriptorSets(device: VkDevice, pAllocateInfo: *const VkDescriptorSetAllocateInfo, pDescriptorSets: *mut VkDescriptorSet) -> VkResult;
+        fn vkFreeDescriptorSets(device: VkDevice, descriptorPool: VkDescriptorPool, descriptorSetCount: u32, pDescriptorSets: *const VkDescriptorSet) -> VkResult;
+        fn vkUpdateDescriptorSets(device: VkDevice, descriptorWriteCount: u32, pDescriptorWrites: *const VkWriteDescriptorSet, descriptorCopyCount: u32, pDescriptorCopies: *const VkCopyDescriptorSet);
+        fn vkCreateFramebuffer(device: VkDevice, pCreateInfo: *const VkFramebufferCreateInfo, pAllocator: *const VkAllocationCallbacks, pFramebuffer: *mut VkFramebuffer) -> VkResult;
+        fn vkDestroyFramebuffer(device: VkDevice, framebuffer: VkFramebuffer, pAllocator: *const VkAllocationCallbacks);
+        fn vkCreateRenderPass(device: VkDevice, pCreateInfo: *const VkRenderPassCreateInfo, pAllocator: *const VkAllocationCallbacks, pRenderPass: *mut VkRenderPass) -> VkResult;
+        fn vkDestroyRenderPass(device: VkDevice, renderPass: VkRenderPass, pAllocator: *const VkAllocationCallbacks);
+        fn vkGetRenderAreaGranularity(device: VkDevice, renderPass: VkRenderPass, pGranularity: *mut VkExtent2D);
+        fn vkCreateCommandPool(device: VkDevice, pCreateInfo: *const VkCommandPoolCreateInfo, pAllocator: *const VkAllocationCallbacks, pCommandPool: *mut VkCommandPool) -> VkResult;
+        fn vkDestroyCommandPool(device: VkDevice, commandPool: VkCommandPool, pAllocator: *const VkAllocationCallbacks);
+        fn vkResetCommandPool(device: VkDevice, commandPool: VkCommandPool, flags: VkCommandPoolResetFlags) -> VkResult;
+        fn vkAllocateCommandBuffers(device: VkDevice, pAllocateInfo: *const VkCommandBufferAllocateInfo, pCommandBuffers: *mut VkCommandBuffer) -> VkResult;
+        fn vkFreeCommandBuffers(device: VkDevice, commandPool: VkCommandPool, commandBufferCount: u32, pCommandBuffers: *const VkCommandBuffer);
+        fn vkBeginCommandBuffer(commandBuffer: VkCommandBuffer, pBeginInfo: *const VkCommandBufferBeginInfo) -> VkResult;
+        fn vkEndCommandBuffer(commandBuffer: VkCommandBuffer) -> VkResult;
+        fn vkResetCommandBuffer(commandBuffer: VkCommandBuffer, flags: VkCommandBufferResetFlags) -> VkResult;
+
+        // commands
+        fn vkCmdBindPipeline(commandBuffer: VkCommandBuffer, pipelineBindPoint: VkPipelineBindPoint, pipeline: VkPipeline);
+        fn vkCmdSetViewport(commandBuffer: VkCommandBuffer, firstViewport: u32, viewportCount: u32, pViewports: *const VkViewport);
+        fn vkCmdSetScissor(commandBuffer: VkCommandBuffer, firstScissor: u32, scissorCount: u32, pScissors: *const VkRect2D);
+        fn vkCmdSetLineWidth(commandBuffer: VkCommandBuffer, lineWidth: f32);
+        fn vkCmdSetDepthBias(commandBuffer: VkCommandBuffer, depthBiasConstantFactor: f32, depthBiasClamp: f32, depthBiasSlopeFactor: f32);
+        fn vkCmdSetBlendConstants(commandBuffer: VkCommandBuffer, blendConstants: *const f32);
+        fn vkCmdSetDepthBounds(commandBuffer: VkCommandBuffer, minDepthBounds: f32, maxDepthBounds: f32);
+        fn vkCmdSetStencilCompareMask(commandBuffer: VkCommandBuffer, faceMask: VkStencilFaceFlags, compareMask: u32);
+        fn vkCmdSetStencilWriteMask(commandBuffer: VkCommandBuffer, faceMask: VkStencilFaceFlags, writeMask: u32);
+        fn vkCmdSetStencilReference(commandBuffer: VkCommandBuffer, faceMask: VkStencilFaceFlags, reference: u32);
+        fn vkCmdBindDescriptorSets(commandBuffer: VkCommandBuffer, pipelineBindPoint: VkPipelineBindPoint, layout: VkPipelineLayout, firstSet: u32, descriptorSetCount: u32, pDescriptorSets: *const VkDescriptorSet, dynamicOffsetCount: u32, pDynamicOffsets: *const u32);
+        fn vkCmdBindIndexBuffer(commandBuffer: VkCommandBuffer, buffer: VkBuffer, offset: VkDeviceSize, indexType: VkIndexType);
+        fn vkCmdBindVertexBuffers(commandBuffer: VkCommandBuffer, firstBinding: u32, bindingCount: u32, pBuffers: *const VkBuffer, pOffsets: *const VkDeviceSize);
+        fn vkCmdDraw(commandBuffer: VkCommandBuffer, vertexCount: u32, instanceCount: u32, firstVertex: u32, firstInstance: u32);
+        fn vkCmdDrawIndexed(commandBuffer: VkCommandBuffer, indexCount: u32, instanceCount: u32, firstIndex: u32, vertexOffset: i32, firstInstance: u32);
+        fn vkCmdDrawIndirect(commandBuffer: VkCommandBuffer, buffer: VkBuffer, offset: VkDeviceSize, drawCount: u32, stride: u32);
+        fn vkCmdDrawIndexedIndirect(commandBuffer: VkCommandBuffer, buffer: VkBuffer, offset: VkDeviceSize, drawCount: u32, stride: u32);
+        fn vkCmdDispatch(commandBuffer: VkCommandBuffer, groupCountX: u32, groupCountY: u32, groupCountZ: u32);
+        fn vkCmdDispatchIndirect(commandBuffer: VkCommandBuffer, buffer: VkBuffer, offset: VkDeviceSize);
+        fn vkCmdCopyBuffer(commandBuffer: VkCommandBuffer, srcBuffer: VkBuffer, dstBuffer: VkBuffer, regionCount: u32, pRegions: *const VkBufferCopy);
+        fn vkCmdCopyImage(commandBuffer: VkCommandBuffer, srcImage: VkImage, srcImageLayout: VkImageLayout, dstImage: VkImage, dstImageLayout: VkImageLayout, regionCount: u32, pRegions: *const VkImageCopy);
+        fn vkCmdBlitImage(commandBuffer: VkCommandBuffer, srcImage: VkImage, srcImageLayout: VkImageLayout, dstImage: VkImage, dstImageLayout: VkImageLayout, regionCount: u32, pRegions: *const VkImageBlit, filter: VkFilter);
+        fn vkCmdCopyBufferToImage(commandBuffer: VkCommandBuffer, srcBuffer: VkBuffer, dstImage: VkImage, dstImageLayout: VkImageLayout, regionCount: u32, pRegions: *const VkBufferImageCopy);
+        fn vkCmdCopyImageToBuffer(commandBuffer: VkCommandBuffer, srcImage: VkImage, srcImageLayout: VkImageLayout, dstBuffer: VkBuffer, regionCount: u32, pRegions: *const VkBufferImageCopy);
+        fn vkCmdUpdateBuffer(commandBuffer: VkCommandBuffer, dstBuffer: VkBuffer, dstOffset: VkDeviceSize, dataSize: VkDeviceSize, pData: *const c_void);
+        fn vkCmdFillBuffer(commandBuffer: VkCommandBuffer, dstBuffer: VkBuffer, dstOffset: VkDeviceSize, size: VkDeviceSize, data: u32);
+        fn vkCmdClearColorImage(commandBuffer: VkCommandBuffer, image: VkImage, imageLayout: VkImageLayout, pColor: *const VkClearColorValue, rangeCount: u32, pRanges: *const VkImageSubresourceRange);
+        fn vkCmdClearDepthStencilImage(commandBuffer: VkCommandBuffer, image: VkImage, imageLayout: VkImageLayout, pDepthStencil: *const VkClearDepthStencilValue, rangeCount: u32, pRanges: *const VkImageSubresourceRange);
+        fn vkCmdClearAttachments(commandBuffer: VkCommandBuffer, attachmentCount: u32, pAttachments: *const VkClearAttachment, rectCount: u32, pRects: *const VkClearRect);
+        fn vkCmdResolveImage(commandBuffer: VkCommandBuffer, srcImage: VkImage, srcImageLayout: VkImageLayout, dstImage: VkImage, dstImageLayout: VkImageLayout, regionCount: u32, pRegions: *const VkImageResolve);
+        fn vkCmdSetEvent(commandBuffer: VkCommandBuffer, event: VkEvent, stageMask: VkPipelineStageFlags);
+        fn vkCmdResetEvent(commandBuffer: VkCommandBuffer, event: VkEvent, stageMask: VkPipelineStageFlags);
+        fn vkCmdWaitEvents(commandBuffer: VkCommandBuffer, eventCount: u32, pEvents: *const VkEvent, srcStageMask: VkPipelineStageFlags, dstStageMask: VkPipelineStageFlags, memoryBarrierCount: u32, pMemoryBarriers: *const VkMemoryBarrier, bufferMemoryBarrierCount: u32, pBufferMemoryBarriers: *const VkBufferMemoryBarrier, imageMemoryBarrierCount: u32, pImageMemoryBarriers: *const VkImageMemoryBarrier);
+        fn vkCmdPipelineBarrier(commandBuffer: VkCommandBuffer, srcStageMask: VkPipelineStageFlags, dstStageMask: VkPipelineStageFlags, dependencyFlags: VkDependencyFlags, memoryBarrierCount: u32, pMemoryBarriers: *const VkMemoryBarrier, bufferMemoryBarrierCount: u32, pBufferMemoryBarriers: *const VkBufferMemoryBarrier, imageMemoryBarrierCount: u32, pImageMemoryBarriers: *const VkImageMemoryBarrier);
+        fn vkCmdBeginQuery(commandBuffer: VkCommandBuffer, queryPool: VkQueryPool, query: u32, flags: VkQueryControlFlags);
+        fn vkCmdEndQuery(commandBuffer: VkCommandBuffer, queryPool: VkQueryPool, query: u32);
+        fn vkCmdResetQueryPool(commandBuffer: VkCommandBuffer, queryPool: VkQueryPool, firstQuery: u32, queryCount: u32);
+        fn vkCmdWriteTimestamp(commandBuffer: VkCommandBuffer, pipelineStage: VkPipelineStageFlagBits, queryPool: VkQueryPool, query: u32);
+        fn vkCmdCopyQueryPoolResults(commandBuffer: VkCommandBuffer, queryPool: VkQueryPool, firstQuery: u32, queryCount: u32, dstBuffer: VkBuffer, dstOffset: VkDeviceSize, stride: VkDeviceSize, flags: VkQueryResultFlags);
+        fn vkCmdPushConstants(commandBuffer: VkCommandBuffer, layout: VkPipelineLayout, stageFlags: VkShaderStageFlags, offset: u32, size: u32, pValues: *const c_void);
+        fn vkCmdBeginRenderPass(commandBuffer: VkCommandBuffer, pRenderPassBegin: *const VkRenderPassBeginInfo, contents: VkSubpassContents);
+        fn vkCmdNextSubpass(commandBuffer: VkCommandBuffer, contents: VkSubpassContents);
+        fn vkCmdEndRenderPass(commandBuffer: VkCommandBuffer);
+        fn vkCmdExecuteCommands(commandBuffer: VkCommandBuffer, commandBufferCount: u32, pCommandBuffers: *const VkCommandBuffer);
+
+        // 1.1
+        fn vkTrimCommandPool(device: VkDevice, commandPool: VkCommandPool, flags: VkCommandPoolTrimFlags);
+        fn vkCreateSamplerYcbcrConversion(device: VkDevice, pCreateInfo: *const VkSamplerYcbcrConversionCreateInfo, pAllocator: *const VkAllocationCallbacks, pYcbcrConversion: *mut VkSamplerYcbcrConversion) -> VkResult;
+        fn vkDestroySamplerYcbcrConversion(device: VkDevice, ycbcrConversion: VkSamplerYcbcrConversion, pAllocator: *const VkAllocationCallbacks);
+        fn vkCreateDescriptorUpdateTemplate(device: VkDevice, pCreateInfo: *const VkDescriptorUpdateTemplateCreateInfo, pAllocator: *const VkAllocationCallbacks, pDescriptorUpdateTemplate: *mut VkDescriptorUpdateTemplate) -> VkResult;
+        fn vkDestroyDescriptorUpdateTemplate(device: VkDevice, descriptorUpdateTemplate: VkDescriptorUpdateTemplate, pAllocator: *const VkAllocationCallbacks);
+        fn vkUpdateDescriptorSetWithTemplate(device: VkDevice, descriptorSet: VkDescriptorSet, descriptorUpdateTemplate: VkDescriptorUpdateTemplate, pData: *const c_void);
+        fn vkCmdSetDeviceMask(commandBuffer: VkCommandBuffer, deviceMask: u32);
+        fn vkCmdDispatchBase(commandBuffer: VkCommandBuffer, baseGroupX: u32, baseGroupY: u32, baseGroupZ: u32, groupCountX: u32, groupCountY: u32, groupCountZ: u32);
+
+        // 1.2
+        fn vkResetQueryPool(device: VkDevice, queryPool: VkQueryPool, firstQuery: u32, queryCount: u32);
+        fn vkCmdDrawIndirectCount(commandBuffer: VkCommandBuffer, buffer: VkBuffer, offset: VkDeviceSize, countBuffer: VkBuffer, countBufferOffset: VkDeviceSize, maxDrawCount: u32, stride: u32);
+        fn vkCmdDrawIndexedIndirectCount(commandBuffer: VkCommandBuffer, buffer: VkBuffer, offset: VkDeviceSize, countBuffer: VkBuffer, countBufferOffset: VkDeviceSize, maxDrawCount: u32, stride: u32);
+        fn vkCmdBeginRenderPass2(commandBuffer: VkCommandBuffer, pRenderPassBegin: *const VkRenderPassBeginInfo, pSubpassBeginInfo: *const VkSubpassBeginInfo);
+        fn vkCmdNextSubpass2(commandBuffer: VkCommandBuffer, pSubpassBeginInfo: *const VkSubpassBeginInfo, pSubpassEndInfo: *const VkSubpassEndInfo);
+        fn vkCmdEndRenderPass2(commandBuffer: VkCommandBuffer, pSubpassEndInfo: *const VkSubpassEndInfo);
+
+        // 1.3
+        fn vkCreatePrivateDataSlot(device: VkDevice, pCreateInfo: *const VkPrivateDataSlotCreateInfo, pAllocator: *const VkAllocationCallbacks, pPrivateDataSlot: *mut VkPrivateDataSlot) -> VkResult;
+        fn vkDestroyPrivateDataSlot(device: VkDevice, privateDataSlot: VkPrivateDataSlot, pAllocator: *const VkAllocationCallbacks);
+        fn vkSetPrivateData(device: VkDevice, objectType: VkObjectType, objectHandle: u64, privateDataSlot: VkPrivateDataSlot, data: u64) -> VkResult;
+        fn vkGetPrivateData(device: VkDevice, objectType: VkObjectType, objectHandle: u64, privateDataSlot: VkPrivateDataSlot, pData: *mut u64);
+        fn vkCmdSetEvent2(commandBuffer: VkCommandBuffer, event: VkEvent, pDependencyInfo: *const VkDependencyInfo);
+        fn vkCmdResetEvent2(commandBuffer: VkCommandBuffer, event: VkEvent, stageMask: VkPipelineStageFlags2);
+        fn vkCmdWaitEvents2(commandBuffer: VkCommandBuffer, eventCount: u32, pEvents: *const VkEvent, pDependencyInfos: *const VkDependencyInfo);
+        fn vkCmdPipelineBarrier2(commandBuffer: VkCommandBuffer, pDependencyInfo: *const VkDependencyInfo);
+        fn vkCmdWriteTimestamp2(commandBuffer: VkCommandBuffer, stage: VkPipelineStageFlags2, queryPool: VkQueryPool, query: u32);
+        fn vkCmdCopyBuffer2(commandBuffer: VkCommandBuffer, pCopyBufferInfo: *const VkCopyBufferInfo2);
+        fn vkCmdCopyImage2(commandBuffer: VkCommandBuffer, pCopyImageInfo: *const VkCopyImageInfo2);
+        fn vkCmdCopyBufferToImage2(commandBuffer: VkCommandBuffer, pCopyBufferToImageInfo: *const VkCopyBufferToImageInfo2);
+        fn vkCmdCopyImageToBuffer2(commandBuffer: VkCommandBuffer, pCopyImageToBufferInfo: *const VkCopyImageToBufferInfo2);
+        fn vkCmdBlitImage2(commandBuffer: VkCommandBuffer, pBlitImageInfo: *const VkBlitImageInfo2);
+        fn vkCmdResolveImage2(commandBuffer: VkCommandBuffer, pResolveImageInfo: *const VkResolveImageInfo2);
+        fn vkCmdBeginRendering(commandBuffer: VkCommandBuffer, pRenderingInfo: *const VkRenderingInfo);
+        fn vkCmdEndRendering(commandBuffer: VkCommandBuffer);
+        fn vkCmdSetCullMode(commandBuffer: VkCommandBuffer, cullMode: VkCullModeFlags);
+        fn vkCmdSetFrontFace(commandBuffer: VkCommandBuffer, frontFace: VkFrontFace);
+        fn vkCmdSetPrimitiveTopology(commandBuffer: VkCommandBuffer, primitiveTopology: VkPrimitiveTopology);
+        fn vkCmdSetViewportWithCount(commandBuffer: VkCommandBuffer, viewportCount: u32, pViewports: *const VkViewport);
+        fn vkCmdSetScissorWithCount(commandBuffer: VkCommandBuffer, scissorCount: u32, pScissors: *const VkRect2D);
+        fn vkCmdBindVertexBuffers2(commandBuffer: VkCommandBuffer, firstBinding: u32, bindingCount: u32, pBuffers: *const VkBuffer, pOffsets: *const VkDeviceSize, pSizes: *const VkDeviceSize, pStrides: *const VkDeviceSize);
+        fn vkCmdSetDepthTestEnable(commandBuffer: VkCommandBuffer, depthTestEnable: VkBool32);
+        fn vkCmdSetDepthWriteEnable(commandBuffer: VkCommandBuffer, depthWriteEnable: VkBool32);
+        fn vkCmdSetDepthCompareOp(commandBuffer: VkCommandBuffer, depthCompareOp: VkCompareOp);
+        fn vkCmdSetDepthBoundsTestEnable(commandBuffer: VkCommandBuffer, depthBoundsTestEnable: VkBool32);
+        fn vkCmdSetStencilTestEnable(commandBuffer: VkCommandBuffer, stencilTestEnable: VkBool32);
+        fn vkCmdSetStencilOp(commandBuffer: VkCommandBuffer, faceMask: VkStencilFaceFlags, failOp: VkStencilOp, passOp: VkStencilOp, depthFailOp: VkStencilOp, compareOp: VkCompareOp);
+        fn vkCmdSetRasterizerDiscardEnable(commandBuffer: VkCommandBuffer, rasterizerDiscardEnable: VkBool32);
+        fn vkCmdSetDepthBiasEnable(commandBuffer: VkCommandBuffer, depthBiasEnable: VkBool32);
+        fn vkCmdSetPrimitiveRestartEnable(commandBuffer: VkCommandBuffer, primitiveRestartEnable: VkBool32);
+
+        // VK_KHR_swapchain
+        fn vkCreateSwapchainKHR(device: VkDevice, pCreateInfo: *const VkSwapchainCreateInfoKHR, pAllocator: *const VkAllocationCallbacks, pSwapchain: *mut VkSwapchainKHR) -> VkResult;
+        fn vkDestroySwapchainKHR(device: VkDevice, swapchain: VkSwapchainKHR, pAllocator: *const VkAllocationCallbacks);
+        fn vkGetSwapchainImagesKHR(device: VkDevice, swapchain: VkSwapchainKHR, pSwapchainImageCount: *mut u32, pSwapchainImages: *mut VkImage) -> VkResult;
+        fn vkAcquireNextImageKHR(device: VkDevice, swapchain: VkSwapchainKHR, timeout: u64, semaphore: VkSemaphore, fence: VkFence, pImageIndex: *mut u32) -> VkResult;
+        fn vkQueuePresentKHR(queue: VkQueue, pPresentInfo: *const VkPresentInfoKHR) -> VkResult;
+
+        // VK_EXT_debug_utils, device level commands of the instance extension
+        fn vkSetDebugUtilsObjectNameEXT(device: VkDevice, pNameInfo: *const VkDebugUtilsObjectNameInfoEXT) -> VkResult;
+        fn vkSetDebugUtilsObjectTagEXT(device: VkDevice, pTagInfo: *const VkDebugUtilsObjectTagInfoEXT) -> VkResult;
+        fn vkQueueBeginDebugUtilsLabelEXT(queue: VkQueue, pLabelInfo: *const VkDebugUtilsLabelEXT);
+        fn vkQueueEndDebugUtilsLabelEXT(queue: VkQueue);
+        fn vkQueueInsertDebugUtilsLabelEXT(queue: VkQueue, pLabelInfo: *const VkDebugUtilsLabelEXT);
+        fn vkCmdBeginDebugUtilsLabelEXT(commandBuffer: VkCommandBuffer, pLabelInfo: *const VkDebugUtilsLabelEXT);
+        fn vkCmdEndDebugUtilsLabelEXT(commandBuffer: VkCommandBuffer);
+        fn vkCmdInsertDebugUtilsLabelEXT(commandBuffer: VkCommandBuffer, pLabelInfo: *const VkDebugUtilsLabelEXT);
+    }
+}
+
+//
+// commands that create or destroy dispatchable handles keep the registries up to date
+//
+/// # Safety
+///
+/// The valid usage of `vkGetInstanceProcAddr` applies.
+pub unsafe fn vkGetInstanceProcAddr(
+    instance: VkInstance,
+    pName: *const c_char,
+) -> PFN_vkVoidFunction {
+    match vulkan_entry() {
+        Ok(entry) => entry.get_instance_proc_addr(instance, CStr::from_ptr(pName)),
+        Err(_) => None,
+    }
+}
+
+/// # Safety
+///
+/// The valid usage of `vkGetDeviceProcAddr` applies.
+pub unsafe fn vkGetDeviceProcAddr(device: VkDevice, pName: *const c_char) -> PFN_vkVoidFunction {
+    let get_device_proc_addr = device_table(device, |table| table.vkGetDeviceProcAddr).flatten()?;
+    get_device_proc_addr(device, pName)
+}
+
+/// # Safety
+///
+/// The valid usage of `vkCreateInstance` applies.
+pub unsafe fn vkCreateInstance(
+    pCreateInfo: *const VkInstanceCreateInfo,
+    pAllocator: *const VkAllocationCallbacks,
+    pInstance: *mut VkInstance,
+) -> VkResult {
+    let entry = match vulkan_entry() {
+        Ok(entry) => entry,
+        Err(_) => return VkResult::VK_ERROR_INITIALIZATION_FAILED,
+    };
+
+    let Some(create_instance) = entry.table.vkCreateInstance else {
+        return VkResult::VK_ERROR_INITIALIZATION_FAILED;
+    };
+    let result = create_instance(pCreateInfo, pAllocator, pInstance);
+
+    if let (VkResult::VK_SUCCESS, Some(key)) = (result, dispatch_key(*pInstance)) {
+        let instance = *pInstance;
+        let table = InstanceTable::load(|name| entry.get_instance_proc_addr(instance, name));
+        INSTANCES.register(key, table);
+    }
+    result
+}
+
+/// # Safety
+///
+/// The valid usage of `vkDestroyInstance` applies.
+pub unsafe fn vkDestroyInstance(instance: VkInstance, pAllocator: *const VkAllocationCallbacks) {
+    let Some(key) = dispatch_key(instance) else {
+        return;
+    };
+
+    match instance_table(instance, |table| table.vkDestroyInstance) {
+        Some(Some(destroy_instance)) => destroy_instance(instance, pAllocator),
+        Some(None) => not_loaded("vkDestroyInstance"),
+        None => unknown_handle("vkDestroyInstance"),
+    }
+    INSTANCES.unregister(key);
+}
+
+/// # Safety
+///
+/// The valid usage of `vkCreateDevice` applies.
+pub unsafe fn vkCreateDevice(
+    physicalDevice: VkPhysicalDevice,
+    pCreateInfo: *const VkDeviceCreateInfo,
+    pAllocator: *const VkAllocationCallbacks,
+    pDevice: *mut VkDevice,
+) -> VkResult {
+    let Some((create_device, get_device_proc_addr)) = instance_table(physicalDevice, |table| {
+        (table.vkCreateDevice, table.vkGetDeviceProcAddr)
+    }) else {
+        return unknown_handle("vkCreateDevice");
+    };
+    // without vkGetDeviceProcAddr the device could not be used
+    let (Some(create_device), Some(get_device_proc_addr)) = (create_device, get_device_proc_addr)
+    else {
+        return VkResult::VK_ERROR_INITIALIZATION_FAILED;
+    };
+    let result = create_device(physicalDevice, pCreateInfo, pAllocator, pDevice);
+
+    if let (VkResult::VK_SUCCESS, Some(key)) = (result, dispatch_key(*pDevice)) {
+        let device = *pDevice;
+        let table = DeviceTable::load(|name| get_device_proc_addr(device, name.as_ptr()));
+        DEVICES.register(key, table);
+    }
+    result
+}
+
+/// # Safety
+///
+/// The valid usage of `vkDestroyDevice` applies.
+pub unsafe fn vkDestroyDevice(device: VkDevice, pAllocator: *const VkAllocationCallbacks) {
+    let Some(key) = dispatch_key(device) else {
+        return;
+    };
+
+    match device_table(device, |table| table.vkDestroyDevice) {
+        Some(Some(destroy_device)) => destroy_device(device, pAllocator),
+        Some(None) => not_loaded("vkDestroyDevice"),
+        None => unknown_handle("vkDestroyDevice"),
+    }
+    DEVICES.unregister(key);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a dispatchable handle only has to start with its dispatch key
+    fn handle(key: &'static usize) -> VkQueue {
+        key as *const usize as VkQueue
+    }
+
+    unsafe extern "C" fn queue_wait_idle(_: VkQueue) -> VkResult {
+        VkResult::VK_SUCCESS
+    }
+
+    fn table_with_queue_wait_idle() -> DeviceTable {
+        unsafe {
+            DeviceTable::load(|name| {
+                (name == c"vkQueueWaitIdle").then(|| {
+                    std::mem::transmute::<
+                        unsafe extern "C" fn(VkQueue) -> VkResult,
+                        unsafe extern "C" fn(),
+                    >(queue_wait_idle)
+                })
+            })
+        }
+    }
+
+    #[test]
+    fn unknown_handles_and_unloaded_commands_return_errors() {
+        static KEY: usize = 0x1001;
+        let queue = handle(&KEY);
+
+        assert_eq!(
+            unsafe { vkQueueWaitIdle(queue) },
+            VkResult::VK_ERROR_INITIALIZATION_FAILED
+        );
+        assert_eq!(
+            unsafe { vkQueueWaitIdle(null_mut()) },
+            VkResult::VK_ERROR_INITIALIZATION_FAILED
+        );
+
+        DEVICES.register(KEY, unsafe { DeviceTable::load(|_| None) });
+        assert_eq!(
+            unsafe { vkQueueWaitIdle(queue) },
+            VkResult::VK_ERROR_EXTENSION_NOT_PRESENT
+        );
+        // commands without a result are skipped
+        unsafe { vkCmdEndRenderPass(queue as VkCommandBuffer) };
+        assert!(!is_device_command_loaded(queue, |table| table
+            .vkQueueWaitIdle
+            .is_some()));
+
+        DEVICES.unregister(KEY);
+    }
+
+    #[test]
+    fn cached_tables_follow_registration() {
+        static KEY: usize = 0x1002;
+        let queue = handle(&KEY);
+
+        DEVICES.register(KEY, table_with_queue_wait_idle());
+        assert_eq!(unsafe { vkQueueWaitIdle(queue) }, VkResult::VK_SUCCESS);
+        // served by the cache of this thread
+        assert_eq!(unsafe { vkQueueWaitIdle(queue) }, VkResult::VK_SUCCESS);
+
+        // a device created again with the same dispatch key
+        DEVICES.register(KEY, unsafe { DeviceTable::load(|_| None) });
+        assert_eq!(
+            unsafe { vkQueueWaitIdle(queue) },
+            VkResult::VK_ERROR_EXTENSION_NOT_PRESENT
+        );
+
+        DEVICES.unregister(KEY);
+        assert_eq!(
+            unsafe { vkQueueWaitIdle(queue) },
+            VkResult::VK_ERROR_INITIALIZATION_FAILED
+        );
+    }
+}
@@ -1,7 +1 @@
-use shader::*;
-use vrx::*;
-
-pub mod graphics;
-pub mod memory;
-
 fn main() {}
@@ -21,6 +21,7 @@ pub struct Buffer<'a, T> {
     device: &'a VkDevice,
     gpu: BufferAndMemory,
     cpu: Data<T>,
+    name: Option<String>,
 }
 
 impl<'a, T> MemoryFunctions for Buffer<'a, T> {
@@ -32,9 +33,9 @@ impl<'a, T> MemoryFunctions for Buffer<'a, T> {
         &self.gpu.1
     }
 
-    fn bind_memory(&self, offset: VkDeviceSize) {
+    fn bind_memory(&self, offset: VkDeviceSize) -> Result<(), VkError> {
         self.device()
-            .bind_buffer_memory(self.gpu.0, self.gpu.1, offset);
+            .bind_buffer_memory(self.gpu.0, self.gpu.1, offset)
     }
 }
 
@@ -50,10 +51,11 @@ impl<'a, T> Buffer<'a, T> {
     pub fn new(
         data_: (Option<*const T>, usize),
         flags: VkBufferCreateFlagBits,
-        usage: VkBufferUsageFlagBits,
+        usage: BufferUsageFlags,
         mem_prop_flags: VkMemoryPropertyFlagBits,
+        physical_device: VkPhysicalDevice,
         device: &'a VkDevice,
-    ) -> Self {
+    ) -> Result<Self, VkError> {
         let cpu = Data {
             ptr_: data_.0,
             len_: data_.1,
@@ -62,61 +64,87 @@ impl<'a, T> Buffer<'a, T> {
         let info = VkBufferCreateInfoBuilder::new()
             .flags(flags as VkBufferCreateFlags)
             .size((cpu.len() * std::mem::size_of::<T>()) as u64)
-            .usage(usage as VkBufferUsageFlags)
+            .usage(usage)
             .sharing_mode(VkSharingMode::VK_SHARING_MODE_EXCLUSIVE)
             .build();
 
-        let buffer = device.create_buffer(&info, None);
-        let memory: VkDeviceMemory =
-            memory_function::allocate_buffer_memory(device, buffer, mem_prop_flags);
-        device.bind_buffer_memory(buffer, memory, 0);
-
-        let gpu = BufferAndMemory(buffer, memory);
-
-        Self { device, gpu, cpu }
+        // a failure drops `buffer`, destroying what was created so far
+        let mut buffer = Self {
+            device,
+            gpu: BufferAndMemory(null_mut(), null_mut()),
+            cpu,
+            name: None,
+        };
+        buffer.gpu.0 = device.create_buffer(&info, None)?;
+        buffer.gpu.1 = memory_function::allocate_buffer_memory(
+            physical_device,
+            device,
+            buffer.gpu.0,
+            mem_prop_flags,
+        )?;
+        device.bind_buffer_memory(buffer.gpu.0, buffer.gpu.1, 0)?;
+
+        Ok(buffer)
     }
 
-    pub fn destroy(&self, p_allocator: Option<*const VkAllocationCallbacks>) {
+    // called on drop, later calls do nothing
+    pub fn destroy(&mut self, p_allocator: Option<*const VkAllocationCallbacks>) {
         self.device.destroy_buffer(self.gpu.0, p_allocator);
         self.device.free_memory(&self.gpu.1, p_allocator);
+        self.gpu = BufferAndMemory(null_mut(), null_mut());
     }
 
     pub fn into_raw_vk(&self) -> VkBuffer {
         self.gpu.0
     }
 
+    // debug name of the buffer and its memory
+    pub fn set_name(&mut self, name: &str) -> Result<(), VkError> {
+        self.device.set_object_name(self.gpu.0, name)?;
+        self.device
+            .set_object_name(self.gpu.1, &format!("{} memory", name))?;
+        self.name = Some(name.to_string());
+        Ok(())
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
     // mappings
-    pub fn map(&mut self, len: usize, data: *const T) {
-        let mapped = self
-            .map_memory(0, (std::mem::size_of::<T>() * len) as u64, 0)
-            .unwrap();
+    // `data` points to `len` values of T, as the pointer of `create_buffer`
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn map(&mut self, len: usize, data: *const T) -> Result<(), VkError> {
+        let mapped = self.map_memory(0, (std::mem::size_of::<T>() * len) as u64, 0)?;
         unsafe {
             std::ptr::copy_nonoverlapping(data, mapped.cast(), len);
         }
         self.unmap_memory();
+        Ok(())
     }
 
-    pub fn map_to_gpu_and_unmap(&self) {
-        let mapped = self.map_memory(0, self.vksize(), 0).unwrap();
+    pub fn map_to_gpu_and_unmap(&self) -> Result<(), VkError> {
+        let mapped = self.map_memory(0, self.vksize(), 0)?;
         unsafe {
             std::ptr::copy_nonoverlapping(self.cpu.as_ptr(), mapped.cast(), self.cpu.len());
         }
         self.unmap_memory();
+        Ok(())
     }
 
-    pub fn map_to_cpu_and_unmap(&mut self) -> Vec<T>
+    pub fn map_to_cpu_and_unmap(&mut self) -> Result<Vec<T>, VkError>
     where
         T: std::clone::Clone + Default,
     {
         let mut output = vec![T::default(); self.cpu.len()];
 
-        let mapped = self.map_memory(0, self.vksize(), 0).unwrap();
+        let mapped = self.map_memory(0, self.vksize(), 0)?;
         unsafe {
             std::ptr::copy_nonoverlapping(mapped.cast(), output.as_mut_ptr(), self.cpu.len());
         }
         self.unmap_memory();
 
-        output
+        Ok(output)
     }
 
     pub fn vksize(&self) -> VkDeviceSize {
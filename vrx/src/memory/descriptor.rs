@@ -9,10 +9,10 @@ pub fn set_layout_binding(
     stage_flag: VkShaderStageFlagBits,
 ) -> VkDescriptorSetLayoutBinding {
     VkDescriptorSetLayoutBinding {
-        binding: binding,
+        binding,
         descriptorType: descriptor_type,
         descriptorCount: 1,
-        stageFlags: VK_SHADER_STAGE_COMPUTE_BIT as u32,
+        stageFlags: VK_SHADER_STAGE_COMPUTE_BIT,
         pImmutableSamplers: null(),
     }
 }
@@ -28,7 +28,7 @@ pub fn set_layout_bindings<const N: usize>(
             binding: inputs[i].0,
             descriptorType: inputs[i].1,
             descriptorCount: inputs[i].2,
-            stageFlags: inputs[i].3 as u32,
+            stageFlags: inputs[i].3,
             pImmutableSamplers: null(),
         }
     }
@@ -36,40 +36,83 @@ pub fn set_layout_bindings<const N: usize>(
     output
 }
 
+impl<'a> Drop for ResourceBinding<'a> {
+    fn drop(&mut self) {
+        self.destroy();
+    }
+}
+
 pub struct ResourceBinding<'a> {
     pub descriptor_pool: VkDescriptorPool,
     pub descriptor_set_layouts: VkDescriptorSetLayout,
     pub descriptor_sets: Vec<VkDescriptorSet>,
     device: &'a VkDevice,
+    name: Option<String>,
 }
 
 impl<'a> ResourceBinding<'a> {
-    pub fn new(bindings: &[VkDescriptorSetLayoutBinding], device: &'a VkDevice) -> Self {
+    pub fn new(
+        bindings: &[VkDescriptorSetLayoutBinding],
+        device: &'a VkDevice,
+    ) -> Result<Self, VkError> {
+        // a failure drops `binding`, destroying what was created so far
+        let mut binding = Self {
+            descriptor_pool: null_mut(),
+            descriptor_set_layouts: null_mut(),
+            descriptor_sets: vec![],
+            device,
+            name: None,
+        };
+
         let pool_sizes = Self::descriptor_pool_size(bindings);
-        let descriptor_pool = Self::create_descriptor_pool(pool_sizes, device);
+        binding.descriptor_pool = Self::create_descriptor_pool(pool_sizes, device)?;
 
         let desc_set_layouts_create_info = VkDescriptorSetLayoutCreateInfoBuilder::new()
             .binding_count(bindings.len() as u32)
             .p_bindings(bindings.as_ptr())
             .build();
 
-        let descriptor_set_layouts =
-            device.create_descriptor_set_layout(&desc_set_layouts_create_info, None);
+        binding.descriptor_set_layouts =
+            device.create_descriptor_set_layout(&desc_set_layouts_create_info, None)?;
 
         let desc_set_allocate_info = VkDescriptorSetAllocateInfoBuilder::new()
-            .descriptor_pool(descriptor_pool)
+            .descriptor_pool(binding.descriptor_pool)
             .descriptor_set_count(bindings.len() as u32)
-            .p_set_layouts(&descriptor_set_layouts)
+            .p_set_layouts(&binding.descriptor_set_layouts)
             .build();
 
-        let descriptor_sets = device.allocate_descriptor_sets(&desc_set_allocate_info);
+        binding.descriptor_sets = device.allocate_descriptor_sets(&desc_set_allocate_info)?;
 
-        Self {
-            descriptor_pool,
-            descriptor_set_layouts,
-            descriptor_sets,
-            device,
+        Ok(binding)
+    }
+
+    // the sets go with the pool, called on drop, later calls do nothing
+    pub fn destroy(&mut self) {
+        self.device
+            .destroy_descriptor_pool(self.descriptor_pool, None);
+        self.device
+            .destroy_descriptor_set_layout(self.descriptor_set_layouts, None);
+        self.descriptor_pool = null_mut();
+        self.descriptor_set_layouts = null_mut();
+        self.descriptor_sets.clear();
+    }
+
+    // debug name of the pool, the layout and the sets
+    pub fn set_name(&mut self, name: &str) -> Result<(), VkError> {
+        self.device
+            .set_object_name(self.descriptor_pool, &format!("{} pool", name))?;
+        self.device
+            .set_object_name(self.descriptor_set_layouts, &format!("{} layout", name))?;
+        for (i, set) in self.descriptor_sets.iter().enumerate() {
+            self.device
+                .set_object_name(*set, &format!("{} set {}", name, i))?;
         }
+        self.name = Some(name.to_string());
+        Ok(())
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
     }
 
     // descriptor pool
@@ -96,7 +139,7 @@ impl<'a> ResourceBinding<'a> {
     fn create_descriptor_pool(
         pool_sizes: Vec<VkDescriptorPoolSize>,
         device: &'a VkDevice,
-    ) -> VkDescriptorPool {
+    ) -> Result<VkDescriptorPool, VkError> {
         let max_sets = pool_sizes
             .iter()
             .fold(0, |init, pool_size| init + pool_size.descriptorCount);
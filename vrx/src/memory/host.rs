@@ -0,0 +1,414 @@
+//
+// host
+// VkAllocationCallbacks on top of a rust allocator, counting live host allocations per scope
+//
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::*;
+
+// VK_SYSTEM_ALLOCATION_SCOPE_COMMAND ..= VK_SYSTEM_ALLOCATION_SCOPE_INSTANCE
+const SCOPES: usize = 5;
+
+/// Live host allocations of one scope
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HostAllocationStats {
+    pub count: usize,
+    pub bytes: usize,
+}
+
+#[derive(Default)]
+struct Counter {
+    count: AtomicUsize,
+    bytes: AtomicUsize,
+}
+
+impl Counter {
+    fn add(&self, size: usize) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(size, Ordering::Relaxed);
+    }
+
+    fn sub(&self, size: usize) {
+        self.count.fetch_sub(1, Ordering::Relaxed);
+        self.bytes.fetch_sub(size, Ordering::Relaxed);
+    }
+
+    fn stats(&self) -> HostAllocationStats {
+        HostAllocationStats {
+            count: self.count.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+enum Backend {
+    Global(Box<dyn GlobalAlloc + Send + Sync>),
+    Closure {
+        alloc: Box<dyn Fn(Layout) -> *mut u8 + Send + Sync>,
+        dealloc: Box<dyn Fn(*mut u8, Layout) + Send + Sync>,
+    },
+}
+
+// written right before every allocation, pfnFree only gets the pointer
+#[repr(C)]
+struct Header {
+    size: usize,
+    alignment: usize,
+    scope: usize,
+}
+
+// boxed so that `callbacks` and `pUserData` keep their address when the allocator moves
+struct Inner {
+    callbacks: VkAllocationCallbacks,
+    backend: Backend,
+    counters: [Counter; SCOPES],
+    // allocations the driver makes itself and only reports
+    internal: [Counter; SCOPES],
+}
+
+///
+/// VkAllocationCallbacks backed by a `GlobalAlloc` or by closures
+///
+/// Allocations keep the alignment the driver asks for, reallocations keep the contents.
+/// Live allocations are counted per VkSystemAllocationScope,
+/// the allocator has to outlive every object created with it.
+///
+/// ```ignore
+/// let allocator = HostAllocator::new();
+/// let buffer = device.create_buffer(&buffer_create_info, Some(allocator.callbacks()))?;
+/// log::info!("{:?}", allocator.stats(VK_SYSTEM_ALLOCATION_SCOPE_OBJECT));
+/// device.destroy_buffer(buffer, Some(allocator.callbacks()));
+/// ```
+///
+pub struct HostAllocator {
+    inner: Box<Inner>,
+}
+
+// the counters are atomic and the backends Send + Sync,
+// the raw pointers of VkAllocationCallbacks only point back into `inner`
+unsafe impl Send for HostAllocator {}
+unsafe impl Sync for HostAllocator {}
+
+impl Default for HostAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HostAllocator {
+    // on top of the system allocator
+    pub fn new() -> Self {
+        Self::with_global(System)
+    }
+
+    pub fn with_global(allocator: impl GlobalAlloc + Send + Sync + 'static) -> Self {
+        Self::with_backend(Backend::Global(Box::new(allocator)))
+    }
+
+    // `alloc` returns null when out of memory, `dealloc` gets the layout `alloc` was called with
+    pub fn from_fn(
+        alloc: impl Fn(Layout) -> *mut u8 + Send + Sync + 'static,
+        dealloc: impl Fn(*mut u8, Layout) + Send + Sync + 'static,
+    ) -> Self {
+        Self::with_backend(Backend::Closure {
+            alloc: Box::new(alloc),
+            dealloc: Box::new(dealloc),
+        })
+    }
+
+    fn with_backend(backend: Backend) -> Self {
+        let mut inner = Box::new(Inner {
+            callbacks: VkAllocationCallbacks::default(),
+            backend,
+            counters: Default::default(),
+            internal: Default::default(),
+        });
+        inner.callbacks = VkAllocationCallbacks {
+            pUserData: &mut *inner as *mut Inner as *mut c_void,
+            pfnAllocation: Some(allocation),
+            pfnReallocation: Some(reallocation),
+            pfnFree: Some(free),
+            pfnInternalAllocation: Some(internal_allocation),
+            pfnInternalFree: Some(internal_free),
+        };
+        Self { inner }
+    }
+
+    // to pass as `p_allocator`
+    pub fn callbacks(&self) -> *const VkAllocationCallbacks {
+        &self.inner.callbacks
+    }
+
+    pub fn stats(&self, scope: VkSystemAllocationScope) -> HostAllocationStats {
+        self.inner.counter(scope).stats()
+    }
+
+    // what the driver reported through pfnInternalAllocation
+    pub fn internal_stats(&self, scope: VkSystemAllocationScope) -> HostAllocationStats {
+        self.inner.internal[scope_index(scope)].stats()
+    }
+
+    // every scope, allocations made through the callbacks only
+    pub fn total(&self) -> HostAllocationStats {
+        self.inner.counters.iter().map(Counter::stats).fold(
+            HostAllocationStats::default(),
+            |total, stats| HostAllocationStats {
+                count: total.count + stats.count,
+                bytes: total.bytes + stats.bytes,
+            },
+        )
+    }
+}
+
+impl Drop for HostAllocator {
+    fn drop(&mut self) {
+        let total = self.total();
+        if total.count != 0 {
+            log::warn!(
+                target: "vrx",
+                "[vrx] host allocator dropped with {} live allocations ({} bytes)",
+                total.count,
+                total.bytes
+            );
+        }
+    }
+}
+
+// out of range scopes end up in the widest one instead of panicking inside a callback
+fn scope_index(scope: VkSystemAllocationScope) -> usize {
+    (scope as usize).min(SCOPES - 1)
+}
+
+impl Inner {
+    fn counter(&self, scope: VkSystemAllocationScope) -> &Counter {
+        &self.counters[scope_index(scope)]
+    }
+
+    // the layout of the whole block and where the memory handed out starts
+    fn layout(size: usize, alignment: usize) -> Option<(Layout, usize)> {
+        let alignment = alignment.max(std::mem::align_of::<Header>());
+        let offset = std::mem::size_of::<Header>().next_multiple_of(alignment);
+        let layout = Layout::from_size_align(offset.checked_add(size)?, alignment).ok()?;
+        Some((layout, offset))
+    }
+
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        match &self.backend {
+            Backend::Global(allocator) => allocator.alloc(layout),
+            Backend::Closure { alloc, .. } => alloc(layout),
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        match &self.backend {
+            Backend::Global(allocator) => allocator.dealloc(ptr, layout),
+            Backend::Closure { dealloc, .. } => dealloc(ptr, layout),
+        }
+    }
+
+    unsafe fn allocate(
+        &self,
+        size: usize,
+        alignment: usize,
+        scope: VkSystemAllocationScope,
+    ) -> *mut c_void {
+        if size == 0 {
+            return null_mut();
+        }
+        let Some((layout, offset)) = Self::layout(size, alignment) else {
+            return null_mut();
+        };
+        let block = self.alloc(layout);
+        if block.is_null() {
+            return null_mut();
+        }
+
+        let memory = block.add(offset);
+        (memory as *mut Header).sub(1).write(Header {
+            size,
+            alignment,
+            scope: scope_index(scope),
+        });
+        self.counter(scope).add(size);
+        memory as *mut c_void
+    }
+
+    unsafe fn reallocate(
+        &self,
+        original: *mut c_void,
+        size: usize,
+        alignment: usize,
+        scope: VkSystemAllocationScope,
+    ) -> *mut c_void {
+        if original.is_null() {
+            return self.allocate(size, alignment, scope);
+        }
+        if size == 0 {
+            self.free(original);
+            return null_mut();
+        }
+
+        // on failure the original stays valid
+        let memory = self.allocate(size, alignment, scope);
+        if !memory.is_null() {
+            let header = (original as *const Header).sub(1).read();
+            std::ptr::copy_nonoverlapping(
+                original as *const u8,
+                memory as *mut u8,
+                header.size.min(size),
+            );
+            self.free(original);
+        }
+        memory
+    }
+
+    unsafe fn free(&self, memory: *mut c_void) {
+        if memory.is_null() {
+            return;
+        }
+        let header = (memory as *const Header).sub(1).read();
+        let (layout, offset) = Self::layout(header.size, header.alignment).unwrap();
+        self.counters[header.scope].sub(header.size);
+        self.dealloc((memory as *mut u8).sub(offset), layout);
+    }
+}
+
+unsafe extern "C" fn allocation(
+    p_user_data: *mut c_void,
+    size: usize,
+    alignment: usize,
+    allocation_scope: VkSystemAllocationScope,
+) -> *mut c_void {
+    (*(p_user_data as *const Inner)).allocate(size, alignment, allocation_scope)
+}
+
+unsafe extern "C" fn reallocation(
+    p_user_data: *mut c_void,
+    p_original: *mut c_void,
+    size: usize,
+    alignment: usize,
+    allocation_scope: VkSystemAllocationScope,
+) -> *mut c_void {
+    (*(p_user_data as *const Inner)).reallocate(p_original, size, alignment, allocation_scope)
+}
+
+unsafe extern "C" fn free(p_user_data: *mut c_void, p_memory: *mut c_void) {
+    (*(p_user_data as *const Inner)).free(p_memory)
+}
+
+unsafe extern "C" fn internal_allocation(
+    p_user_data: *mut c_void,
+    size: usize,
+    _allocation_type: VkInternalAllocationType,
+    allocation_scope: VkSystemAllocationScope,
+) {
+    (*(p_user_data as *const Inner)).internal[scope_index(allocation_scope)].add(size);
+}
+
+unsafe extern "C" fn internal_free(
+    p_user_data: *mut c_void,
+    size: usize,
+    _allocation_type: VkInternalAllocationType,
+    allocation_scope: VkSystemAllocationScope,
+) {
+    (*(p_user_data as *const Inner)).internal[scope_index(allocation_scope)].sub(size);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allocate(
+        allocator: &HostAllocator,
+        size: usize,
+        alignment: usize,
+        scope: VkSystemAllocationScope,
+    ) -> *mut c_void {
+        let callbacks = unsafe { &*allocator.callbacks() };
+        unsafe { callbacks.pfnAllocation.unwrap()(callbacks.pUserData, size, alignment, scope) }
+    }
+
+    fn free(allocator: &HostAllocator, memory: *mut c_void) {
+        let callbacks = unsafe { &*allocator.callbacks() };
+        unsafe { callbacks.pfnFree.unwrap()(callbacks.pUserData, memory) }
+    }
+
+    #[test]
+    fn keeps_alignment() {
+        let allocator = HostAllocator::new();
+        for alignment in [1, 8, 64, 256, 4096] {
+            let memory = allocate(&allocator, 24, alignment, VK_SYSTEM_ALLOCATION_SCOPE_OBJECT);
+            assert!(!memory.is_null());
+            assert_eq!(memory as usize % alignment, 0);
+            free(&allocator, memory);
+        }
+        assert_eq!(allocator.total(), HostAllocationStats::default());
+    }
+
+    #[test]
+    fn counts_per_scope() {
+        let allocator = HostAllocator::new();
+        let a = allocate(&allocator, 16, 8, VK_SYSTEM_ALLOCATION_SCOPE_OBJECT);
+        let b = allocate(&allocator, 32, 8, VK_SYSTEM_ALLOCATION_SCOPE_OBJECT);
+        let c = allocate(&allocator, 64, 8, VK_SYSTEM_ALLOCATION_SCOPE_COMMAND);
+
+        let object = allocator.stats(VK_SYSTEM_ALLOCATION_SCOPE_OBJECT);
+        assert_eq!((object.count, object.bytes), (2, 48));
+        let command = allocator.stats(VK_SYSTEM_ALLOCATION_SCOPE_COMMAND);
+        assert_eq!((command.count, command.bytes), (1, 64));
+
+        free(&allocator, a);
+        free(&allocator, b);
+        free(&allocator, c);
+        assert_eq!(allocator.total(), HostAllocationStats::default());
+    }
+
+    #[test]
+    fn reallocation_keeps_contents() {
+        let allocator = HostAllocator::new();
+        let callbacks = unsafe { &*allocator.callbacks() };
+        let scope = VK_SYSTEM_ALLOCATION_SCOPE_CACHE;
+
+        let memory = allocate(&allocator, 4, 16, scope) as *mut u8;
+        unsafe { std::ptr::copy_nonoverlapping([1u8, 2, 3, 4].as_ptr(), memory, 4) };
+
+        let grown = unsafe {
+            callbacks.pfnReallocation.unwrap()(callbacks.pUserData, memory as _, 1024, 16, scope)
+        } as *mut u8;
+        assert_eq!(grown as usize % 16, 0);
+        assert_eq!(
+            unsafe { std::slice::from_raw_parts(grown, 4) },
+            &[1, 2, 3, 4]
+        );
+        let cache = allocator.stats(scope);
+        assert_eq!((cache.count, cache.bytes), (1, 1024));
+
+        let freed = unsafe {
+            callbacks.pfnReallocation.unwrap()(callbacks.pUserData, grown as _, 0, 16, scope)
+        };
+        assert!(freed.is_null());
+        assert_eq!(allocator.total(), HostAllocationStats::default());
+    }
+
+    #[test]
+    fn closures() {
+        let allocations = std::sync::Arc::new(AtomicUsize::new(0));
+        let (on_alloc, on_dealloc) = (allocations.clone(), allocations.clone());
+        let allocator = HostAllocator::from_fn(
+            move |layout| {
+                on_alloc.fetch_add(1, Ordering::Relaxed);
+                unsafe { System.alloc(layout) }
+            },
+            move |ptr, layout| {
+                on_dealloc.fetch_sub(1, Ordering::Relaxed);
+                unsafe { System.dealloc(ptr, layout) }
+            },
+        );
+
+        let memory = allocate(&allocator, 100, 32, VK_SYSTEM_ALLOCATION_SCOPE_DEVICE);
+        assert_eq!(allocations.load(Ordering::Relaxed), 1);
+        free(&allocator, memory);
+        assert_eq!(allocations.load(Ordering::Relaxed), 0);
+    }
+}
@@ -3,73 +3,88 @@ use crate::*;
 
 pub mod buffer;
 pub mod descriptor;
+pub mod host;
 pub mod texture;
 
 pub use buffer::*;
 pub use descriptor::*;
+pub use host::*;
 pub use texture::*;
 
 use func_static::vk_instantiate;
 
-mod memory_function {
+pub(crate) mod memory_function {
 
     use crate::*;
 
     /// functional
     fn get_phyiscal_device_memory_property_collect(
+        physical_device: VkPhysicalDevice,
         mem_prop_flags: VkMemoryPropertyFlagBits,
     ) -> Vec<u32> {
-        let ctx = vulkan_context();
-
         //@@ to static
-        let mut mem_prop = ctx.get_physical_device_memory_properties();
+        let mut mem_prop = physical_device.get_memory_properties();
         let mut collect: Vec<u32> = (0..mem_prop.memoryTypeCount).collect();
+        // drivers like lavapipe report device local memory that is host visible as well
         collect.retain(|i| {
-            mem_prop.memoryTypes[*i as usize].propertyFlags
-                == (mem_prop_flags as VkMemoryPropertyFlags)
+            let flags = mem_prop_flags as VkMemoryPropertyFlags;
+            mem_prop.memoryTypes[*i as usize].propertyFlags & flags == flags
         });
         collect
     }
 
     pub fn allocate_buffer_memory(
+        physical_device: VkPhysicalDevice,
         device: &VkDevice,
         buffer: VkBuffer,
         mem_prop_flags: VkMemoryPropertyFlagBits,
-    ) -> VkDeviceMemory {
-        let collect = get_phyiscal_device_memory_property_collect(mem_prop_flags);
+    ) -> Result<VkDeviceMemory, VkError> {
+        let mut collect =
+            get_phyiscal_device_memory_property_collect(physical_device, mem_prop_flags);
 
         let mut mem_req = device.get_buffer_memory_requirements(buffer);
+        collect.retain(|i| mem_req.memoryTypeBits & (1 << i) != 0);
+        // none of the allowed memory types has the requested properties
         let mut mem_alloc_info = VkMemoryAllocateInfoBuilder::new()
             .allocation_size(mem_req.size)
-            .memory_type_index(collect[0])
+            .memory_type_index(*collect.first().ok_or(VkError::OutOfDeviceMemory)?)
             .build();
 
         device.allocate_memory(&mem_alloc_info, None)
     }
 
     pub fn allocate_image_memory(
+        physical_device: VkPhysicalDevice,
         device: &VkDevice,
         image: VkImage,
         mem_prop_flags: VkMemoryPropertyFlagBits,
-    ) -> VkDeviceMemory {
-        let collect = get_phyiscal_device_memory_property_collect(mem_prop_flags);
+    ) -> Result<VkDeviceMemory, VkError> {
+        let mut collect =
+            get_phyiscal_device_memory_property_collect(physical_device, mem_prop_flags);
 
         let mut mem_req = device.get_image_memory_requirements(image);
+        collect.retain(|i| mem_req.memoryTypeBits & (1 << i) != 0);
+        // none of the allowed memory types has the requested properties
         let mut mem_alloc_info = VkMemoryAllocateInfoBuilder::new()
             .allocation_size(mem_req.size)
-            .memory_type_index(collect[0])
+            .memory_type_index(*collect.first().ok_or(VkError::OutOfDeviceMemory)?)
             .build();
 
         device.allocate_memory(&mem_alloc_info, None)
     }
 
     #[inline]
-    pub fn to_gpu<T>(device: &VkDevice, gpu: &VkDeviceMemory, cpu: (*const T, usize)) {
-        let mapped = device.map_memory(0, cpu.1 as u64, 0, gpu).unwrap();
+    pub fn to_gpu<T>(
+        device: &VkDevice,
+        gpu: &VkDeviceMemory,
+        cpu: (*const T, usize),
+    ) -> Result<(), VkError> {
+        let mapped = device.map_memory(0, cpu.1 as u64, 0, gpu)?;
         unsafe {
             std::ptr::copy_nonoverlapping(cpu.0, mapped.cast(), cpu.1);
         }
         device.unmap_memory(gpu);
+        Ok(())
     }
 }
 
@@ -87,7 +102,7 @@ pub trait MemoryFunctions {
         offset: u64,
         size: u64,
         flags: u32,
-    ) -> anyhow::Result<*mut std::os::raw::c_void> {
+    ) -> Result<*mut std::os::raw::c_void, VkError> {
         self.device().map_memory(offset, size, flags, self.memory())
     }
 
@@ -99,14 +114,17 @@ pub trait MemoryFunctions {
         self.device().free_memory(self.memory(), p_allocator);
     }
 
-    fn invalidate_mapped_memory_ranges(&self, mapped_memory_range: &[VkMappedMemoryRange]) {
+    fn invalidate_mapped_memory_ranges(
+        &self,
+        mapped_memory_range: &[VkMappedMemoryRange],
+    ) -> Result<(), VkError> {
         self.device().invalidate_mapped_memory_ranges(
             mapped_memory_range.len() as u32,
             mapped_memory_range.as_ptr(),
-        );
+        )
     }
 
-    fn bind_memory(&self, offset: VkDeviceSize); // buffer or image
+    fn bind_memory(&self, offset: VkDeviceSize) -> Result<(), VkError>; // buffer or image
 }
 
 pub struct PushConstant<T> {
@@ -117,11 +135,7 @@ pub struct PushConstant<T> {
 
 impl<T> PushConstant<T> {
     pub fn new(stage: VkShaderStageFlagBits, data: *const T, size: u32) -> Self {
-        Self {
-            stage: stage as u32,
-            data: data,
-            size: size,
-        }
+        Self { stage, data, size }
     }
 
     pub fn vksize(&self) -> u32 {
@@ -147,8 +161,8 @@ impl<T> PushConstant<T> {
     pub fn range_custom(&self, offset: u32, size: u32) -> VkPushConstantRange {
         VkPushConstantRange {
             stageFlags: self.stage,
-            offset: offset,
-            size: size,
+            offset,
+            size,
         }
     }
 }
@@ -5,11 +5,17 @@ pub struct TextureBuilder<'a, T, const dim: usize> {
     create_info: VkImageCreateInfo,
     mem_prop_flags: VkMemoryPropertyFlagBits,
     data: (Option<*const T>, [u32; dim]),
+    physical_device: VkPhysicalDevice,
     device: &'a VkDevice,
+    name: Option<String>,
 }
 
 impl<'a, T, const dim: usize> TextureBuilder<'a, T, dim> {
-    pub fn new(data: (Option<*const T>, [u32; dim]), device: &'a VkDevice) -> Self {
+    pub fn new(
+        data: (Option<*const T>, [u32; dim]),
+        physical_device: VkPhysicalDevice,
+        device: &'a VkDevice,
+    ) -> Self {
         // default create info
         let create_info = VkImageCreateInfoBuilder::new()
             .mip_levels(1)
@@ -23,7 +29,9 @@ impl<'a, T, const dim: usize> TextureBuilder<'a, T, dim> {
             create_info,
             mem_prop_flags,
             data,
+            physical_device,
             device,
+            name: None,
         }
     }
 
@@ -57,16 +65,20 @@ impl<'a, T, const dim: usize> TextureBuilder<'a, T, dim> {
         self
     }
 
-    pub fn usage(mut self, usage: VkImageUsageFlagBits) -> Self {
-        self.create_info.usage = usage as VkImageUsageFlags;
+    pub fn usage(mut self, usage: impl Into<ImageUsageFlags>) -> Self {
+        self.create_info.usage = usage.into().bits();
+        self
+    }
+
+    // debug name of the image, its memory and the staging buffer
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
         self
     }
 
-    pub fn build(mut self) -> TextureImpl<'a, T, dim> {
+    pub fn build(mut self) -> Result<TextureImpl<'a, T, dim>, VkError> {
         let mut shape = [1, 1, 1];
-        for i in 0..dim {
-            shape[i] = self.data.1[i]
-        }
+        shape[..dim].copy_from_slice(&self.data.1[..dim]);
 
         self.create_info.extent = VkExtent3D {
             width: shape[0],
@@ -74,53 +86,32 @@ impl<'a, T, const dim: usize> TextureBuilder<'a, T, dim> {
             depth: shape[2],
         };
 
-        match dim {
-            1 => {
-                self.create_info.imageType = VkImageType::VK_IMAGE_TYPE_1D;
-                Texture::D1(TextureImpl::new(
-                    (self.data.0, [self.data.1[0]]),
-                    self.create_info,
-                    self.mem_prop_flags,
-                    self.device,
-                ))
-            }
-            2 => {
-                self.create_info.imageType = VkImageType::VK_IMAGE_TYPE_2D;
-                Texture::D2(TextureImpl::new(
-                    (self.data.0, [self.data.1[0], self.data.1[1]]),
-                    self.create_info,
-                    self.mem_prop_flags,
-                    self.device,
-                ))
-            }
-            3 => {
-                self.create_info.imageType = VkImageType::VK_IMAGE_TYPE_3D;
-                Texture::D3(TextureImpl::new(
-                    (
-                        self.data.0,
-                        [self.data.1[0], self.data.1[1], self.data.1[2]],
-                    ),
-                    self.create_info,
-                    self.mem_prop_flags,
-                    self.device,
-                ))
-            }
+        self.create_info.imageType = match dim {
+            1 => VkImageType::VK_IMAGE_TYPE_1D,
+            2 => VkImageType::VK_IMAGE_TYPE_2D,
+            3 => VkImageType::VK_IMAGE_TYPE_3D,
             _ => todo!(),
         };
 
-        TextureImpl::new(
+        let mut texture = TextureImpl::new(
             self.data,
             self.create_info,
             self.mem_prop_flags,
+            self.physical_device,
             self.device,
-        )
+        )?;
+        if let Some(name) = &self.name {
+            texture.set_name(name)?;
+        }
+        Ok(texture)
     }
 }
 
-pub fn texture_builder_from_image(
+pub fn texture_builder_from_image<'a>(
     image: std::fs::File,
-    device: &VkDevice,
-) -> TextureBuilder<'_, u8, 2> {
+    physical_device: VkPhysicalDevice,
+    device: &'a VkDevice,
+) -> TextureBuilder<'a, u8, 2> {
     let decoder = png::Decoder::new(image);
     let mut reader = decoder.read_info().unwrap();
 
@@ -138,17 +129,22 @@ pub fn texture_builder_from_image(
         _ => VkFormat::VK_FORMAT_R32G32B32A32_SINT,
     };
 
-    let mut builder = TextureBuilder::new((Some(pixels.as_ptr()), [width, height]), device);
+    let mut builder = TextureBuilder::new(
+        (Some(pixels.as_ptr()), [width, height]),
+        physical_device,
+        device,
+    );
     // builder = builder.format(format);
     builder
 }
 
 pub fn texture_builder_from_path<'a>(
     path: &'static str,
+    physical_device: VkPhysicalDevice,
     device: &'a VkDevice,
 ) -> TextureBuilder<'a, u8, 2> {
     let image = std::fs::File::open(path).unwrap();
-    texture_builder_from_image(image, device)
+    texture_builder_from_image(image, physical_device, device)
 }
 
 struct TData<T, const dim: usize> {
@@ -176,6 +172,13 @@ pub struct TextureImpl<'a, T, const dim: usize> {
     gpu_stage: BufferAndMemory,
     cpu: TData<T, dim>,
     info: VkImageCreateInfo,
+    name: Option<String>,
+}
+
+impl<'a, T, const dim: usize> Drop for TextureImpl<'a, T, dim> {
+    fn drop(&mut self) {
+        self.destroy();
+    }
 }
 
 impl<'a, T, const dim: usize> TextureImpl<'a, T, dim> {
@@ -183,54 +186,91 @@ impl<'a, T, const dim: usize> TextureImpl<'a, T, dim> {
         data_: (Option<*const T>, [u32; dim]),
         info: VkImageCreateInfo,
         mem_prop_flags: VkMemoryPropertyFlagBits,
+        physical_device: VkPhysicalDevice,
         device: &'a VkDevice,
-    ) -> Self {
+    ) -> Result<Self, VkError> {
         let cpu = TData {
             ptr_: data_.0,
             len_: data_.1,
         };
 
         let buffer_create_info = VkBufferCreateInfoBuilder::new()
-            .usage(VK_BUFFER_USAGE_TRANSFER_SRC_BIT as VkBufferUsageFlags)
+            .usage(BufferUsageFlags::TRANSFER_SRC)
             .size((cpu.len() * 4 * std::mem::size_of::<T>()) as u64)
             .build();
 
-        let buffer = device.create_buffer(&buffer_create_info, None);
-        let buf_memory = memory_function::allocate_buffer_memory(
+        // a failure drops `texture`, destroying what was created so far
+        let mut texture = Self {
+            device,
+            gpu: ImageAndMemory(null_mut(), null_mut()),
+            gpu_stage: BufferAndMemory(null_mut(), null_mut()),
+            cpu,
+            info,
+            name: None,
+        };
+
+        texture.gpu_stage.0 = device.create_buffer(&buffer_create_info, None)?;
+        texture.gpu_stage.1 = memory_function::allocate_buffer_memory(
+            physical_device,
             device,
-            buffer,
+            texture.gpu_stage.0,
             VK_MEMORY_PROPERTY_HOST_COHERENT_BIT | VK_MEMORY_PROPERTY_HOST_VISIBLE_BIT,
-        );
-        memory_function::to_gpu(device, &buf_memory, (cpu.as_ptr(), cpu.len()));
+        )?;
+        memory_function::to_gpu(
+            device,
+            &texture.gpu_stage.1,
+            (texture.cpu.as_ptr(), texture.cpu.len()),
+        )?;
+        device.bind_buffer_memory(texture.gpu_stage.0, texture.gpu_stage.1, 0)?;
+
+        texture.gpu.0 = device.create_image(&info, None)?;
+        texture.gpu.1 = memory_function::allocate_image_memory(
+            physical_device,
+            device,
+            texture.gpu.0,
+            mem_prop_flags,
+        )?;
+        device.bind_image_memory(texture.gpu.0, texture.gpu.1, 0)?;
 
-        device.bind_buffer_memory(buffer, buf_memory, 0);
-        let gpu_stage = BufferAndMemory(buffer, buf_memory);
+        Ok(texture)
+    }
 
-        let image = device.create_image(&info, None);
-        let img_memory = memory_function::allocate_image_memory(device, image, mem_prop_flags);
+    // views from `make_view` have to be dropped first, called on drop, later calls do nothing
+    pub fn destroy(&mut self) {
+        self.device.destroy_image(self.gpu.0, None);
+        self.device.free_memory(&self.gpu.1, None);
+        self.device.destroy_buffer(self.gpu_stage.0, None);
+        self.device.free_memory(&self.gpu_stage.1, None);
+        self.gpu = ImageAndMemory(null_mut(), null_mut());
+        self.gpu_stage = BufferAndMemory(null_mut(), null_mut());
+    }
 
-        device.bind_image_memory(image, img_memory, 0);
-        let gpu = ImageAndMemory(image, img_memory);
+    pub fn set_name(&mut self, name: &str) -> Result<(), VkError> {
+        self.device.set_object_name(self.gpu.0, name)?;
+        self.device
+            .set_object_name(self.gpu.1, &format!("{} memory", name))?;
+        self.device
+            .set_object_name(self.gpu_stage.0, &format!("{} staging", name))?;
+        self.device
+            .set_object_name(self.gpu_stage.1, &format!("{} staging memory", name))?;
+        self.name = Some(name.to_string());
+        Ok(())
+    }
 
-        Self {
-            device,
-            gpu,
-            cpu,
-            gpu_stage,
-            info,
-        }
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
     }
 
     pub fn transition_image_layout() {}
 
-    pub fn cmd_copy_buffer_to_image(&self, command_pool: VkCommandPool) {
+    pub fn cmd_copy_buffer_to_image(&self, command_pool: VkCommandPool) -> Result<(), VkError> {
         let info = VkCommandBufferAllocateInfoBuilder::new()
             .command_pool(command_pool)
             .level(VkCommandBufferLevel(0))
             .command_buffer_count(1)
             .build();
 
-        let cmds = (*self.device).allocate_command_buffers(&info);
+        let cmds = (*self.device).allocate_command_buffers(&info)?;
 
         vkCmdBlock! {
             THIS cmds[0];
@@ -249,11 +289,13 @@ impl<'a, T, const dim: usize> TextureImpl<'a, T, dim> {
                 .build();
 
             COPY_BUFFER_TO_IMAGE(self.gpu_stage.0, self.gpu.0, VkImageLayout::VK_IMAGE_LAYOUT_TRANSFER_DST_OPTIMAL, 1, &region);
-        };
+        }?;
+
+        Ok(())
     }
 
-    // If want to generate same view with the current
-    pub fn make_view(&self) -> VkImageView {
+    // If want to generate same view with the current, destroyed when the view is dropped
+    pub fn make_view(&self) -> Result<Owned<'a, VkImageView>, VkError> {
         //@@TODO should be consideration
         let subresource_range = VkImageSubresourceRangeBuilder::new()
             .aspect_mask(VK_IMAGE_ASPECT_COLOR_BIT as VkImageAspectFlags)
@@ -283,6 +325,6 @@ impl<'a, T, const dim: usize> TextureImpl<'a, T, dim> {
         };
         let info = builder.build();
 
-        self.device.create_image_view(&info, None)
+        self.device.create_image_view_owned(&info, None)
     }
 }
@@ -0,0 +1,310 @@
+//
+// owned
+// handles destroyed on drop, the optional safe layer over `VkDeviceFunctions`
+//
+use paste::paste;
+
+use crate::*;
+
+/// Handles an `Owned` can destroy
+pub trait VkDestroy: VkHandle {
+    fn destroy(device: &VkDevice, handle: Self, p_allocator: Option<*const VkAllocationCallbacks>);
+}
+
+macro_rules! vk_destroy {
+    ($($handle:ty => $destroy:ident),* $(,)?) => {
+        $(
+            impl VkDestroy for $handle {
+                fn destroy(
+                    device: &VkDevice,
+                    handle: Self,
+                    p_allocator: Option<*const VkAllocationCallbacks>,
+                ) {
+                    device.$destroy(handle, p_allocator);
+                }
+            }
+        )*
+    };
+}
+
+vk_destroy! {
+    VkCommandPool => destroy_command_pool,
+    VkBuffer => destroy_buffer,
+    VkImage => destroy_image,
+    VkImageView => destroy_image_view,
+    VkSampler => destroy_sampler,
+    VkDescriptorPool => destroy_descriptor_pool,
+    VkDescriptorSetLayout => destroy_descriptor_set_layout,
+    VkFence => destroy_fence,
+    VkSemaphore => destroy_semaphore,
+    VkSwapchainKHR => destroy_swapchain,
+    VkRenderPass => destroy_render_pass,
+    VkFramebuffer => destroy_framebuffer,
+    VkPipelineCache => destroy_pipeline_cache,
+    VkPipelineLayout => destroy_pipeline_layout,
+    VkPipeline => destroy_pipeline,
+    VkShaderModule => destroy_shader_module,
+    VkQueryPool => destroy_query_pool,
+    VkEvent => destroy_event,
+    VkBufferView => destroy_buffer_view,
+    VkSamplerYcbcrConversion => destroy_sampler_ycbcr_conversion,
+    VkDescriptorUpdateTemplate => destroy_descriptor_update_template,
+    VkPrivateDataSlot => destroy_private_data_slot,
+}
+
+impl VkDestroy for VkDeviceMemory {
+    fn destroy(device: &VkDevice, handle: Self, p_allocator: Option<*const VkAllocationCallbacks>) {
+        device.free_memory(&handle, p_allocator);
+    }
+}
+
+///
+/// A handle destroyed on drop, borrowing the `VkDevice` it was created on
+///
+/// It cannot be used after it is destroyed and derefs to the raw handle,
+/// so it still goes wherever the handle does:
+///
+/// ```ignore
+/// let buffer = device.create_buffer_owned(&buffer_create_info, None)?;
+/// let memory = device.allocate_memory_owned(&memory_allocate_info, None)?;
+/// device.bind_buffer_memory(*buffer, *memory, 0)?;
+/// // memory and buffer are destroyed here, before the borrow of `device` ends
+/// ```
+///
+/// # Safety
+/// `VkDevice` is a `Copy` pointer, the borrow keeps that variable alive but not the device behind it.
+/// Nothing stops `VulkanHandler::destroy` (or dropping the handler) while an `Owned` is left,
+/// its drop then calls `vkDestroy*` on a destroyed device.
+/// Drop every `Owned` first, e.g. borrow `handler.device` rather than a copy of it
+/// so the borrow checker rejects `handler.destroy()`; debug builds report the objects still
+/// alive when the device goes (see `VulkanHandler::live_objects`).
+///
+pub struct Owned<'d, H: VkDestroy> {
+    handle: H,
+    device: &'d VkDevice,
+    p_allocator: Option<*const VkAllocationCallbacks>,
+}
+
+impl<'d, H: VkDestroy> Owned<'d, H> {
+    ///
+    /// Takes over `handle`
+    ///
+    /// # Safety
+    /// `handle` was created on `device` with `p_allocator` and nothing else destroys it.
+    ///
+    pub unsafe fn from_raw(
+        device: &'d VkDevice,
+        handle: H,
+        p_allocator: Option<*const VkAllocationCallbacks>,
+    ) -> Self {
+        Self {
+            handle,
+            device,
+            p_allocator,
+        }
+    }
+
+    pub fn device(&self) -> &'d VkDevice {
+        self.device
+    }
+
+    // gives the handle back without destroying it
+    pub fn into_raw(self) -> H {
+        let handle = self.handle;
+        std::mem::forget(self);
+        handle
+    }
+}
+
+impl<'d, H: VkDestroy> std::ops::Deref for Owned<'d, H> {
+    type Target = H;
+
+    fn deref(&self) -> &H {
+        &self.handle
+    }
+}
+
+impl<'d, H: VkDestroy> Drop for Owned<'d, H> {
+    fn drop(&mut self) {
+        H::destroy(self.device, self.handle, self.p_allocator);
+    }
+}
+
+impl<'d, H: VkDestroy> std::fmt::Debug for Owned<'d, H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Owned<{}>({:#x})", H::TYPE_NAME, self.handle.as_raw())
+    }
+}
+
+macro_rules! owned_func {
+    (DECLARE $name:expr $(, $khr:expr)?) => {
+        paste! {
+            fn [<create_ $name:snake _owned>](
+                &self,
+                [<$name:snake _create_info>]: *const [<Vk $name CreateInfo $($khr)?>],
+                p_allocator: Option<*const VkAllocationCallbacks>,
+            ) -> Result<Owned<'_, [<Vk $name $($khr)?>]>, VkError>;
+        }
+    };
+
+    (DEFINE $name:expr $(, $khr:expr)?) => {
+        paste! {
+            fn [<create_ $name:snake _owned>](
+                &self,
+                [<$name:snake _create_info>]: *const [<Vk $name CreateInfo $($khr)?>],
+                p_allocator: Option<*const VkAllocationCallbacks>,
+            ) -> Result<Owned<'_, [<Vk $name $($khr)?>]>, VkError> {
+                let handle = self.[<create_ $name:snake>]([<$name:snake _create_info>], p_allocator)?;
+                Ok(unsafe { Owned::from_raw(self, handle, p_allocator) })
+            }
+        }
+    };
+
+    (DC_PIPE $name:expr) => {
+        paste! {
+            fn [<create_ $name:snake s_owned>](
+                &self,
+                pipeline_cache: VkPipelineCache,
+                [<$name:snake _create_info>]: &[[<Vk $name CreateInfo>]],
+                p_allocator: Option<*const VkAllocationCallbacks>,
+            ) -> Result<Vec<Owned<'_, VkPipeline>>, VkError>;
+        }
+    };
+
+    (DF_PIPE $name:expr) => {
+        paste! {
+            fn [<create_ $name:snake s_owned>](
+                &self,
+                pipeline_cache: VkPipelineCache,
+                [<$name:snake _create_info>]: &[[<Vk $name CreateInfo>]],
+                p_allocator: Option<*const VkAllocationCallbacks>,
+            ) -> Result<Vec<Owned<'_, VkPipeline>>, VkError> {
+                let pipelines = self.[<create_ $name:snake s>](
+                    pipeline_cache,
+                    [<$name:snake _create_info>],
+                    p_allocator,
+                )?;
+                Ok(pipelines
+                    .into_iter()
+                    .map(|pipeline| unsafe { Owned::from_raw(self, pipeline, p_allocator) })
+                    .collect())
+            }
+        }
+    };
+}
+
+///
+/// `create_*_owned` counterparts of the `VkDeviceFunctions` constructors
+///
+pub trait VkOwnedFunctions {
+    owned_func!(DECLARE CommandPool);
+    owned_func!(DECLARE Buffer);
+    owned_func!(DECLARE Image);
+    owned_func!(DECLARE ImageView);
+    owned_func!(DECLARE Sampler);
+    owned_func!(DECLARE DescriptorPool);
+    owned_func!(DECLARE DescriptorSetLayout);
+    owned_func!(DECLARE Fence);
+    owned_func!(DECLARE Semaphore);
+    owned_func!(DECLARE Swapchain, KHR);
+    owned_func!(DECLARE RenderPass);
+    owned_func!(DECLARE Framebuffer);
+    owned_func!(DECLARE PipelineCache);
+    owned_func!(DECLARE PipelineLayout);
+    owned_func!(DECLARE QueryPool);
+    owned_func!(DECLARE Event);
+    owned_func!(DECLARE BufferView);
+    owned_func!(DECLARE SamplerYcbcrConversion);
+    owned_func!(DECLARE DescriptorUpdateTemplate);
+    owned_func!(DECLARE PrivateDataSlot);
+    owned_func!(DC_PIPE ComputePipeline);
+    owned_func!(DC_PIPE GraphicsPipeline);
+
+    fn create_shader_module_owned(
+        &self,
+        code: &[u8],
+        p_allocator: Option<*const VkAllocationCallbacks>,
+    ) -> Result<Owned<'_, VkShaderModule>, VkError>;
+
+    fn allocate_memory_owned(
+        &self,
+        memory_allocate_info: *const VkMemoryAllocateInfo,
+        p_allocator: Option<*const VkAllocationCallbacks>,
+    ) -> Result<Owned<'_, VkDeviceMemory>, VkError>;
+}
+
+impl VkOwnedFunctions for VkDevice {
+    owned_func!(DEFINE CommandPool);
+    owned_func!(DEFINE Buffer);
+    owned_func!(DEFINE Image);
+    owned_func!(DEFINE ImageView);
+    owned_func!(DEFINE Sampler);
+    owned_func!(DEFINE DescriptorPool);
+    owned_func!(DEFINE DescriptorSetLayout);
+    owned_func!(DEFINE Fence);
+    owned_func!(DEFINE Semaphore);
+    owned_func!(DEFINE Swapchain, KHR);
+    owned_func!(DEFINE RenderPass);
+    owned_func!(DEFINE Framebuffer);
+    owned_func!(DEFINE PipelineCache);
+    owned_func!(DEFINE PipelineLayout);
+    owned_func!(DEFINE QueryPool);
+    owned_func!(DEFINE Event);
+    owned_func!(DEFINE BufferView);
+    owned_func!(DEFINE SamplerYcbcrConversion);
+    owned_func!(DEFINE DescriptorUpdateTemplate);
+    owned_func!(DEFINE PrivateDataSlot);
+    owned_func!(DF_PIPE ComputePipeline);
+    owned_func!(DF_PIPE GraphicsPipeline);
+
+    fn create_shader_module_owned(
+        &self,
+        code: &[u8],
+        p_allocator: Option<*const VkAllocationCallbacks>,
+    ) -> Result<Owned<'_, VkShaderModule>, VkError> {
+        let module = self.create_shader_module(code, p_allocator)?;
+        Ok(unsafe { Owned::from_raw(self, module, p_allocator) })
+    }
+
+    fn allocate_memory_owned(
+        &self,
+        memory_allocate_info: *const VkMemoryAllocateInfo,
+        p_allocator: Option<*const VkAllocationCallbacks>,
+    ) -> Result<Owned<'_, VkDeviceMemory>, VkError> {
+        let memory = self.allocate_memory(memory_allocate_info, p_allocator)?;
+        Ok(unsafe { Owned::from_raw(self, memory, p_allocator) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a dispatchable handle only has to start with its dispatch key, this one belongs to no device
+    // so the destroy commands are skipped, what is left to see is the tracking
+    static KEY: usize = 0x4001;
+
+    fn live_handles(device: VkDevice) -> Vec<u64> {
+        tracking::live_objects(device)
+            .iter()
+            .map(|object| object.handle)
+            .collect()
+    }
+
+    #[test]
+    #[cfg_attr(not(debug_assertions), ignore = "only debug builds keep track")]
+    fn into_raw_gives_the_handle_back_without_destroying_it() {
+        let device = &KEY as *const usize as VkDevice;
+        let kept = 0x10 as VkBuffer;
+        let dropped = 0x20 as VkBuffer;
+        tracking::track(device, kept);
+        tracking::track(device, dropped);
+
+        let owned = unsafe { Owned::from_raw(&device, kept, None) };
+        assert_eq!(owned.into_raw(), kept);
+        drop(unsafe { Owned::from_raw(&device, dropped, None) });
+
+        assert_eq!(live_handles(device), [0x10]);
+        tracking::report_leaks(device);
+    }
+}
@@ -0,0 +1,369 @@
+//
+// physical device
+// filtering by requirements, ranking of the devices that are left
+//
+use std::cmp::Reverse;
+
+use crate::*;
+
+#[derive(Debug)]
+pub enum PhysicalDeviceError {
+    NoPhysicalDevice,
+    // name of every physical device with the reason it was rejected
+    NoSuitableDevice(Vec<(String, String)>),
+}
+
+impl std::fmt::Display for PhysicalDeviceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PhysicalDeviceError::NoPhysicalDevice => {
+                write!(f, "[vrx] the instance has no physical device")
+            }
+            PhysicalDeviceError::NoSuitableDevice(rejected) => {
+                write!(f, "[vrx] no physical device meets the requirements")?;
+                for (name, reason) in rejected {
+                    write!(f, "\n    {}: {}", name, reason)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for PhysicalDeviceError {}
+
+/// A physical device that met every requirement of the selector
+#[derive(Debug, Clone)]
+pub struct PhysicalDeviceCandidate {
+    pub physical_device: VkPhysicalDevice,
+    pub name: String,
+    pub device_type: VkPhysicalDeviceType,
+    // sum of the device local heaps
+    pub device_local_memory: u64,
+}
+
+///
+/// Picks the physical device a `VulkanHandler` is created on
+///
+/// Devices missing a required queue family, extension, feature or surface support are dropped,
+/// the rest are ranked by device type (discrete, integrated, virtual, cpu) and then by device local memory.
+///
+/// ```ignore
+/// let physical_device = PhysicalDeviceSelector::new(&ctx)
+///     .queue(QueueFlags::GRAPHICS)
+///     .extension("VK_KHR_swapchain")
+///     .surface(surface)
+///     .select()?;
+/// ```
+///
+pub struct PhysicalDeviceSelector<'a> {
+    context: &'a Context,
+    queue_flags: Vec<QueueFlags>,
+    extensions: Vec<String>,
+    features: VkPhysicalDeviceFeatures,
+    surface: Option<VkSurfaceKHR>,
+    preferred_type: Option<VkPhysicalDeviceType>,
+}
+
+impl<'a> PhysicalDeviceSelector<'a> {
+    pub fn new(context: &'a Context) -> Self {
+        Self {
+            context,
+            queue_flags: vec![],
+            extensions: vec![],
+            features: VkPhysicalDeviceFeatures::default(),
+            surface: None,
+            preferred_type: None,
+        }
+    }
+
+    // one queue family has to support all of `flags`
+    pub fn queue(mut self, flags: impl Into<QueueFlags>) -> Self {
+        self.queue_flags.push(flags.into());
+        self
+    }
+
+    pub fn extension(mut self, name: &str) -> Self {
+        self.extensions.push(name.to_string());
+        self
+    }
+
+    // every VK_TRUE field has to be supported
+    pub fn features(mut self, features: VkPhysicalDeviceFeatures) -> Self {
+        self.features = features;
+        self
+    }
+
+    // one queue family has to present to `surface`
+    pub fn surface(mut self, surface: VkSurfaceKHR) -> Self {
+        self.surface = Some(surface);
+        self
+    }
+
+    ///
+    /// Ranks `device_type` first, e.g. the integrated gpu of a laptop to save power
+    ///
+    pub fn prefer(mut self, device_type: VkPhysicalDeviceType) -> Self {
+        self.preferred_type = Some(device_type);
+        self
+    }
+
+    pub fn select(&self) -> Result<VkPhysicalDevice, PhysicalDeviceError> {
+        Ok(self.candidates()?[0].physical_device)
+    }
+
+    // suitable devices, best first
+    pub fn candidates(&self) -> Result<Vec<PhysicalDeviceCandidate>, PhysicalDeviceError> {
+        if self.context.physical_devices.is_empty() {
+            return Err(PhysicalDeviceError::NoPhysicalDevice);
+        }
+
+        let mut candidates = vec![];
+        let mut rejected = vec![];
+        for physical_device in &self.context.physical_devices {
+            let properties = physical_device.get_properties();
+            let name = unsafe { CStr::from_ptr(properties.deviceName.as_ptr()) }
+                .to_string_lossy()
+                .into_owned();
+
+            match self.check(*physical_device, &properties) {
+                Ok(()) => candidates.push(PhysicalDeviceCandidate {
+                    physical_device: *physical_device,
+                    name,
+                    device_type: properties.deviceType,
+                    device_local_memory: device_local_memory(
+                        &physical_device.get_memory_properties(),
+                    ),
+                }),
+                Err(reason) => rejected.push((name, reason)),
+            }
+        }
+
+        if candidates.is_empty() {
+            return Err(PhysicalDeviceError::NoSuitableDevice(rejected));
+        }
+
+        rank(&mut candidates, self.preferred_type);
+        Ok(candidates)
+    }
+
+    // the reason of the rejection
+    fn check(
+        &self,
+        physical_device: VkPhysicalDevice,
+        properties: &VkPhysicalDeviceProperties,
+    ) -> Result<(), String> {
+        if properties.apiVersion < self.context.api_version {
+            return Err(format!(
+                "supports vulkan {} only",
+                context::version_string(properties.apiVersion)
+            ));
+        }
+
+        let queue_families = physical_device.get_queue_family_properties();
+        for flags in &self.queue_flags {
+            let found = queue_families.iter().any(|family| {
+                family.queueCount > 0 && QueueFlags::from(family.queueFlags).contains(*flags)
+            });
+            if !found {
+                return Err(format!("no queue family supports {:?}", flags));
+            }
+        }
+
+        let available = physical_device
+            .enumerate_device_extension_names()
+            .map_err(|e| e.to_string())?;
+        if let Some(missing) = self.extensions.iter().find(|e| !available.contains(e)) {
+            return Err(format!("extension {} is not supported", missing));
+        }
+
+        // the member names come from the table of `DeviceFeatures`
+        let required = DeviceFeatures {
+            core: self.features,
+            ..DeviceFeatures::new()
+        };
+        let supported = DeviceFeatures {
+            core: physical_device.get_features(),
+            ..DeviceFeatures::new()
+        };
+        if let Err(DeviceError::MissingFeature(structure, member)) =
+            DeviceFeatures::negotiate(&required, &DeviceFeatures::new(), &supported)
+        {
+            return Err(format!(
+                "feature {}::{} is not supported",
+                structure, member
+            ));
+        }
+
+        if let Some(surface) = self.surface {
+            let mut presentable = false;
+            for index in 0..queue_families.len() as u32 {
+                if physical_device
+                    .get_surface_support_khr(index, surface)
+                    .map_err(|e| e.to_string())?
+                {
+                    presentable = true;
+                    break;
+                }
+            }
+            if !presentable {
+                return Err("no queue family presents to the surface".to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// best first, stable so that ties keep the enumeration order
+fn rank(candidates: &mut [PhysicalDeviceCandidate], preferred_type: Option<VkPhysicalDeviceType>) {
+    candidates.sort_by_key(|c| {
+        Reverse((
+            device_type_rank(c.device_type, preferred_type),
+            c.device_local_memory,
+        ))
+    });
+}
+
+fn device_type_rank(
+    device_type: VkPhysicalDeviceType,
+    preferred_type: Option<VkPhysicalDeviceType>,
+) -> u32 {
+    if Some(device_type) == preferred_type {
+        return 5;
+    }
+    match device_type {
+        VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_DISCRETE_GPU => 4,
+        VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_INTEGRATED_GPU => 3,
+        VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_VIRTUAL_GPU => 2,
+        VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_CPU => 1,
+        _ => 0,
+    }
+}
+
+fn device_local_memory(memory_properties: &VkPhysicalDeviceMemoryProperties) -> u64 {
+    memory_properties.memoryHeaps[..memory_properties.memoryHeapCount as usize]
+        .iter()
+        .filter(|heap| heap.flags & VK_MEMORY_HEAP_DEVICE_LOCAL_BIT as VkMemoryHeapFlags != 0)
+        .map(|heap| heap.size)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(
+        name: &str,
+        device_type: VkPhysicalDeviceType,
+        device_local_memory: u64,
+    ) -> PhysicalDeviceCandidate {
+        PhysicalDeviceCandidate {
+            physical_device: null_mut(),
+            name: name.to_string(),
+            device_type,
+            device_local_memory,
+        }
+    }
+
+    fn ranked(preferred_type: Option<VkPhysicalDeviceType>) -> Vec<String> {
+        let mut candidates = vec![
+            candidate(
+                "llvmpipe",
+                VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_CPU,
+                1 << 34,
+            ),
+            candidate(
+                "igpu",
+                VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_INTEGRATED_GPU,
+                1 << 30,
+            ),
+            candidate(
+                "small dgpu",
+                VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_DISCRETE_GPU,
+                4 << 30,
+            ),
+            candidate(
+                "other",
+                VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_OTHER,
+                1 << 40,
+            ),
+            candidate(
+                "dgpu",
+                VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_DISCRETE_GPU,
+                8 << 30,
+            ),
+            candidate(
+                "dgpu twin",
+                VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_DISCRETE_GPU,
+                8 << 30,
+            ),
+            candidate(
+                "vgpu",
+                VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_VIRTUAL_GPU,
+                2 << 30,
+            ),
+        ];
+        rank(&mut candidates, preferred_type);
+        candidates.into_iter().map(|c| c.name).collect()
+    }
+
+    #[test]
+    fn device_types_rank_discrete_integrated_virtual_cpu() {
+        let ranks: Vec<u32> = [
+            VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_DISCRETE_GPU,
+            VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_INTEGRATED_GPU,
+            VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_VIRTUAL_GPU,
+            VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_CPU,
+            VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_OTHER,
+        ]
+        .iter()
+        .map(|device_type| device_type_rank(*device_type, None))
+        .collect();
+        assert_eq!(ranks, [4, 3, 2, 1, 0]);
+
+        // the preferred type goes above discrete gpus
+        let preferred = Some(VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_CPU);
+        assert_eq!(
+            device_type_rank(VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_CPU, preferred),
+            5
+        );
+        assert_eq!(
+            device_type_rank(
+                VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_DISCRETE_GPU,
+                preferred
+            ),
+            4
+        );
+    }
+
+    #[test]
+    fn candidates_rank_by_type_then_memory_then_enumeration_order() {
+        assert_eq!(
+            ranked(None),
+            [
+                "dgpu",
+                "dgpu twin",
+                "small dgpu",
+                "igpu",
+                "vgpu",
+                "llvmpipe",
+                "other"
+            ]
+        );
+        assert_eq!(
+            ranked(Some(
+                VkPhysicalDeviceType::VK_PHYSICAL_DEVICE_TYPE_INTEGRATED_GPU
+            )),
+            [
+                "igpu",
+                "dgpu",
+                "dgpu twin",
+                "small dgpu",
+                "vgpu",
+                "llvmpipe",
+                "other"
+            ]
+        );
+    }
+}
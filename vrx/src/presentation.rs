@@ -0,0 +1,350 @@
+//
+// presentation
+// acquire/present over a swapchain or over plain images that are read back
+//
+use std::path::PathBuf;
+
+use crate::memory::memory_function;
+use crate::*;
+
+///
+/// Render target of a frame loop
+///
+/// The swapchain presentation of vrx-graphics and `OffscreenPresentation` share it,
+/// so the same render loop runs with a window or headless.
+///
+pub trait Present {
+    fn images(&self) -> &[VkImage];
+    fn image_views(&self) -> &[VkImageView];
+    fn format(&self) -> VkSurfaceFormatKHR;
+    fn extent(&self) -> VkExtent2D;
+
+    // layout the render pass leaves the images in
+    fn final_layout(&self) -> VkImageLayout;
+
+    /// Index of the next image, `semaphore` is signaled once it can be rendered to.
+    /// `None` when the target is out of date and has to be recreated.
+    fn acquire(&mut self, semaphore: VkSemaphore) -> anyhow::Result<Option<u32>>;
+
+    /// Presents the image once `wait_semaphores` are signaled,
+    /// true when the target changed and has to be recreated.
+    fn present(
+        &mut self,
+        queue: VkQueue,
+        wait_semaphores: &[VkSemaphore],
+        image_index: u32,
+    ) -> anyhow::Result<bool>;
+
+    // before recreating the target, implementations also call it on drop so it has to be idempotent
+    fn destroy(&mut self);
+}
+
+///
+/// Presentation into plain images
+///
+/// `present` copies the image into a host visible buffer, the last frame is kept in `pixels`
+/// and written to `<output_dir>/frame_0000.png`, `frame_0001.png` ... when an output directory is set.
+///
+pub struct OffscreenPresentation<'a> {
+    images: Vec<VkImage>,
+    memories: Vec<VkDeviceMemory>,
+    image_views: Vec<VkImageView>,
+    format: VkSurfaceFormatKHR,
+    extent: VkExtent2D,
+
+    readback: VkBuffer,
+    readback_memory: VkDeviceMemory,
+    command_pool: VkCommandPool,
+    command_buffer: VkCommandBuffer,
+    fence: VkFence,
+    queue: VkQueue,
+
+    next: u32,
+    frame: usize,
+    output_dir: Option<PathBuf>,
+    pub pixels: Vec<u8>,
+
+    device: &'a VkDevice,
+}
+
+impl<'a> OffscreenPresentation<'a> {
+    pub const FORMAT: VkFormat = VkFormat::VK_FORMAT_R8G8B8A8_SRGB;
+
+    pub fn new(
+        physical_device: VkPhysicalDevice,
+        device: &'a VkDevice,
+        queue_family_index: u32,
+        extent: VkExtent2D,
+        image_count: u32,
+    ) -> Result<Self, VkError> {
+        let format = VkSurfaceFormatKHR {
+            format: Self::FORMAT,
+            colorSpace: VK_COLOR_SPACE_SRGB_NONLINEAR_KHR,
+        };
+
+        let image_create_info = VkImageCreateInfoBuilder::new()
+            .image_type(VkImageType::VK_IMAGE_TYPE_2D)
+            .format(format.format)
+            .extent(VkExtent3D {
+                width: extent.width,
+                height: extent.height,
+                depth: 1,
+            })
+            .mip_levels(1)
+            .array_layers(1)
+            .samples(VK_SAMPLE_COUNT_1_BIT)
+            .tiling(VkImageTiling::VK_IMAGE_TILING_OPTIMAL)
+            .usage(ImageUsageFlags::COLOR_ATTACHMENT | ImageUsageFlags::TRANSFER_SRC)
+            .sharing_mode(VkSharingMode::VK_SHARING_MODE_EXCLUSIVE)
+            .initial_layout(VkImageLayout::VK_IMAGE_LAYOUT_UNDEFINED)
+            .build();
+
+        // a failure drops `presentation`, destroying what was created so far
+        let mut presentation = Self {
+            images: vec![],
+            memories: vec![],
+            image_views: vec![],
+            format,
+            extent,
+            readback: null_mut(),
+            readback_memory: null_mut(),
+            command_pool: null_mut(),
+            command_buffer: null_mut(),
+            fence: null_mut(),
+            queue: device.get_queue(queue_family_index, 0),
+            next: 0,
+            frame: 0,
+            output_dir: None,
+            pixels: vec![],
+            device,
+        };
+
+        for _ in 0..image_count {
+            let image = device.create_image(&image_create_info, None)?;
+            presentation.images.push(image);
+            let memory = memory_function::allocate_image_memory(
+                physical_device,
+                device,
+                image,
+                VK_MEMORY_PROPERTY_DEVICE_LOCAL_BIT,
+            )?;
+            presentation.memories.push(memory);
+            device.bind_image_memory(image, memory, 0)?;
+
+            let subresource_range = VkImageSubresourceRangeBuilder::new()
+                .aspect_mask(VK_IMAGE_ASPECT_COLOR_BIT as VkImageAspectFlags)
+                .base_mip_level(0)
+                .level_count(1)
+                .base_array_layer(0)
+                .layer_count(1)
+                .build();
+            let image_view_create_info = VkImageViewCreateInfoBuilder::new()
+                .image(image)
+                .view_type(VkImageViewType::VK_IMAGE_VIEW_TYPE_2D)
+                .format(format.format)
+                .subresource_range(subresource_range)
+                .build();
+
+            presentation
+                .image_views
+                .push(device.create_image_view(&image_view_create_info, None)?);
+        }
+
+        // tightly packed rgba8
+        let buffer_create_info = VkBufferCreateInfoBuilder::new()
+            .size((extent.width * extent.height * 4) as u64)
+            .usage(BufferUsageFlags::TRANSFER_DST)
+            .sharing_mode(VkSharingMode::VK_SHARING_MODE_EXCLUSIVE)
+            .build();
+        presentation.readback = device.create_buffer(&buffer_create_info, None)?;
+        presentation.readback_memory = memory_function::allocate_buffer_memory(
+            physical_device,
+            device,
+            presentation.readback,
+            VK_MEMORY_PROPERTY_HOST_COHERENT_BIT | VK_MEMORY_PROPERTY_HOST_VISIBLE_BIT,
+        )?;
+        device.bind_buffer_memory(presentation.readback, presentation.readback_memory, 0)?;
+
+        let command_pool_create_info = VkCommandPoolCreateInfoBuilder::new()
+            .flags(VK_COMMAND_POOL_CREATE_RESET_COMMAND_BUFFER_BIT as VkCommandPoolCreateFlags)
+            .queue_family_index(queue_family_index)
+            .build();
+        presentation.command_pool = device.create_command_pool(&command_pool_create_info, None)?;
+        let command_buffer_allocate_info = VkCommandBufferAllocateInfoBuilder::new()
+            .command_pool(presentation.command_pool)
+            .level(VkCommandBufferLevel::VK_COMMAND_BUFFER_LEVEL_PRIMARY)
+            .command_buffer_count(1)
+            .build();
+        // freed with the pool
+        presentation.command_buffer =
+            device.allocate_command_buffers(&command_buffer_allocate_info)?[0];
+
+        presentation.fence = device.create_fence(&VkFenceCreateInfoBuilder::new().build(), None)?;
+
+        Ok(presentation)
+    }
+
+    pub fn output_dir(mut self, output_dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = Some(output_dir.into());
+        self
+    }
+
+    // number of presented frames
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    pub fn save_png(&self, path: impl AsRef<std::path::Path>) -> anyhow::Result<()> {
+        let file = std::fs::File::create(path)?;
+        let mut encoder = png::Encoder::new(
+            std::io::BufWriter::new(file),
+            self.extent.width,
+            self.extent.height,
+        );
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        Ok(())
+    }
+
+    fn read_back(
+        &mut self,
+        queue: VkQueue,
+        wait_semaphores: &[VkSemaphore],
+        image_index: u32,
+    ) -> Result<(), VkError> {
+        let cmd = self.command_buffer;
+        let image = self.images[image_index as usize];
+        let readback = self.readback;
+
+        vkCmdBlock! {
+            THIS cmd;
+
+            let subresource = VkImageSubresourceLayers {
+                aspectMask: VK_IMAGE_ASPECT_COLOR_BIT as VkImageAspectFlags,
+                mipLevel: 0,
+                baseArrayLayer: 0,
+                layerCount: 1
+            };
+
+            let region = VkBufferImageCopyBuilder::new()
+                .image_subresource(subresource)
+                .image_offset(VkOffset3D { x: 0, y: 0, z: 0 })
+                .image_extent(VkExtent3D { width: self.extent.width, height: self.extent.height, depth: 1 })
+                .build();
+
+            COPY_IMAGE_TO_BUFFER(image, self.final_layout(), readback, 1, &region);
+        }?;
+
+        // semaphores carry the memory dependency on the color attachment writes
+        let wait_stages =
+            vec![VK_PIPELINE_STAGE_TRANSFER_BIT as VkPipelineStageFlags; wait_semaphores.len()];
+        let submit_info = VkSubmitInfoBuilder::new()
+            .wait_semaphore_count(wait_semaphores.len() as u32)
+            .p_wait_semaphores(wait_semaphores.as_ptr())
+            .p_wait_dst_stage_mask(wait_stages.as_ptr())
+            .command_buffer_count(1)
+            .p_command_buffers(&cmd)
+            .build();
+        queue.submit(&[submit_info], Some(self.fence))?;
+        self.device.wait_for_fence(&[self.fence], true, u64::MAX)?;
+        self.device.reset_fence(&[self.fence])?;
+
+        let size = (self.extent.width * self.extent.height * 4) as usize;
+        let mapped = self
+            .device
+            .map_memory(0, size as u64, 0, &self.readback_memory)?;
+        self.pixels.resize(size, 0);
+        unsafe {
+            std::ptr::copy_nonoverlapping(mapped as *const u8, self.pixels.as_mut_ptr(), size);
+        }
+        self.device.unmap_memory(&self.readback_memory);
+        Ok(())
+    }
+}
+
+impl<'a> Drop for OffscreenPresentation<'a> {
+    fn drop(&mut self) {
+        self.destroy();
+    }
+}
+
+impl<'a> Present for OffscreenPresentation<'a> {
+    fn images(&self) -> &[VkImage] {
+        &self.images
+    }
+
+    fn image_views(&self) -> &[VkImageView] {
+        &self.image_views
+    }
+
+    fn format(&self) -> VkSurfaceFormatKHR {
+        self.format
+    }
+
+    fn extent(&self) -> VkExtent2D {
+        self.extent
+    }
+
+    fn final_layout(&self) -> VkImageLayout {
+        VkImageLayout::VK_IMAGE_LAYOUT_TRANSFER_SRC_OPTIMAL
+    }
+
+    // images are handed out round-robin, `present` already waited for the previous read back
+    fn acquire(&mut self, semaphore: VkSemaphore) -> anyhow::Result<Option<u32>> {
+        if self.images.is_empty() {
+            anyhow::bail!("[vrx] acquire on a destroyed presentation");
+        }
+        let image_index = self.next;
+        self.next = (self.next + 1) % self.images.len() as u32;
+
+        // an empty submission stands in for the presentation engine signaling the semaphore
+        let submit_info = VkSubmitInfoBuilder::new()
+            .signal_semaphore_count(1)
+            .p_signal_semaphores(&semaphore)
+            .build();
+        self.queue.submit(&[submit_info], None)?;
+
+        Ok(Some(image_index))
+    }
+
+    fn present(
+        &mut self,
+        queue: VkQueue,
+        wait_semaphores: &[VkSemaphore],
+        image_index: u32,
+    ) -> anyhow::Result<bool> {
+        self.read_back(queue, wait_semaphores, image_index)?;
+
+        if let Some(dir) = &self.output_dir {
+            std::fs::create_dir_all(dir)?;
+            self.save_png(dir.join(format!("frame_{:04}.png", self.frame)))?;
+        }
+        self.frame += 1;
+
+        Ok(false)
+    }
+
+    fn destroy(&mut self) {
+        self.image_views
+            .drain(..)
+            .for_each(|iv| self.device.destroy_image_view(iv, None));
+        self.images
+            .drain(..)
+            .for_each(|i| self.device.destroy_image(i, None));
+        self.memories
+            .drain(..)
+            .for_each(|m| self.device.free_memory(&m, None));
+
+        self.device.destroy_buffer(self.readback, None);
+        self.device.free_memory(&self.readback_memory, None);
+        self.device.destroy_fence(self.fence, None);
+        self.device.destroy_command_pool(self.command_pool, None);
+        self.readback = null_mut();
+        self.readback_memory = null_mut();
+        self.fence = null_mut();
+        self.command_pool = null_mut();
+    }
+}
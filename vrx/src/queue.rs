@@ -0,0 +1,316 @@
+//
+// queue
+// which queue family and queue index serve each demanded queue type
+//
+use std::collections::BTreeMap;
+
+use crate::*;
+
+#[derive(Debug)]
+pub enum QueuePlanError {
+    NoQueueFamily(QueueType),
+}
+
+impl std::fmt::Display for QueuePlanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueuePlanError::NoQueueFamily(queue_type) => {
+                write!(f, "[vrx] no queue family supports {:?} queues", queue_type)
+            }
+        }
+    }
+}
+
+impl std::error::Error for QueuePlanError {}
+
+/// Queues created on one family, one priority per queue
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueueFamilyPlan {
+    pub family_index: u32,
+    pub priorities: Vec<f32>,
+}
+
+///
+/// Queue families and queues of a device for a list of demands
+///
+/// Compute and transfer demands go to dedicated families when the device has them
+/// (a compute family without graphics, a transfer family without graphics and compute)
+/// and fall back to the shared ones, families with unused queues first.
+/// Several queue types may land on one family,
+/// once every family is full the queues are handed out again.
+///
+/// ```ignore
+/// let plan = QueuePlan::new(&families, &[(QueueType::graphics, &[1.0]), (QueueType::transfer, &[0.5])])?;
+/// let (family, index) = plan.queue(QueueType::transfer, 0).unwrap();
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuePlan {
+    // sorted by family index, the VkDeviceQueueCreateInfos
+    pub families: Vec<QueueFamilyPlan>,
+    queues: BTreeMap<QueueType, Vec<(u32, u32)>>,
+}
+
+impl QueuePlan {
+    pub fn new(
+        families: &[VkQueueFamilyProperties],
+        demands: &[(QueueType, &[f32])],
+    ) -> Result<Self, QueuePlanError> {
+        // the scarce graphics families are taken first
+        let mut demands = demands.to_vec();
+        demands.sort_by_key(|demand| demand.0);
+
+        let mut priorities: BTreeMap<u32, Vec<f32>> = BTreeMap::new();
+        let mut queues: BTreeMap<QueueType, Vec<(u32, u32)>> = BTreeMap::new();
+
+        for (queue_type, demanded) in demands {
+            let used = |index: usize| priorities.get(&(index as u32)).map_or(0, |p| p.len()) as u32;
+            let family_index = families
+                .iter()
+                .enumerate()
+                .filter(|(_, family)| family.queueCount > 0)
+                .filter_map(|(index, family)| {
+                    let rank = family_rank(queue_type, family.queueFlags)?;
+                    let free = family.queueCount.saturating_sub(used(index));
+                    // a family with queues left, then the best rank, then the lowest index
+                    Some(((free == 0, rank), index))
+                })
+                .min()
+                .map(|(_, index)| index as u32)
+                .ok_or(QueuePlanError::NoQueueFamily(queue_type))?;
+
+            let queue_count = families[family_index as usize].queueCount;
+            let family_priorities = priorities.entry(family_index).or_default();
+            let assigned = queues.entry(queue_type).or_default();
+            for priority in demanded {
+                let queue_index = family_priorities.len() as u32;
+                if queue_index < queue_count {
+                    family_priorities.push(*priority);
+                    assigned.push((family_index, queue_index));
+                } else {
+                    // the family is full, share its queues round-robin
+                    assigned.push((family_index, assigned.len() as u32 % queue_count));
+                }
+            }
+        }
+
+        Ok(Self {
+            families: priorities
+                .into_iter()
+                .filter(|(_, priorities)| !priorities.is_empty())
+                .map(|(family_index, priorities)| QueueFamilyPlan {
+                    family_index,
+                    priorities,
+                })
+                .collect(),
+            queues,
+        })
+    }
+
+    /// `(family, index)` of the `n`th queue demanded for `queue_type`
+    pub fn queue(&self, queue_type: QueueType, n: usize) -> Option<(u32, u32)> {
+        self.queues.get(&queue_type)?.get(n).copied()
+    }
+
+    // every `(family, index)` of `queue_type` in the demanded order
+    pub fn queues(&self, queue_type: QueueType) -> &[(u32, u32)] {
+        self.queues.get(&queue_type).map_or(&[], |q| q.as_slice())
+    }
+
+    // families serving `queue_type`, without duplicates
+    pub fn family_indices(&self, queue_type: QueueType) -> Vec<u32> {
+        let mut indices = vec![];
+        for (family_index, _) in self.queues(queue_type) {
+            if !indices.contains(family_index) {
+                indices.push(*family_index);
+            }
+        }
+        indices
+    }
+}
+
+// lower is better, None when the family cannot serve the queue type
+fn family_rank(queue_type: QueueType, queue_flags: VkQueueFlags) -> Option<u32> {
+    let flags = QueueFlags::from(queue_flags);
+    let graphics = flags.contains(QueueFlags::GRAPHICS);
+    let compute = flags.contains(QueueFlags::COMPUTE);
+    // graphics and compute families support transfers without reporting it
+    let transfer = flags.contains(QueueFlags::TRANSFER) || graphics || compute;
+
+    match queue_type {
+        QueueType::graphics if graphics => Some(0),
+        QueueType::computes if compute && !graphics => Some(0),
+        QueueType::computes if compute => Some(1),
+        QueueType::transfer if transfer && !graphics && !compute => Some(0),
+        QueueType::transfer if transfer && !graphics => Some(1),
+        QueueType::transfer if transfer => Some(2),
+        QueueType::none => Some(0),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn family(flags: QueueFlags, count: u32) -> VkQueueFamilyProperties {
+        VkQueueFamilyProperties {
+            queueFlags: flags.bits(),
+            queueCount: count,
+            ..Default::default()
+        }
+    }
+
+    // a typical discrete gpu: universal, async compute, dma
+    fn discrete() -> Vec<VkQueueFamilyProperties> {
+        vec![
+            family(
+                QueueFlags::GRAPHICS | QueueFlags::COMPUTE | QueueFlags::TRANSFER,
+                16,
+            ),
+            family(QueueFlags::COMPUTE | QueueFlags::TRANSFER, 8),
+            family(QueueFlags::TRANSFER, 2),
+        ]
+    }
+
+    // e.g. lavapipe and many integrated gpus
+    fn single() -> Vec<VkQueueFamilyProperties> {
+        vec![family(
+            QueueFlags::GRAPHICS | QueueFlags::COMPUTE | QueueFlags::TRANSFER,
+            1,
+        )]
+    }
+
+    #[test]
+    fn dedicated_families_are_preferred() {
+        let plan = QueuePlan::new(
+            &discrete(),
+            &[
+                (QueueType::transfer, &[1.0]),
+                (QueueType::computes, &[1.0]),
+                (QueueType::graphics, &[1.0]),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(plan.queue(QueueType::graphics, 0), Some((0, 0)));
+        assert_eq!(plan.queue(QueueType::computes, 0), Some((1, 0)));
+        assert_eq!(plan.queue(QueueType::transfer, 0), Some((2, 0)));
+        assert_eq!(plan.families.len(), 3);
+    }
+
+    #[test]
+    fn shared_family_fallback() {
+        let families = vec![
+            family(QueueFlags::GRAPHICS | QueueFlags::COMPUTE, 4),
+            family(QueueFlags::COMPUTE, 1),
+        ];
+        let plan = QueuePlan::new(
+            &families,
+            &[
+                (QueueType::graphics, &[1.0]),
+                (QueueType::computes, &[1.0]),
+                (QueueType::transfer, &[1.0]),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(plan.queue(QueueType::graphics, 0), Some((0, 0)));
+        assert_eq!(plan.queue(QueueType::computes, 0), Some((1, 0)));
+        // the compute family is full, the universal one still has queues left
+        assert_eq!(plan.queue(QueueType::transfer, 0), Some((0, 1)));
+    }
+
+    #[test]
+    fn several_queue_types_on_one_family() {
+        let families = vec![family(
+            QueueFlags::GRAPHICS | QueueFlags::COMPUTE | QueueFlags::TRANSFER,
+            4,
+        )];
+        let plan = QueuePlan::new(
+            &families,
+            &[
+                (QueueType::graphics, &[1.0, 0.5]),
+                (QueueType::computes, &[0.8]),
+                (QueueType::transfer, &[0.2]),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(plan.queues(QueueType::graphics), &[(0, 0), (0, 1)]);
+        assert_eq!(plan.queue(QueueType::computes, 0), Some((0, 2)));
+        assert_eq!(plan.queue(QueueType::transfer, 0), Some((0, 3)));
+        assert_eq!(
+            plan.families,
+            vec![QueueFamilyPlan {
+                family_index: 0,
+                priorities: vec![1.0, 0.5, 0.8, 0.2],
+            }]
+        );
+    }
+
+    #[test]
+    fn full_family_shares_its_queues() {
+        let plan = QueuePlan::new(
+            &single(),
+            &[
+                (QueueType::graphics, &[1.0, 1.0]),
+                (QueueType::computes, &[1.0]),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(plan.queues(QueueType::graphics), &[(0, 0), (0, 0)]);
+        assert_eq!(plan.queue(QueueType::computes, 0), Some((0, 0)));
+        assert_eq!(plan.families[0].priorities, vec![1.0]);
+    }
+
+    #[test]
+    fn indices_are_not_shifted_by_earlier_demands() {
+        // the families after the graphics one keep their index
+        let plan = QueuePlan::new(
+            &discrete(),
+            &[(QueueType::graphics, &[1.0]), (QueueType::transfer, &[1.0])],
+        )
+        .unwrap();
+
+        assert_eq!(plan.family_indices(QueueType::transfer), vec![2]);
+        assert_eq!(plan.family_indices(QueueType::graphics), vec![0]);
+        assert!(plan.family_indices(QueueType::computes).is_empty());
+    }
+
+    #[test]
+    fn demand_order_does_not_matter() {
+        let demands_a: [(QueueType, &[f32]); 2] =
+            [(QueueType::computes, &[1.0]), (QueueType::graphics, &[1.0])];
+        let demands_b: [(QueueType, &[f32]); 2] =
+            [(QueueType::graphics, &[1.0]), (QueueType::computes, &[1.0])];
+
+        assert_eq!(
+            QueuePlan::new(&single(), &demands_a).unwrap(),
+            QueuePlan::new(&single(), &demands_b).unwrap()
+        );
+    }
+
+    #[test]
+    fn missing_queue_type_is_an_error() {
+        let families = vec![family(QueueFlags::TRANSFER, 1)];
+        let plan = QueuePlan::new(&families, &[(QueueType::graphics, &[1.0])]);
+
+        assert!(matches!(
+            plan,
+            Err(QueuePlanError::NoQueueFamily(QueueType::graphics))
+        ));
+    }
+
+    #[test]
+    fn empty_families_are_skipped() {
+        let families = vec![
+            family(QueueFlags::GRAPHICS | QueueFlags::COMPUTE, 0),
+            family(QueueFlags::GRAPHICS, 1),
+        ];
+        let plan = QueuePlan::new(&families, &[(QueueType::graphics, &[1.0])]).unwrap();
+
+        assert_eq!(plan.queue(QueueType::graphics, 0), Some((1, 0)));
+    }
+}
@@ -0,0 +1,182 @@
+//
+// tracking
+// objects created through `VkDeviceFunctions`, reported when their device is destroyed
+// only debug builds keep track, release builds skip all of it
+//
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::*;
+
+/// An object created on a device and not destroyed yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveObject {
+    // e.g. "VkBuffer"
+    pub type_name: &'static str,
+    pub handle: u64,
+    // see `VkDeviceFunctions::set_object_name`
+    pub name: Option<String>,
+}
+
+impl std::fmt::Display for LiveObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {:#x}", self.type_name, self.handle)?;
+        if let Some(name) = &self.name {
+            write!(f, " \"{}\"", name)?;
+        }
+        Ok(())
+    }
+}
+
+// device -> handle -> object
+static LIVE_OBJECTS: Mutex<BTreeMap<usize, BTreeMap<u64, LiveObject>>> =
+    Mutex::new(BTreeMap::new());
+
+fn with_objects<R>(
+    device: VkDevice,
+    f: impl FnOnce(&mut BTreeMap<u64, LiveObject>) -> R,
+) -> Option<R> {
+    if !cfg!(debug_assertions) {
+        return None;
+    }
+    // a panic while holding the lock must not disable the report
+    let mut live = LIVE_OBJECTS.lock().unwrap_or_else(|e| e.into_inner());
+    Some(f(live.entry(device as usize).or_default()))
+}
+
+pub(crate) fn track<H: VkHandle>(device: VkDevice, handle: H) {
+    if handle.as_raw() == 0 {
+        return;
+    }
+    with_objects(device, |objects| {
+        objects.insert(
+            handle.as_raw(),
+            LiveObject {
+                type_name: H::TYPE_NAME,
+                handle: handle.as_raw(),
+                name: None,
+            },
+        )
+    });
+}
+
+pub(crate) fn untrack<H: VkHandle>(device: VkDevice, handle: H) {
+    with_objects(device, |objects| objects.remove(&handle.as_raw()));
+}
+
+pub(crate) fn set_name<H: VkHandle>(device: VkDevice, handle: H, name: &str) {
+    with_objects(device, |objects| {
+        if let Some(object) = objects.get_mut(&handle.as_raw()) {
+            object.name = Some(name.to_string());
+        }
+    });
+}
+
+// objects of `device` still alive, always empty in release builds
+pub fn live_objects(device: VkDevice) -> Vec<LiveObject> {
+    with_objects(device, |objects| objects.values().cloned().collect()).unwrap_or_default()
+}
+
+// logs what `device` still owns and forgets the device, right before vkDestroyDevice
+pub(crate) fn report_leaks(device: VkDevice) {
+    let leaked = with_objects(device, std::mem::take).unwrap_or_default();
+    LIVE_OBJECTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&(device as usize));
+
+    if leaked.is_empty() {
+        return;
+    }
+    log::warn!(
+        target: "vrx",
+        "[vrx] {} objects outlive their device {:?}",
+        leaked.len(),
+        device
+    );
+    for object in leaked.values() {
+        log::warn!(target: "vrx", "    {}", object);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // nothing is dereferenced, the keys only have to differ between the tests
+    fn fake_device(key: usize) -> VkDevice {
+        key as VkDevice
+    }
+
+    #[test]
+    #[cfg_attr(not(debug_assertions), ignore = "only debug builds keep track")]
+    fn track_and_untrack_follow_the_live_objects() {
+        let device = fake_device(0x1000);
+        let buffer = 0x10 as VkBuffer;
+        let image = 0x20 as VkImage;
+
+        track(device, buffer);
+        track(device, image);
+        // null handles are not objects
+        track(device, null_mut::<VkBuffer_T>());
+        assert_eq!(
+            live_objects(device),
+            [
+                LiveObject {
+                    type_name: "VkBuffer",
+                    handle: 0x10,
+                    name: None,
+                },
+                LiveObject {
+                    type_name: "VkImage",
+                    handle: 0x20,
+                    name: None,
+                },
+            ]
+        );
+
+        untrack(device, buffer);
+        untrack(device, buffer);
+        assert_eq!(live_objects(device).len(), 1);
+        assert_eq!(live_objects(device)[0].type_name, "VkImage");
+
+        report_leaks(device);
+    }
+
+    #[test]
+    #[cfg_attr(not(debug_assertions), ignore = "only debug builds keep track")]
+    fn set_name_names_tracked_objects_only() {
+        let device = fake_device(0x2000);
+        let buffer = 0x10 as VkBuffer;
+
+        set_name(device, buffer, "before tracking");
+        track(device, buffer);
+        assert_eq!(live_objects(device)[0].name, None);
+
+        set_name(device, buffer, "vertices");
+        assert_eq!(live_objects(device)[0].name.as_deref(), Some("vertices"));
+        assert_eq!(
+            live_objects(device)[0].to_string(),
+            "VkBuffer 0x10 \"vertices\""
+        );
+
+        report_leaks(device);
+    }
+
+    #[test]
+    #[cfg_attr(not(debug_assertions), ignore = "only debug builds keep track")]
+    fn report_leaks_forgets_the_device() {
+        let device = fake_device(0x3000);
+        let other = fake_device(0x3001);
+        track(device, 0x10 as VkBuffer);
+        track(other, 0x10 as VkBuffer);
+
+        report_leaks(device);
+        let devices: Vec<usize> = LIVE_OBJECTS.lock().unwrap().keys().copied().collect();
+        assert!(!devices.contains(&(device as usize)));
+        // the same handle value on another device is another object
+        assert_eq!(live_objects(other).len(), 1);
+
+        report_leaks(other);
+    }
+}
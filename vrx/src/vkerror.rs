@@ -0,0 +1,190 @@
+//
+// error
+// typed VkResult for the trait bindings
+//
+use std::fmt;
+
+macro_rules! vk_error {
+    ( $( $variant:ident = $result:ident, $desc:literal; )* ) => {
+        ///
+        /// Error half of `VkResult`
+        ///
+        /// Every negative result code known to the headers gets its own variant,
+        /// codes added by newer drivers fall back to `Other`.
+        /// `InvalidShaderCode` comes from vrx itself, the driver is not called.
+        /// Success codes (`VK_SUBOPTIMAL_KHR`, `VK_TIMEOUT`, `VK_NOT_READY` ...) are never errors,
+        /// wrappers that can observe them return them as `Ok(VkResult)`.
+        ///
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum VkError {
+            $( $variant, )*
+            Other(VkResult),
+            // byte length of SPIR-V code that is not a whole number of 32-bit words
+            InvalidShaderCode(usize),
+        }
+
+        impl From<VkResult> for VkError {
+            fn from(result: VkResult) -> Self {
+                match result {
+                    $( VkResult::$result => VkError::$variant, )*
+                    _ => VkError::Other(result),
+                }
+            }
+        }
+
+        impl From<VkError> for VkResult {
+            fn from(error: VkError) -> Self {
+                match error {
+                    $( VkError::$variant => VkResult::$result, )*
+                    VkError::Other(result) => result,
+                    // the valid usage the driver would have reported, it reads back as `ValidationFailedEXT`
+                    VkError::InvalidShaderCode(_) => VkResult::VK_ERROR_VALIDATION_FAILED_EXT,
+                }
+            }
+        }
+
+        impl fmt::Display for VkError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    $( VkError::$variant => write!(f, "{}: {}", stringify!($result), $desc), )*
+                    VkError::Other(result) => write!(f, "VkResult({})", result.0),
+                    VkError::InvalidShaderCode(len) => {
+                        write!(f, "[vrx] shader code of {} bytes is not a whole number of 32-bit words", len)
+                    }
+                }
+            }
+        }
+    };
+}
+
+vk_error! {
+    OutOfHostMemory = VK_ERROR_OUT_OF_HOST_MEMORY, "a host memory allocation has failed";
+    OutOfDeviceMemory = VK_ERROR_OUT_OF_DEVICE_MEMORY, "a device memory allocation has failed";
+    InitializationFailed = VK_ERROR_INITIALIZATION_FAILED, "initialization of an object could not be completed";
+    DeviceLost = VK_ERROR_DEVICE_LOST, "the logical or physical device has been lost";
+    MemoryMapFailed = VK_ERROR_MEMORY_MAP_FAILED, "mapping of a memory object has failed";
+    LayerNotPresent = VK_ERROR_LAYER_NOT_PRESENT, "a requested layer is not present or could not be loaded";
+    ExtensionNotPresent = VK_ERROR_EXTENSION_NOT_PRESENT, "a requested extension is not supported";
+    FeatureNotPresent = VK_ERROR_FEATURE_NOT_PRESENT, "a requested feature is not supported";
+    IncompatibleDriver = VK_ERROR_INCOMPATIBLE_DRIVER, "the requested version of Vulkan is not supported by the driver";
+    TooManyObjects = VK_ERROR_TOO_MANY_OBJECTS, "too many objects of the type have already been created";
+    FormatNotSupported = VK_ERROR_FORMAT_NOT_SUPPORTED, "a requested format is not supported on this device";
+    FragmentedPool = VK_ERROR_FRAGMENTED_POOL, "a pool allocation has failed due to fragmentation";
+    Unknown = VK_ERROR_UNKNOWN, "an unknown error has occurred";
+    OutOfPoolMemory = VK_ERROR_OUT_OF_POOL_MEMORY, "a pool memory allocation has failed";
+    InvalidExternalHandle = VK_ERROR_INVALID_EXTERNAL_HANDLE, "an external handle is not a valid handle of the specified type";
+    Fragmentation = VK_ERROR_FRAGMENTATION, "a descriptor pool creation has failed due to fragmentation";
+    InvalidOpaqueCaptureAddress = VK_ERROR_INVALID_OPAQUE_CAPTURE_ADDRESS, "a buffer or memory capture address is not available";
+    SurfaceLostKHR = VK_ERROR_SURFACE_LOST_KHR, "a surface is no longer available";
+    NativeWindowInUseKHR = VK_ERROR_NATIVE_WINDOW_IN_USE_KHR, "the requested window is already in use";
+    OutOfDateKHR = VK_ERROR_OUT_OF_DATE_KHR, "the surface has changed and the swapchain must be recreated";
+    IncompatibleDisplayKHR = VK_ERROR_INCOMPATIBLE_DISPLAY_KHR, "the display used by the swapchain does not match";
+    ValidationFailedEXT = VK_ERROR_VALIDATION_FAILED_EXT, "a validation layer rejected the command";
+    InvalidShaderNV = VK_ERROR_INVALID_SHADER_NV, "one or more shaders failed to compile or link";
+    ImageUsageNotSupportedKHR = VK_ERROR_IMAGE_USAGE_NOT_SUPPORTED_KHR, "the requested image usage is not supported";
+    VideoPictureLayoutNotSupportedKHR = VK_ERROR_VIDEO_PICTURE_LAYOUT_NOT_SUPPORTED_KHR, "the requested video picture layout is not supported";
+    VideoProfileOperationNotSupportedKHR = VK_ERROR_VIDEO_PROFILE_OPERATION_NOT_SUPPORTED_KHR, "the requested video profile operation is not supported";
+    VideoProfileFormatNotSupportedKHR = VK_ERROR_VIDEO_PROFILE_FORMAT_NOT_SUPPORTED_KHR, "the requested video profile format is not supported";
+    VideoProfileCodecNotSupportedKHR = VK_ERROR_VIDEO_PROFILE_CODEC_NOT_SUPPORTED_KHR, "the requested video codec is not supported";
+    VideoStdVersionNotSupportedKHR = VK_ERROR_VIDEO_STD_VERSION_NOT_SUPPORTED_KHR, "the requested video std header version is not supported";
+    InvalidDrmFormatModifierPlaneLayoutEXT = VK_ERROR_INVALID_DRM_FORMAT_MODIFIER_PLANE_LAYOUT_EXT, "the drm format modifier plane layout is invalid";
+    NotPermittedKHR = VK_ERROR_NOT_PERMITTED_KHR, "the driver does not permit the requested priority";
+    FullScreenExclusiveModeLostEXT = VK_ERROR_FULL_SCREEN_EXCLUSIVE_MODE_LOST_EXT, "the swapchain lost full-screen exclusive mode";
+    CompressionExhaustedEXT = VK_ERROR_COMPRESSION_EXHAUSTED_EXT, "an image creation failed because compression resources were exhausted";
+}
+
+impl VkError {
+    pub fn as_raw(&self) -> VkResult {
+        VkResult::from(*self)
+    }
+}
+
+impl std::error::Error for VkError {}
+
+/// Splits a `VkResult` into the success code or a `VkError`
+#[inline]
+pub fn vk_check(result: VkResult) -> Result<VkResult, VkError> {
+    if result.0 >= 0 {
+        Ok(result)
+    } else {
+        Err(VkError::from(result))
+    }
+}
+
+// included into the crate root next to vktraits.rs, which has the `tests` module
+#[cfg(test)]
+mod vkerror_tests {
+    use super::*;
+
+    // the raw codes of vulkan_core.h, checked against the bindings rather than against `vk_error!` itself
+    const NEGATIVE_CODES: [(i32, VkError); 33] = [
+        (-1, VkError::OutOfHostMemory),
+        (-2, VkError::OutOfDeviceMemory),
+        (-3, VkError::InitializationFailed),
+        (-4, VkError::DeviceLost),
+        (-5, VkError::MemoryMapFailed),
+        (-6, VkError::LayerNotPresent),
+        (-7, VkError::ExtensionNotPresent),
+        (-8, VkError::FeatureNotPresent),
+        (-9, VkError::IncompatibleDriver),
+        (-10, VkError::TooManyObjects),
+        (-11, VkError::FormatNotSupported),
+        (-12, VkError::FragmentedPool),
+        (-13, VkError::Unknown),
+        (-1000069000, VkError::OutOfPoolMemory),
+        (-1000072003, VkError::InvalidExternalHandle),
+        (-1000161000, VkError::Fragmentation),
+        (-1000257000, VkError::InvalidOpaqueCaptureAddress),
+        (-1000000000, VkError::SurfaceLostKHR),
+        (-1000000001, VkError::NativeWindowInUseKHR),
+        (-1000001004, VkError::OutOfDateKHR),
+        (-1000003001, VkError::IncompatibleDisplayKHR),
+        (-1000011001, VkError::ValidationFailedEXT),
+        (-1000012000, VkError::InvalidShaderNV),
+        (-1000023000, VkError::ImageUsageNotSupportedKHR),
+        (-1000023001, VkError::VideoPictureLayoutNotSupportedKHR),
+        (-1000023002, VkError::VideoProfileOperationNotSupportedKHR),
+        (-1000023003, VkError::VideoProfileFormatNotSupportedKHR),
+        (-1000023004, VkError::VideoProfileCodecNotSupportedKHR),
+        (-1000023005, VkError::VideoStdVersionNotSupportedKHR),
+        (-1000158000, VkError::InvalidDrmFormatModifierPlaneLayoutEXT),
+        (-1000174001, VkError::NotPermittedKHR),
+        (-1000255000, VkError::FullScreenExclusiveModeLostEXT),
+        (-1000338000, VkError::CompressionExhaustedEXT),
+    ];
+
+    #[test]
+    fn negative_codes_map_to_their_variant_and_back() {
+        for (code, error) in NEGATIVE_CODES {
+            assert_eq!(vk_check(VkResult(code)), Err(error), "VkResult({})", code);
+            assert_eq!(VkResult::from(error), VkResult(code), "{:?}", error);
+        }
+
+        // unknown to the headers, kept as is
+        assert_eq!(vk_check(VkResult(-1234)), Err(VkError::Other(VkResult(-1234))));
+        assert_eq!(VkError::Other(VkResult(-1234)).as_raw(), VkResult(-1234));
+    }
+
+    #[test]
+    fn success_codes_are_not_errors() {
+        for result in [
+            VkResult::VK_SUCCESS,
+            VkResult::VK_NOT_READY,
+            VkResult::VK_TIMEOUT,
+            VkResult::VK_INCOMPLETE,
+            VkResult::VK_SUBOPTIMAL_KHR,
+            // VK_ERROR_INCOMPATIBLE_SHADER_BINARY_EXT is positive despite its name
+            VkResult(1000482000),
+        ] {
+            assert_eq!(vk_check(result), Ok(result));
+        }
+    }
+
+    #[test]
+    fn invalid_shader_code_reads_back_as_validation_failed() {
+        // one way, the byte length is not part of the raw code
+        let result = VkError::InvalidShaderCode(6).as_raw();
+        assert_eq!(result, VkResult::VK_ERROR_VALIDATION_FAILED_EXT);
+        assert_eq!(VkError::from(result), VkError::ValidationFailedEXT);
+    }
+}
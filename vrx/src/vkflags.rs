@@ -0,0 +1,152 @@
+//
+// flags
+// typed Vk*FlagBits, one type per bitmask instead of bare VkFlags
+//
+macro_rules! vk_flags {
+    ( pub struct $name:ident($raw:ty) $(from $alt:ty)? { $( $flag:ident = $value:expr, )* } ) => {
+        #[repr(transparent)]
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+        pub struct $name($raw);
+
+        impl $name {
+            $( pub const $flag: Self = Self($value); )*
+
+            const NAMES: &'static [(&'static str, $raw)] = &[ $( (stringify!($flag), $value), )* ];
+
+            pub const fn empty() -> Self {
+                Self(0)
+            }
+
+            pub const fn all() -> Self {
+                Self(0 $( | $value )*)
+            }
+
+            pub const fn from_raw(bits: $raw) -> Self {
+                Self(bits)
+            }
+
+            pub const fn bits(&self) -> $raw {
+                self.0
+            }
+
+            pub const fn is_empty(&self) -> bool {
+                self.0 == 0
+            }
+
+            pub const fn contains(&self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            pub const fn intersects(&self, other: Self) -> bool {
+                self.0 & other.0 != 0
+            }
+        }
+
+        impl std::ops::BitOr for $name {
+            type Output = Self;
+            fn bitor(self, rhs: Self) -> Self {
+                Self(self.0 | rhs.0)
+            }
+        }
+
+        impl std::ops::BitOrAssign for $name {
+            fn bitor_assign(&mut self, rhs: Self) {
+                self.0 |= rhs.0;
+            }
+        }
+
+        impl std::ops::BitAnd for $name {
+            type Output = Self;
+            fn bitand(self, rhs: Self) -> Self {
+                Self(self.0 & rhs.0)
+            }
+        }
+
+        impl std::ops::BitAndAssign for $name {
+            fn bitand_assign(&mut self, rhs: Self) {
+                self.0 &= rhs.0;
+            }
+        }
+
+        impl std::ops::BitXor for $name {
+            type Output = Self;
+            fn bitxor(self, rhs: Self) -> Self {
+                Self(self.0 ^ rhs.0)
+            }
+        }
+
+        impl std::ops::BitXorAssign for $name {
+            fn bitxor_assign(&mut self, rhs: Self) {
+                self.0 ^= rhs.0;
+            }
+        }
+
+        impl std::ops::Sub for $name {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0 & !rhs.0)
+            }
+        }
+
+        impl std::ops::Not for $name {
+            type Output = Self;
+            fn not(self) -> Self {
+                Self(!self.0 & Self::all().0)
+            }
+        }
+
+        // raw values keep working, e.g. `VK_IMAGE_USAGE_STORAGE_BIT | VK_IMAGE_USAGE_TRANSFER_DST_BIT`
+        impl From<$raw> for $name {
+            fn from(bits: $raw) -> Self {
+                Self(bits)
+            }
+        }
+
+        // FlagBits constants are `i32` where the C enum is signed (msvc)
+        $(
+            impl From<$alt> for $name {
+                fn from(bits: $alt) -> Self {
+                    Self(bits as $raw)
+                }
+            }
+        )?
+
+        impl From<$name> for $raw {
+            fn from(flags: $name) -> Self {
+                flags.0
+            }
+        }
+
+        // ImageUsageFlags(STORAGE | TRANSFER_DST)
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let mut remaining = self.0;
+                let mut first = true;
+
+                write!(f, "{}(", stringify!($name))?;
+                for (name, value) in Self::NAMES {
+                    if *value != 0 && self.0 & value == *value && remaining & value != 0 {
+                        if !first {
+                            f.write_str(" | ")?;
+                        }
+                        f.write_str(name)?;
+                        remaining &= !value;
+                        first = false;
+                    }
+                }
+
+                if remaining != 0 {
+                    if !first {
+                        f.write_str(" | ")?;
+                    }
+                    write!(f, "{:#x}", remaining)?;
+                } else if first {
+                    f.write_str("empty")?;
+                }
+                f.write_str(")")
+            }
+        }
+    };
+}
+
+include!(concat!(env!("OUT_DIR"), "/vk_flags.rs"));
@@ -12,7 +12,7 @@ macro_rules! create_func {
                 &self,
                 [<$name:snake _create_info>]: *const [<Vk $name CreateInfo $($khr)?>],
                 p_allocator: Option<*const VkAllocationCallbacks>,
-            ) -> [<Vk $name $($khr)?>];
+            ) -> Result<[<Vk $name $($khr)?>], VkError>;
         }
     };
 
@@ -22,17 +22,44 @@ macro_rules! create_func {
                 &self,
                 [<$name:snake _create_info>]: *const [<Vk $name CreateInfo $($khr)?>],
                 p_allocator: Option<*const VkAllocationCallbacks>,
-            ) -> [<Vk $name $($khr)?>] {
+            ) -> Result<[<Vk $name $($khr)?>], VkError> {
                 let mut instance = vk_instantiate!([<Vk $name $($khr)?>]);
 
                 unsafe {
-                    if let Some(p) = p_allocator {
-                        [<vkCreate $name $($khr)?>](*self, [<$name:snake _create_info>], p_allocator.unwrap(), &mut instance);
-                    } else {
-                        [<vkCreate $name $($khr)?>](*self, [<$name:snake _create_info>], null(), &mut instance);
-                    }
+                    vk_check([<vkCreate $name $($khr)?>](
+                        *self,
+                        [<$name:snake _create_info>],
+                        p_allocator.unwrap_or(null()),
+                        &mut instance,
+                    ))?;
                 }
-                instance
+                tracking::track(*self, instance);
+                Ok(instance)
+            }
+        }
+    };
+
+    // objects of Vulkan 1.1-1.3, FeatureNotPresent when the device doesn't load vkCreate*
+    (DF_CORE $name:expr) => {
+        paste! {
+            fn [<create_ $name:snake>](
+                &self,
+                [<$name:snake _create_info>]: *const [<Vk $name CreateInfo>],
+                p_allocator: Option<*const VkAllocationCallbacks>,
+            ) -> Result<[<Vk $name>], VkError> {
+                require_loaded(*self, |table| table.[<vkCreate $name>].is_some())?;
+                let mut instance = vk_instantiate!([<Vk $name>]);
+
+                unsafe {
+                    vk_check([<vkCreate $name>](
+                        *self,
+                        [<$name:snake _create_info>],
+                        p_allocator.unwrap_or(null()),
+                        &mut instance,
+                    ))?;
+                }
+                tracking::track(*self, instance);
+                Ok(instance)
             }
         }
     };
@@ -53,7 +80,7 @@ macro_rules! create_func {
                 pipeline_cache: VkPipelineCache,
                 [<$name:snake _create_info>]: &[[<Vk $name CreateInfo>]],
                 p_allocator: Option<*const VkAllocationCallbacks>,
-            ) -> Vec<VkPipeline>;
+            ) -> Result<Vec<VkPipeline>, VkError>;
         }
     };
 
@@ -65,36 +92,26 @@ macro_rules! create_func {
                 pipeline_cache: VkPipelineCache,
                 [<$name:snake _create_info>]: &[[<Vk $name CreateInfo>]],
                 p_allocator: Option<*const VkAllocationCallbacks>,
-            ) -> Vec<VkPipeline> {
+            ) -> Result<Vec<VkPipeline>, VkError> {
                 let size = [<$name:snake _create_info>].len();
                 let mut pipelines = vec![vk_instantiate!(VkPipeline); size];
 
                 unsafe {
-                    if let Some(p) = p_allocator {
-                        vk_assert([<vkCreate $name s>](
-                            *self,
-                            pipeline_cache,
-                            size as u32,
-                            [<$name:snake _create_info>].as_ptr(),
-                            p_allocator.unwrap(),
-                            pipelines.as_mut_ptr(),
-                        ));
-                    } else {
-                        vk_assert([<vkCreate $name s>](
-                            *self,
-                            pipeline_cache,
-                            size as u32,
-                            [<$name:snake _create_info>].as_ptr(),
-                            null(),
-                            pipelines.as_mut_ptr(),
-                        ));
-                    }
+                    vk_check([<vkCreate $name s>](
+                        *self,
+                        pipeline_cache,
+                        size as u32,
+                        [<$name:snake _create_info>].as_ptr(),
+                        p_allocator.unwrap_or(null()),
+                        pipelines.as_mut_ptr(),
+                    ))?;
                 }
+                pipelines.iter().for_each(|pipeline| tracking::track(*self, *pipeline));
 
-                pipelines
+                Ok(pipelines)
             }
         }
-};
+    };
 }
 
 macro_rules! destroy_func {
@@ -115,48 +132,201 @@ macro_rules! destroy_func {
                 [<$name:snake>]: [<Vk $name $($khr)?>],
                 p_allocator: Option<*const VkAllocationCallbacks>,
             ) {
-
+                tracking::untrack(*self, [<$name:snake>]);
                 unsafe {
-                    if let Some(p) = p_allocator {
-                        [<vkDestroy $name $($khr)?>](*self, [<$name:snake>], p_allocator.unwrap());
-                    } else {
-                        [<vkDestroy $name $($khr)?>](*self, [<$name:snake>], null());
-                    }
+                    [<vkDestroy $name $($khr)?>](*self, [<$name:snake>], p_allocator.unwrap_or(null()));
                 }
             }
         }
     };
 }
 
+// the commands of a later core version are only loaded when the device reports that version
+fn require_loaded(device: VkDevice, loaded: impl FnOnce(&loader::DeviceTable) -> bool) -> Result<(), VkError> {
+    if loader::is_device_command_loaded(device, loaded) {
+        Ok(())
+    } else {
+        Err(VkError::FeatureNotPresent)
+    }
+}
+
+/// Vulkan handles that can be named, see `VkDeviceFunctions::set_object_name`
+pub trait VkHandle: Copy {
+    const OBJECT_TYPE: VkObjectType;
+    // e.g. "VkBuffer"
+    const TYPE_NAME: &'static str;
+    fn as_raw(&self) -> u64;
+}
+
+macro_rules! vk_handle {
+    ($($handle:ty => $object_type:ident),* $(,)?) => {
+        $(
+            impl VkHandle for $handle {
+                const OBJECT_TYPE: VkObjectType = $object_type;
+                const TYPE_NAME: &'static str = stringify!($handle);
+
+                fn as_raw(&self) -> u64 {
+                    *self as u64
+                }
+            }
+        )*
+    };
+}
+
+vk_handle! {
+    VkInstance => VK_OBJECT_TYPE_INSTANCE,
+    VkPhysicalDevice => VK_OBJECT_TYPE_PHYSICAL_DEVICE,
+    VkDevice => VK_OBJECT_TYPE_DEVICE,
+    VkQueue => VK_OBJECT_TYPE_QUEUE,
+    VkSemaphore => VK_OBJECT_TYPE_SEMAPHORE,
+    VkCommandBuffer => VK_OBJECT_TYPE_COMMAND_BUFFER,
+    VkFence => VK_OBJECT_TYPE_FENCE,
+    VkDeviceMemory => VK_OBJECT_TYPE_DEVICE_MEMORY,
+    VkBuffer => VK_OBJECT_TYPE_BUFFER,
+    VkImage => VK_OBJECT_TYPE_IMAGE,
+    VkEvent => VK_OBJECT_TYPE_EVENT,
+    VkQueryPool => VK_OBJECT_TYPE_QUERY_POOL,
+    VkBufferView => VK_OBJECT_TYPE_BUFFER_VIEW,
+    VkImageView => VK_OBJECT_TYPE_IMAGE_VIEW,
+    VkShaderModule => VK_OBJECT_TYPE_SHADER_MODULE,
+    VkPipelineCache => VK_OBJECT_TYPE_PIPELINE_CACHE,
+    VkPipelineLayout => VK_OBJECT_TYPE_PIPELINE_LAYOUT,
+    VkRenderPass => VK_OBJECT_TYPE_RENDER_PASS,
+    VkPipeline => VK_OBJECT_TYPE_PIPELINE,
+    VkDescriptorSetLayout => VK_OBJECT_TYPE_DESCRIPTOR_SET_LAYOUT,
+    VkSampler => VK_OBJECT_TYPE_SAMPLER,
+    VkDescriptorPool => VK_OBJECT_TYPE_DESCRIPTOR_POOL,
+    VkDescriptorSet => VK_OBJECT_TYPE_DESCRIPTOR_SET,
+    VkFramebuffer => VK_OBJECT_TYPE_FRAMEBUFFER,
+    VkCommandPool => VK_OBJECT_TYPE_COMMAND_POOL,
+    VkSamplerYcbcrConversion => VK_OBJECT_TYPE_SAMPLER_YCBCR_CONVERSION,
+    VkDescriptorUpdateTemplate => VK_OBJECT_TYPE_DESCRIPTOR_UPDATE_TEMPLATE,
+    VkPrivateDataSlot => VK_OBJECT_TYPE_PRIVATE_DATA_SLOT,
+    VkSurfaceKHR => VK_OBJECT_TYPE_SURFACE_KHR,
+    VkSwapchainKHR => VK_OBJECT_TYPE_SWAPCHAIN_KHR,
+    VkDebugUtilsMessengerEXT => VK_OBJECT_TYPE_DEBUG_UTILS_MESSENGER_EXT,
+}
+
 pub trait VkPhysicalDeviceFunctions {
     // pub fn getFeatures(&self) -> *mut VkPhyiscalDeviceFeatures;
     // pub fn getFormatProperties(&self, format: VkFormat) -> *mut VkFormatProperties;
     // 
-    fn create_device(&self, create_info: *const VkDeviceCreateInfo, p_allocator: Option<*const VkAllocationCallbacks>) -> VkDevice;
+    fn create_device(&self, create_info: *const VkDeviceCreateInfo, p_allocator: Option<*const VkAllocationCallbacks>) -> Result<VkDevice, VkError>;
+
+    fn get_properties(&self) -> VkPhysicalDeviceProperties;
+    fn get_features(&self) -> VkPhysicalDeviceFeatures;
+    fn get_memory_properties(&self) -> VkPhysicalDeviceMemoryProperties;
+    fn get_queue_family_properties(&self) -> Vec<VkQueueFamilyProperties>;
+    fn enumerate_device_extension_names(&self) -> Result<Vec<String>, VkError>;
+
+    fn get_surface_support_khr(&self, queue_family_index: u32, surface: VkSurfaceKHR) -> Result<bool, VkError>;
+    fn get_surface_capabilities_khr(&self, surface: VkSurfaceKHR) -> Result<VkSurfaceCapabilitiesKHR, VkError>;
+    fn get_surface_formats_khr(&self, surface: VkSurfaceKHR) -> Result<Vec<VkSurfaceFormatKHR>, VkError>;
+    fn get_surface_present_modes_khr(&self, surface: VkSurfaceKHR) -> Result<Vec<VkPresentModeKHR>, VkError>;
 }
 
+// create infos and allocators are raw pointers handed to vulkan, as in the C API
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 impl VkPhysicalDeviceFunctions for VkPhysicalDevice {
 
-    fn create_device(&self, create_info: *const VkDeviceCreateInfo, p_allocator: Option<*const VkAllocationCallbacks>) -> VkDevice {
+    fn create_device(&self, create_info: *const VkDeviceCreateInfo, p_allocator: Option<*const VkAllocationCallbacks>) -> Result<VkDevice, VkError> {
         let mut device = vk_instantiate!(VkDevice);
         unsafe {
-            if let Some(p) = p_allocator { 
-                vkCreateDevice(
-                    *self,
-                    create_info,
-                    p_allocator.unwrap(), 
-                    &mut device,
-                );
-            } else {
-                vkCreateDevice(
-                    *self,
-                    create_info,
-                    null(), 
-                    &mut device,
-                );
-            }
+            vk_check(vkCreateDevice(
+                *self,
+                create_info,
+                p_allocator.unwrap_or(null()),
+                &mut device,
+            ))?;
+        }
+        Ok(device)
+    }
+
+    fn get_properties(&self) -> VkPhysicalDeviceProperties {
+        let mut properties = VkPhysicalDeviceProperties::default();
+        unsafe {
+            vkGetPhysicalDeviceProperties(*self, &mut properties);
+        }
+        properties
+    }
+
+    fn get_features(&self) -> VkPhysicalDeviceFeatures {
+        let mut features = VkPhysicalDeviceFeatures::default();
+        unsafe {
+            vkGetPhysicalDeviceFeatures(*self, &mut features);
+        }
+        features
+    }
+
+    fn get_memory_properties(&self) -> VkPhysicalDeviceMemoryProperties {
+        let mut memory_properties = VkPhysicalDeviceMemoryProperties::default();
+        unsafe {
+            vkGetPhysicalDeviceMemoryProperties(*self, &mut memory_properties);
+        }
+        memory_properties
+    }
+
+    fn get_queue_family_properties(&self) -> Vec<VkQueueFamilyProperties> {
+        let mut count = 0;
+        unsafe {
+            vkGetPhysicalDeviceQueueFamilyProperties(*self, &mut count, null_mut());
+            let mut properties = vec![VkQueueFamilyProperties::default(); count as usize];
+            vkGetPhysicalDeviceQueueFamilyProperties(*self, &mut count, properties.as_mut_ptr());
+            properties.truncate(count as usize);
+            properties
+        }
+    }
+
+    fn enumerate_device_extension_names(&self) -> Result<Vec<String>, VkError> {
+        let mut count = 0;
+        unsafe {
+            vk_check(vkEnumerateDeviceExtensionProperties(*self, null(), &mut count, null_mut()))?;
+            let mut properties = vec![std::mem::zeroed::<VkExtensionProperties>(); count as usize];
+            vk_check(vkEnumerateDeviceExtensionProperties(*self, null(), &mut count, properties.as_mut_ptr()))?;
+            properties.truncate(count as usize);
+            Ok(properties
+                .iter()
+                .map(|p| CStr::from_ptr(p.extensionName.as_ptr()).to_string_lossy().into_owned())
+                .collect())
+        }
+    }
+
+    fn get_surface_support_khr(&self, queue_family_index: u32, surface: VkSurfaceKHR) -> Result<bool, VkError> {
+        let mut supported = VK_FALSE;
+        unsafe {
+            vk_check(vkGetPhysicalDeviceSurfaceSupportKHR(*self, queue_family_index, surface, &mut supported))?;
+        }
+        Ok(supported == VK_TRUE)
+    }
+
+    fn get_surface_capabilities_khr(&self, surface: VkSurfaceKHR) -> Result<VkSurfaceCapabilitiesKHR, VkError> {
+        let mut capabilities = VkSurfaceCapabilitiesKHR::default();
+        unsafe {
+            vk_check(vkGetPhysicalDeviceSurfaceCapabilitiesKHR(*self, surface, &mut capabilities))?;
+        }
+        Ok(capabilities)
+    }
+
+    fn get_surface_formats_khr(&self, surface: VkSurfaceKHR) -> Result<Vec<VkSurfaceFormatKHR>, VkError> {
+        let mut count = 0;
+        unsafe {
+            vk_check(vkGetPhysicalDeviceSurfaceFormatsKHR(*self, surface, &mut count, null_mut()))?;
+            let mut formats = vec![VkSurfaceFormatKHR::default(); count as usize];
+            vk_check(vkGetPhysicalDeviceSurfaceFormatsKHR(*self, surface, &mut count, formats.as_mut_ptr()))?;
+            formats.truncate(count as usize);
+            Ok(formats)
+        }
+    }
+
+    fn get_surface_present_modes_khr(&self, surface: VkSurfaceKHR) -> Result<Vec<VkPresentModeKHR>, VkError> {
+        let mut count = 0;
+        unsafe {
+            vk_check(vkGetPhysicalDeviceSurfacePresentModesKHR(*self, surface, &mut count, null_mut()))?;
+            let mut present_modes = vec![0; count as usize];
+            vk_check(vkGetPhysicalDeviceSurfacePresentModesKHR(*self, surface, &mut count, present_modes.as_mut_ptr()))?;
+            present_modes.truncate(count as usize);
+            Ok(present_modes)
         }
-        device
     }
 }
 
@@ -175,6 +345,12 @@ pub trait VkDeviceFunctions {
     create_func!(DECLARE Framebuffer);
     create_func!(DECLARE PipelineCache);
     create_func!(DECLARE PipelineLayout);
+    create_func!(DECLARE QueryPool);
+    create_func!(DECLARE Event);
+    create_func!(DECLARE BufferView);
+    create_func!(DECLARE SamplerYcbcrConversion);
+    create_func!(DECLARE DescriptorUpdateTemplate);
+    create_func!(DECLARE PrivateDataSlot);
     create_func!(DC_PIPE ComputePipeline);
     create_func!(DC_PIPE GraphicsPipeline);
 
@@ -193,44 +369,54 @@ pub trait VkDeviceFunctions {
     destroy_func!(DECLARE PipelineCache);
     destroy_func!(DECLARE PipelineLayout);
     destroy_func!(DECLARE Pipeline);
+    destroy_func!(DECLARE QueryPool);
+    destroy_func!(DECLARE Event);
+    destroy_func!(DECLARE BufferView);
+    destroy_func!(DECLARE SamplerYcbcrConversion);
+    destroy_func!(DECLARE DescriptorUpdateTemplate);
+    destroy_func!(DECLARE PrivateDataSlot);
 
     fn create_shader_module(
         &self,
         code: &[u8],
         p_allocator: Option<*const VkAllocationCallbacks>,
-    ) -> VkShaderModule;
+    ) -> Result<VkShaderModule, VkError>;
     destroy_func!(DECLARE ShaderModule);
 
     //
     // Control functions
     //
     // memory
-    fn allocate_memory(&self, memory_allocate_info: *const VkMemoryAllocateInfo, p_allocator: Option<*const VkAllocationCallbacks>) -> VkDeviceMemory;
-    fn map_memory(&self, offset: u64, size: u64, flags: u32, memory: &VkDeviceMemory) -> anyhow::Result<*mut c_void>;
+    fn allocate_memory(&self, memory_allocate_info: *const VkMemoryAllocateInfo, p_allocator: Option<*const VkAllocationCallbacks>) -> Result<VkDeviceMemory, VkError>;
+    fn map_memory(&self, offset: u64, size: u64, flags: u32, memory: &VkDeviceMemory) -> Result<*mut c_void, VkError>;
     fn unmap_memory(&self, memory: &VkDeviceMemory);
     fn free_memory(&self, memory: &VkDeviceMemory, p_allocator: Option<*const VkAllocationCallbacks>);
-    fn flush_mapped_memory_range(&self, memory_range_count: u32, p_memory_ranges: *const VkMappedMemoryRange);
-    fn invalidate_mapped_memory_ranges(&self, memory_range_count: u32, p_memory_ranges: *const VkMappedMemoryRange);
+    fn flush_mapped_memory_range(&self, memory_range_count: u32, p_memory_ranges: *const VkMappedMemoryRange) -> Result<(), VkError>;
+    fn invalidate_mapped_memory_ranges(&self, memory_range_count: u32, p_memory_ranges: *const VkMappedMemoryRange) -> Result<(), VkError>;
 
     fn bind_buffer_memory(
         &self,
         buffer: VkBuffer,
         memory: VkDeviceMemory,
         memory_offset: VkDeviceSize,
-    ) -> VkResult;
+    ) -> Result<(), VkError>;
     
     fn bind_image_memory(
         &self,
         image: VkImage,
         memory: VkDeviceMemory,
         memory_offset: VkDeviceSize,
-    ) -> VkResult;
+    ) -> Result<(), VkError>;
 
     // command buffer
     fn allocate_command_buffers(
         &self,
         allocate_info: *const VkCommandBufferAllocateInfo,
-    ) -> Vec<VkCommandBuffer>;
+    ) -> Result<Vec<VkCommandBuffer>, VkError>;
+    fn free_command_buffers(&self, command_pool: VkCommandPool, command_buffers: &[VkCommandBuffer]);
+    fn reset_command_pool(&self, command_pool: VkCommandPool, flags: impl Into<CommandPoolResetFlags>) -> Result<(), VkError>;
+    // 1.1, gives the unused memory of the pool back to the system
+    fn trim_command_pool(&self, command_pool: VkCommandPool) -> Result<(), VkError>;
     // Queue
     fn get_queue(&self, queue_family_index: u32, queue_index: u32) -> VkQueue;
 
@@ -239,28 +425,65 @@ pub trait VkDeviceFunctions {
     fn get_image_memory_requirements(&self, image: VkImage) -> VkMemoryRequirements;
 
     // descriptor set
-    fn allocate_descriptor_sets(&self, allocate_info: &VkDescriptorSetAllocateInfo) -> Vec<VkDescriptorSet>;
-    fn free_descriptor_sets(&self, descriptor_pool: VkDescriptorPool, descriptor_sets: Vec<VkDescriptorSet>);
+    fn allocate_descriptor_sets(&self, allocate_info: &VkDescriptorSetAllocateInfo) -> Result<Vec<VkDescriptorSet>, VkError>;
+    fn free_descriptor_sets(&self, descriptor_pool: VkDescriptorPool, descriptor_sets: Vec<VkDescriptorSet>) -> Result<(), VkError>;
     fn update_descriptor_sets(&self, descriptor_writes: &[VkWriteDescriptorSet], descriptor_copies: &[VkCopyDescriptorSet]);
+    // frees every set allocated from the pool
+    fn reset_descriptor_pool(&self, descriptor_pool: VkDescriptorPool) -> Result<(), VkError>;
+    // 1.1, `data` is laid out as the entries of the template say
+    fn update_descriptor_set_with_template(&self, descriptor_set: VkDescriptorSet, descriptor_update_template: VkDescriptorUpdateTemplate, data: *const c_void) -> Result<(), VkError>;
+
+    // Query
+    // each query writes `data.len() / query_count` values,
+    // the success code is VK_NOT_READY when some results are not available yet
+    fn get_query_pool_results<T: Copy>(
+        &self,
+        query_pool: VkQueryPool,
+        first_query: u32,
+        query_count: u32,
+        data: &mut [T],
+        flags: impl Into<QueryResultFlags>,
+    ) -> Result<VkResult, VkError>;
+    // 1.2, reset from the host instead of a command buffer
+    fn reset_query_pool(&self, query_pool: VkQueryPool, first_query: u32, query_count: u32) -> Result<(), VkError>;
+
+    // Event
+    // true when the event is signaled
+    fn get_event_status(&self, event: VkEvent) -> Result<bool, VkError>;
+    fn set_event(&self, event: VkEvent) -> Result<(), VkError>;
+    fn reset_event(&self, event: VkEvent) -> Result<(), VkError>;
+
+    // private data, 1.3, FeatureNotPresent like the other 1.1-1.3 commands when the device doesn't load them
+    fn set_private_data<H: VkHandle>(&self, handle: H, private_data_slot: VkPrivateDataSlot, data: u64) -> Result<(), VkError>;
+    fn get_private_data<H: VkHandle>(&self, handle: H, private_data_slot: VkPrivateDataSlot) -> Result<u64, VkError>;
 
     // Swapchain
-    fn get_swapchain_images_khr(&self, swapchain: VkSwapchainKHR) -> Vec<VkImage>;
+    fn get_swapchain_images_khr(&self, swapchain: VkSwapchainKHR) -> Result<Vec<VkImage>, VkError>;
+    // the success code tells VK_SUBOPTIMAL_KHR apart from VK_SUCCESS
     fn acquire_next_image_khr(
         &self,
         swapchain: VkSwapchainKHR,
         timeout: u64,
         semaphore: VkSemaphore,
         fence: VkFence,
-    ) -> Result<u32, VkResult>;
+    ) -> Result<(u32, VkResult), VkError>;
 
     // Fence
-    fn wait_for_fence(&self, fences: &[VkFence], wait_all: bool, timeout: u64);
-    fn reset_fence(&self, fences: &[VkFence]);
+    // the success code is VK_TIMEOUT when the fences were not signaled in time
+    fn wait_for_fence(&self, fences: &[VkFence], wait_all: bool, timeout: u64) -> Result<VkResult, VkError>;
+    fn reset_fence(&self, fences: &[VkFence]) -> Result<(), VkError>;
 
     //
-    fn wait_idle(&self) -> anyhow::Result<VkResult>;
+    fn wait_idle(&self) -> Result<(), VkError>;
+
+    // debug
+    // shows `name` instead of the handle in validation messages and captures,
+    // nothing happens without VK_EXT_debug_utils
+    fn set_object_name<H: VkHandle>(&self, handle: H, name: &str) -> Result<(), VkError>;
 }
 
+// create infos, allocators and data pointers are passed on to vulkan as is
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 impl VkDeviceFunctions for VkDevice {
     create_func!(DEFINE CommandPool);
     create_func!(DEFINE Buffer);
@@ -276,6 +499,12 @@ impl VkDeviceFunctions for VkDevice {
     create_func!(DEFINE Framebuffer);
     create_func!(DEFINE PipelineCache);
     create_func!(DEFINE PipelineLayout);
+    create_func!(DEFINE QueryPool);
+    create_func!(DEFINE Event);
+    create_func!(DEFINE BufferView);
+    create_func!(DF_CORE SamplerYcbcrConversion);
+    create_func!(DF_CORE DescriptorUpdateTemplate);
+    create_func!(DF_CORE PrivateDataSlot);
     create_func!(DF_PIPE ComputePipeline);
     create_func!(DF_PIPE GraphicsPipeline);
 
@@ -294,44 +523,44 @@ impl VkDeviceFunctions for VkDevice {
     destroy_func!(DEFINE PipelineCache);
     destroy_func!(DEFINE PipelineLayout);
     destroy_func!(DEFINE Pipeline);
+    destroy_func!(DEFINE QueryPool);
+    destroy_func!(DEFINE Event);
+    destroy_func!(DEFINE BufferView);
+    destroy_func!(DEFINE SamplerYcbcrConversion);
+    destroy_func!(DEFINE DescriptorUpdateTemplate);
+    destroy_func!(DEFINE PrivateDataSlot);
 
     fn create_shader_module(
         &self,
         code: &[u8],
         p_allocator: Option<*const VkAllocationCallbacks>,
-    ) -> VkShaderModule {
-        let mut module = vk_instantiate!(VkShaderModule);
+    ) -> Result<VkShaderModule, VkError> {
+        // spir-v is a stream of words
+        if !code.len().is_multiple_of(4) {
+            return Err(VkError::InvalidShaderCode(code.len()));
+        }
+        // copied, `include_bytes!` and friends don't have to be 4-byte aligned
+        let words: Vec<u32> = code
+            .chunks_exact(4)
+            .map(|word| u32::from_ne_bytes([word[0], word[1], word[2], word[3]]))
+            .collect();
 
-        unsafe {
-            let code = Vec::<u8>::from(code);
-            let (prefix, code_u32, suffix) = code.align_to::<u32>();
-            if !prefix.is_empty() || !suffix.is_empty() {
-                // return Err(anyhow!("Load code for module failed"));
-                return module;
-            }
+        let shader_create_info = VkShaderModuleCreateInfoBuilder::new()
+            .code_size(code.len())
+            .p_code(words.as_ptr())
+            .build();
 
-            let shader_create_info = VkShaderModuleCreateInfoBuilder::new()
-                .code_size(code.len())
-                .p_code(code_u32.as_ptr())
-                .build();
-
-            if let Some(p) = p_allocator {
-                vk_assert(vkCreateShaderModule(
-                    *self,
-                    &shader_create_info,
-                    p_allocator.unwrap(),
-                    &mut module,
-                ));
-            } else {
-                vk_assert(vkCreateShaderModule(
-                    *self,
-                    &shader_create_info,
-                    null(),
-                    &mut module,
-                ));
-            }
+        let mut module = vk_instantiate!(VkShaderModule);
+        unsafe {
+            vk_check(vkCreateShaderModule(
+                *self,
+                &shader_create_info,
+                p_allocator.unwrap_or(null()),
+                &mut module,
+            ))?;
         }
-        module
+        tracking::track(*self, module);
+        Ok(module)
     }
     destroy_func!(DEFINE ShaderModule);
 
@@ -339,40 +568,32 @@ impl VkDeviceFunctions for VkDevice {
     //  Control functions
     //
     // memory
-    fn allocate_memory(&self, memory_allocate_info: *const VkMemoryAllocateInfo, p_allocator: Option<*const VkAllocationCallbacks>) -> VkDeviceMemory {
+    fn allocate_memory(&self, memory_allocate_info: *const VkMemoryAllocateInfo, p_allocator: Option<*const VkAllocationCallbacks>) -> Result<VkDeviceMemory, VkError> {
         let mut memory = vk_instantiate!(VkDeviceMemory);
         unsafe {
-            if let Some(p) = p_allocator {
-                vkAllocateMemory(
-                    *self, 
-                    memory_allocate_info, 
-                    p_allocator.unwrap(),
-                    &mut memory
-                );
-            } else {
-                vkAllocateMemory(
-                    *self,
-                    memory_allocate_info, 
-                    null(),
-                    &mut memory
-                );
-            }
+            vk_check(vkAllocateMemory(
+                *self,
+                memory_allocate_info,
+                p_allocator.unwrap_or(null()),
+                &mut memory
+            ))?;
         }
-        memory
+        tracking::track(*self, memory);
+        Ok(memory)
     }
 
-    fn map_memory(&self, offset: u64, size: u64, flags: u32, memory: &VkDeviceMemory) -> anyhow::Result<*mut c_void> {
+    fn map_memory(&self, offset: u64, size: u64, flags: u32, memory: &VkDeviceMemory) -> Result<*mut c_void, VkError> {
         unsafe {
             let mut mapped = MaybeUninit::<*mut c_void>::uninit();
 
-            vk_assert(vkMapMemory(
+            vk_check(vkMapMemory(
                 *self,
                 *memory,
                 offset,
                 size,
                 flags,
                 mapped.as_mut_ptr(),
-            ));
+            ))?;
 
             Ok(mapped.assume_init())
         }
@@ -385,25 +606,24 @@ impl VkDeviceFunctions for VkDevice {
     }
 
     fn free_memory(&self, memory: &VkDeviceMemory, p_allocator: Option<*const VkAllocationCallbacks>) {
+        tracking::untrack(*self, *memory);
         unsafe {
-            if let Some(p) = p_allocator {
-                vkFreeMemory(*self, *memory, p);
-            } else {
-                vkFreeMemory(*self, *memory, null());
-            }
+            vkFreeMemory(*self, *memory, p_allocator.unwrap_or(null()));
         }
     }
 
-    fn flush_mapped_memory_range(&self, memory_range_count: u32, p_memory_ranges: *const VkMappedMemoryRange) {
+    fn flush_mapped_memory_range(&self, memory_range_count: u32, p_memory_ranges: *const VkMappedMemoryRange) -> Result<(), VkError> {
         unsafe  {
-            vkFlushMappedMemoryRanges(*self, memory_range_count, p_memory_ranges);
+            vk_check(vkFlushMappedMemoryRanges(*self, memory_range_count, p_memory_ranges))?;
         }
+        Ok(())
     }
     
-    fn invalidate_mapped_memory_ranges(&self, memory_range_count: u32, p_memory_ranges: *const VkMappedMemoryRange){
+    fn invalidate_mapped_memory_ranges(&self, memory_range_count: u32, p_memory_ranges: *const VkMappedMemoryRange) -> Result<(), VkError> {
         unsafe {
-            vkInvalidateMappedMemoryRanges(*self, memory_range_count, p_memory_ranges);
+            vk_check(vkInvalidateMappedMemoryRanges(*self, memory_range_count, p_memory_ranges))?;
         }
+        Ok(())
     }
 
 
@@ -412,12 +632,11 @@ impl VkDeviceFunctions for VkDevice {
         buffer: VkBuffer,
         memory: VkDeviceMemory,
         memory_offset: VkDeviceSize,
-    ) -> VkResult {
-        let mut result = VkResult::VK_SUCCESS;
+    ) -> Result<(), VkError> {
         unsafe {
-            result = vkBindBufferMemory(*self, buffer, memory, memory_offset);
+            vk_check(vkBindBufferMemory(*self, buffer, memory, memory_offset))?;
         }
-        result
+        Ok(())
     }
 
     fn bind_image_memory(
@@ -425,30 +644,55 @@ impl VkDeviceFunctions for VkDevice {
         image: VkImage,
         memory: VkDeviceMemory,
         memory_offset: VkDeviceSize,
-    ) -> VkResult {
-        let mut result = VkResult::VK_SUCCESS;
+    ) -> Result<(), VkError> {
         unsafe {
-            result = vkBindImageMemory(*self, image, memory, memory_offset);
+            vk_check(vkBindImageMemory(*self, image, memory, memory_offset))?;
         }
-        result
+        Ok(())
     }
 
     // command buffer
     fn allocate_command_buffers(
         &self,
         allocate_info: *const VkCommandBufferAllocateInfo,
-    ) -> Vec<VkCommandBuffer> {
+    ) -> Result<Vec<VkCommandBuffer>, VkError> {
         let size = unsafe { (*allocate_info).commandBufferCount };
         let mut command_buffers = vec![vk_instantiate!(VkCommandBuffer); size as usize];
 
         unsafe {
-            vk_assert(vkAllocateCommandBuffers(
+            vk_check(vkAllocateCommandBuffers(
                 *self,
                 allocate_info,
                 command_buffers.as_mut_ptr(),
-            ));
+            ))?;
+        }
+        Ok(command_buffers)
+    }
+
+    fn free_command_buffers(&self, command_pool: VkCommandPool, command_buffers: &[VkCommandBuffer]) {
+        unsafe {
+            vkFreeCommandBuffers(
+                *self,
+                command_pool,
+                command_buffers.len() as u32,
+                command_buffers.as_ptr(),
+            );
+        }
+    }
+
+    fn reset_command_pool(&self, command_pool: VkCommandPool, flags: impl Into<CommandPoolResetFlags>) -> Result<(), VkError> {
+        unsafe {
+            vk_check(vkResetCommandPool(*self, command_pool, flags.into().bits()))?;
         }
-        command_buffers
+        Ok(())
+    }
+
+    fn trim_command_pool(&self, command_pool: VkCommandPool) -> Result<(), VkError> {
+        require_loaded(*self, |table| table.vkTrimCommandPool.is_some())?;
+        unsafe {
+            vkTrimCommandPool(*self, command_pool, 0);
+        }
+        Ok(())
     }
 
     // Queue
@@ -488,27 +732,28 @@ impl VkDeviceFunctions for VkDevice {
     }
 
     // descriptor set
-    fn allocate_descriptor_sets(&self, allocate_info: &VkDescriptorSetAllocateInfo) -> Vec<VkDescriptorSet> {
+    fn allocate_descriptor_sets(&self, allocate_info: &VkDescriptorSetAllocateInfo) -> Result<Vec<VkDescriptorSet>, VkError> {
         let mut descriptor_sets = vec![vk_instantiate!(VkDescriptorSet); allocate_info.descriptorSetCount as usize];
         unsafe {
-            vkAllocateDescriptorSets(
+            vk_check(vkAllocateDescriptorSets(
                 *self,
                 allocate_info,
                 descriptor_sets.as_mut_ptr()
-            );
+            ))?;
         }
-        descriptor_sets
+        Ok(descriptor_sets)
     }
 
-    fn free_descriptor_sets(&self, descriptor_pool: VkDescriptorPool, descriptor_sets: Vec<VkDescriptorSet>) {
+    fn free_descriptor_sets(&self, descriptor_pool: VkDescriptorPool, descriptor_sets: Vec<VkDescriptorSet>) -> Result<(), VkError> {
         unsafe {
-            vkFreeDescriptorSets(
+            vk_check(vkFreeDescriptorSets(
                 *self,
                 descriptor_pool,
                 descriptor_sets.len() as u32,
                 descriptor_sets.as_ptr()
-            );
+            ))?;
         }
+        Ok(())
     }   
 
     fn update_descriptor_sets(&self, descriptor_writes: &[VkWriteDescriptorSet], descriptor_copies: &[VkCopyDescriptorSet]) {
@@ -523,28 +768,116 @@ impl VkDeviceFunctions for VkDevice {
         }
     }
 
+    fn reset_descriptor_pool(&self, descriptor_pool: VkDescriptorPool) -> Result<(), VkError> {
+        unsafe {
+            vk_check(vkResetDescriptorPool(*self, descriptor_pool, 0))?;
+        }
+        Ok(())
+    }
+
+    fn update_descriptor_set_with_template(&self, descriptor_set: VkDescriptorSet, descriptor_update_template: VkDescriptorUpdateTemplate, data: *const c_void) -> Result<(), VkError> {
+        require_loaded(*self, |table| table.vkUpdateDescriptorSetWithTemplate.is_some())?;
+        unsafe {
+            vkUpdateDescriptorSetWithTemplate(*self, descriptor_set, descriptor_update_template, data);
+        }
+        Ok(())
+    }
+
+    // query
+    fn get_query_pool_results<T: Copy>(
+        &self,
+        query_pool: VkQueryPool,
+        first_query: u32,
+        query_count: u32,
+        data: &mut [T],
+        flags: impl Into<QueryResultFlags>,
+    ) -> Result<VkResult, VkError> {
+        if query_count == 0 {
+            return Ok(VkResult::VK_SUCCESS);
+        }
+        let data_size = std::mem::size_of_val(data);
+        unsafe {
+            vk_check(vkGetQueryPoolResults(
+                *self,
+                query_pool,
+                first_query,
+                query_count,
+                data_size,
+                data.as_mut_ptr() as *mut c_void,
+                (data_size / query_count as usize) as VkDeviceSize,
+                flags.into().bits(),
+            ))
+        }
+    }
+
+    fn reset_query_pool(&self, query_pool: VkQueryPool, first_query: u32, query_count: u32) -> Result<(), VkError> {
+        require_loaded(*self, |table| table.vkResetQueryPool.is_some())?;
+        unsafe {
+            vkResetQueryPool(*self, query_pool, first_query, query_count);
+        }
+        Ok(())
+    }
+
+    // event
+    fn get_event_status(&self, event: VkEvent) -> Result<bool, VkError> {
+        let status = unsafe { vk_check(vkGetEventStatus(*self, event))? };
+        Ok(status == VkResult::VK_EVENT_SET)
+    }
+
+    fn set_event(&self, event: VkEvent) -> Result<(), VkError> {
+        unsafe {
+            vk_check(vkSetEvent(*self, event))?;
+        }
+        Ok(())
+    }
+
+    fn reset_event(&self, event: VkEvent) -> Result<(), VkError> {
+        unsafe {
+            vk_check(vkResetEvent(*self, event))?;
+        }
+        Ok(())
+    }
+
+    // private data
+    fn set_private_data<H: VkHandle>(&self, handle: H, private_data_slot: VkPrivateDataSlot, data: u64) -> Result<(), VkError> {
+        require_loaded(*self, |table| table.vkSetPrivateData.is_some())?;
+        unsafe {
+            vk_check(vkSetPrivateData(*self, H::OBJECT_TYPE, handle.as_raw(), private_data_slot, data))?;
+        }
+        Ok(())
+    }
+
+    fn get_private_data<H: VkHandle>(&self, handle: H, private_data_slot: VkPrivateDataSlot) -> Result<u64, VkError> {
+        require_loaded(*self, |table| table.vkGetPrivateData.is_some())?;
+        let mut data = 0;
+        unsafe {
+            vkGetPrivateData(*self, H::OBJECT_TYPE, handle.as_raw(), private_data_slot, &mut data);
+        }
+        Ok(data)
+    }
+
     // swapchain
-    fn get_swapchain_images_khr(&self, swapchain: VkSwapchainKHR) -> Vec<VkImage> {
+    fn get_swapchain_images_khr(&self, swapchain: VkSwapchainKHR) -> Result<Vec<VkImage>, VkError> {
         // get images count
         let mut n_images: u32 = 0;
         unsafe {
-            vk_assert(vkGetSwapchainImagesKHR(
+            vk_check(vkGetSwapchainImagesKHR(
                 *self,
                 swapchain,
                 &mut n_images,
                 null_mut(),
-            ));
+            ))?;
         }
         let mut images = vec![vk_instantiate!(VkImage); n_images as usize];
         unsafe {
-            vk_assert(vkGetSwapchainImagesKHR(
+            vk_check(vkGetSwapchainImagesKHR(
                 *self,
                 swapchain,
                 &mut n_images,
                 images.as_mut_ptr(),
-            ));
+            ))?;
         }
-        images
+        Ok(images)
     }
 
     fn acquire_next_image_khr(
@@ -553,54 +886,67 @@ impl VkDeviceFunctions for VkDevice {
         timeout: u64,
         semaphore: VkSemaphore,
         fence: VkFence,
-    ) -> Result<u32, VkResult> {
+    ) -> Result<(u32, VkResult), VkError> {
         let mut image_index = 0;
-        let mut result = VkResult::VK_SUCCESS;
-        unsafe {
-            result = vkAcquireNextImageKHR(
+        let result = unsafe {
+            vk_check(vkAcquireNextImageKHR(
                 *self,
                 swapchain,
                 timeout,
                 semaphore,
                 fence,
                 &mut image_index,
-            );
-        }
+            ))?
+        };
 
-        let x: Result<u32, VkResult>;
-        match result {
-            VkResult::VK_SUCCESS => x = Result::Ok(image_index),
-            _ => x = Err(result),
-        }
-        x
+        Ok((image_index, result))
     }
 
     // Fence
-    fn wait_for_fence(&self, fences: &[VkFence], wait_all: bool, timeout: u64) {
+    fn wait_for_fence(&self, fences: &[VkFence], wait_all: bool, timeout: u64) -> Result<VkResult, VkError> {
         unsafe {
-            vk_assert(vkWaitForFences(
+            vk_check(vkWaitForFences(
                 *self,
                 fences.len() as u32,
                 fences.as_ptr(),
                 wait_all as VkBool32,
                 timeout,
-            ));
+            ))
         }
     }
 
-    fn reset_fence(&self, fences: &[VkFence]) {
+    fn reset_fence(&self, fences: &[VkFence]) -> Result<(), VkError> {
         unsafe {
-            vkResetFences(*self, fences.len() as u32, fences.as_ptr());
+            vk_check(vkResetFences(*self, fences.len() as u32, fences.as_ptr()))?;
         }
+        Ok(())
     }
 
     // wait
-    fn wait_idle(&self) -> anyhow::Result<VkResult> {
-        let result: anyhow::Result<VkResult>;
+    fn wait_idle(&self) -> Result<(), VkError> {
+        unsafe {
+            vk_check(vkDeviceWaitIdle(*self))?;
+        }
+        Ok(())
+    }
+
+    fn set_object_name<H: VkHandle>(&self, handle: H, name: &str) -> Result<(), VkError> {
+        // the leak report shows the name even without the extension
+        tracking::set_name(*self, handle, name);
+        if !loader::is_device_command_loaded(*self, |table| table.vkSetDebugUtilsObjectNameEXT.is_some()) {
+            return Ok(());
+        }
+
+        let name = CString::new(name.replace('\0', "")).unwrap();
+        let name_info = VkDebugUtilsObjectNameInfoEXTBuilder::new()
+            .object_type(H::OBJECT_TYPE)
+            .object_handle(handle.as_raw())
+            .p_object_name(name.as_ptr())
+            .build();
         unsafe {
-            result = Ok(vkDeviceWaitIdle(*self));
+            vk_check(vkSetDebugUtilsObjectNameEXT(*self, &name_info))?;
         }
-        result
+        Ok(())
     }
 }
 
@@ -609,35 +955,817 @@ pub trait VkQueueFunctions {
         &self,
         infos: &[VkSubmitInfo],
         fence: Option<VkFence>,
-    );
-    fn wait_idle(&self);
-    fn present_khr(&self, index: usize, present_info: &VkPresentInfoKHR) -> VkResult;
+    ) -> Result<(), VkError>;
+    fn wait_idle(&self) -> Result<(), VkError>;
+    // the success code tells VK_SUBOPTIMAL_KHR apart from VK_SUCCESS
+    fn present_khr(&self, index: usize, present_info: &VkPresentInfoKHR) -> Result<VkResult, VkError>;
 }
 
+// the submit and present infos are passed on to vulkan as is
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 impl VkQueueFunctions for VkQueue {
     fn submit(
         &self,
         infos: &[VkSubmitInfo],
         opt_fence: Option<VkFence>,
+    ) -> Result<(), VkError> {
+        unsafe {
+            vk_check(vkQueueSubmit(
+                *self,
+                infos.len() as u32,
+                infos.as_ptr(),
+                opt_fence.unwrap_or(null_mut()),
+            ))?;
+        }
+        Ok(())
+    }
+
+    fn wait_idle(&self) -> Result<(), VkError> {
+        unsafe {
+            vk_check(vkQueueWaitIdle(*self))?;
+        }
+        Ok(())
+    }
+
+    fn present_khr(&self, index: usize, present_info: &VkPresentInfoKHR) -> Result<VkResult, VkError> {
+        unsafe { vk_check(vkQueuePresentKHR(*self, present_info)) }
+    }
+}
+
+///
+/// Recording on a VkCommandBuffer, one method per vkCmd*
+///
+/// The method-call counterpart of `vkCmdBlock!`, recording can be split across functions,
+/// loops and conditions:
+/// ```ignore
+/// cmd.begin(CommandBufferUsageFlags::ONE_TIME_SUBMIT)?;
+/// cmd.bind_pipeline(VK_PIPELINE_BIND_POINT_COMPUTE, pipeline);
+/// for (i, set) in descriptor_sets.iter().enumerate() {
+///     cmd.bind_descriptor_sets(VK_PIPELINE_BIND_POINT_COMPUTE, layout, 0, &[*set], &[]);
+///     cmd.dispatch(groups[i], 1, 1);
+/// }
+/// cmd.end()?;
+/// ```
+///
+pub trait VkCommandBufferFunctions {
+    fn begin(&self, flags: impl Into<CommandBufferUsageFlags>) -> Result<(), VkError>;
+    // e.g. secondary command buffers with their inheritance info
+    fn begin_with(&self, begin_info: &VkCommandBufferBeginInfo) -> Result<(), VkError>;
+    fn end(&self) -> Result<(), VkError>;
+    // the pool needs VK_COMMAND_POOL_CREATE_RESET_COMMAND_BUFFER_BIT
+    fn reset(&self, flags: impl Into<CommandBufferResetFlags>) -> Result<(), VkError>;
+
+    // pipeline and bindings
+    fn bind_pipeline(&self, pipeline_bind_point: VkPipelineBindPoint, pipeline: VkPipeline);
+    fn bind_descriptor_sets(&self, pipeline_bind_point: VkPipelineBindPoint, layout: VkPipelineLayout, first_set: u32, descriptor_sets: &[VkDescriptorSet], dynamic_offsets: &[u32]);
+    fn bind_index_buffer(&self, buffer: VkBuffer, offset: VkDeviceSize, index_type: VkIndexType);
+    // `buffers` and `offsets` have the same length
+    fn bind_vertex_buffers(&self, first_binding: u32, buffers: &[VkBuffer], offsets: &[VkDeviceSize]);
+    fn push_constants<T: Copy>(&self, layout: VkPipelineLayout, stage_flags: impl Into<ShaderStageFlags>, offset: u32, values: &[T]);
+    // 1.3, `sizes` and `strides` are empty or as long as `buffers`
+    fn bind_vertex_buffers2(&self, first_binding: u32, buffers: &[VkBuffer], offsets: &[VkDeviceSize], sizes: &[VkDeviceSize], strides: &[VkDeviceSize]);
+
+    // dynamic state
+    fn set_viewport(&self, first_viewport: u32, viewports: &[VkViewport]);
+    fn set_scissor(&self, first_scissor: u32, scissors: &[VkRect2D]);
+    fn set_line_width(&self, line_width: f32);
+    fn set_depth_bias(&self, constant_factor: f32, clamp: f32, slope_factor: f32);
+    fn set_blend_constants(&self, blend_constants: [f32; 4]);
+    fn set_depth_bounds(&self, min_depth_bounds: f32, max_depth_bounds: f32);
+    fn set_stencil_compare_mask(&self, face_mask: impl Into<StencilFaceFlags>, compare_mask: u32);
+    fn set_stencil_write_mask(&self, face_mask: impl Into<StencilFaceFlags>, write_mask: u32);
+    fn set_stencil_reference(&self, face_mask: impl Into<StencilFaceFlags>, reference: u32);
+    // 1.1
+    fn set_device_mask(&self, device_mask: u32);
+    // 1.3
+    fn set_cull_mode(&self, cull_mode: impl Into<CullModeFlags>);
+    fn set_front_face(&self, front_face: VkFrontFace);
+    fn set_primitive_topology(&self, primitive_topology: VkPrimitiveTopology);
+    fn set_viewport_with_count(&self, viewports: &[VkViewport]);
+    fn set_scissor_with_count(&self, scissors: &[VkRect2D]);
+    fn set_depth_test_enable(&self, depth_test_enable: bool);
+    fn set_depth_write_enable(&self, depth_write_enable: bool);
+    fn set_depth_compare_op(&self, depth_compare_op: VkCompareOp);
+    fn set_depth_bounds_test_enable(&self, depth_bounds_test_enable: bool);
+    fn set_stencil_test_enable(&self, stencil_test_enable: bool);
+    fn set_stencil_op(&self, face_mask: impl Into<StencilFaceFlags>, fail_op: VkStencilOp, pass_op: VkStencilOp, depth_fail_op: VkStencilOp, compare_op: VkCompareOp);
+    fn set_rasterizer_discard_enable(&self, rasterizer_discard_enable: bool);
+    fn set_depth_bias_enable(&self, depth_bias_enable: bool);
+    fn set_primitive_restart_enable(&self, primitive_restart_enable: bool);
+
+    // draw and dispatch
+    fn draw(&self, vertex_count: u32, instance_count: u32, first_vertex: u32, first_instance: u32);
+    fn draw_indexed(&self, index_count: u32, instance_count: u32, first_index: u32, vertex_offset: i32, first_instance: u32);
+    fn draw_indirect(&self, buffer: VkBuffer, offset: VkDeviceSize, draw_count: u32, stride: u32);
+    fn draw_indexed_indirect(&self, buffer: VkBuffer, offset: VkDeviceSize, draw_count: u32, stride: u32);
+    fn dispatch(&self, group_count_x: u32, group_count_y: u32, group_count_z: u32);
+    fn dispatch_indirect(&self, buffer: VkBuffer, offset: VkDeviceSize);
+    // 1.1
+    fn dispatch_base(&self, base_group_x: u32, base_group_y: u32, base_group_z: u32, group_count_x: u32, group_count_y: u32, group_count_z: u32);
+    // 1.2
+    fn draw_indirect_count(&self, buffer: VkBuffer, offset: VkDeviceSize, count_buffer: VkBuffer, count_buffer_offset: VkDeviceSize, max_draw_count: u32, stride: u32);
+    fn draw_indexed_indirect_count(&self, buffer: VkBuffer, offset: VkDeviceSize, count_buffer: VkBuffer, count_buffer_offset: VkDeviceSize, max_draw_count: u32, stride: u32);
+
+    // transfer
+    fn copy_buffer(&self, src_buffer: VkBuffer, dst_buffer: VkBuffer, regions: &[VkBufferCopy]);
+    fn copy_image(&self, src_image: VkImage, src_image_layout: VkImageLayout, dst_image: VkImage, dst_image_layout: VkImageLayout, regions: &[VkImageCopy]);
+    fn blit_image(&self, src_image: VkImage, src_image_layout: VkImageLayout, dst_image: VkImage, dst_image_layout: VkImageLayout, regions: &[VkImageBlit], filter: VkFilter);
+    fn copy_buffer_to_image(&self, src_buffer: VkBuffer, dst_image: VkImage, dst_image_layout: VkImageLayout, regions: &[VkBufferImageCopy]);
+    fn copy_image_to_buffer(&self, src_image: VkImage, src_image_layout: VkImageLayout, dst_buffer: VkBuffer, regions: &[VkBufferImageCopy]);
+    fn resolve_image(&self, src_image: VkImage, src_image_layout: VkImageLayout, dst_image: VkImage, dst_image_layout: VkImageLayout, regions: &[VkImageResolve]);
+    // at most 65536 bytes, see vkCmdUpdateBuffer
+    fn update_buffer<T: Copy>(&self, dst_buffer: VkBuffer, dst_offset: VkDeviceSize, data: &[T]);
+    fn fill_buffer(&self, dst_buffer: VkBuffer, dst_offset: VkDeviceSize, size: VkDeviceSize, data: u32);
+    fn clear_color_image(&self, image: VkImage, image_layout: VkImageLayout, color: &VkClearColorValue, ranges: &[VkImageSubresourceRange]);
+    fn clear_depth_stencil_image(&self, image: VkImage, image_layout: VkImageLayout, depth_stencil: &VkClearDepthStencilValue, ranges: &[VkImageSubresourceRange]);
+    fn clear_attachments(&self, attachments: &[VkClearAttachment], rects: &[VkClearRect]);
+    // 1.3, the regions are part of the infos
+    fn copy_buffer2(&self, copy_buffer_info: &VkCopyBufferInfo2);
+    fn copy_image2(&self, copy_image_info: &VkCopyImageInfo2);
+    fn copy_buffer_to_image2(&self, copy_buffer_to_image_info: &VkCopyBufferToImageInfo2);
+    fn copy_image_to_buffer2(&self, copy_image_to_buffer_info: &VkCopyImageToBufferInfo2);
+    fn blit_image2(&self, blit_image_info: &VkBlitImageInfo2);
+    fn resolve_image2(&self, resolve_image_info: &VkResolveImageInfo2);
+
+    // synchronization
+    fn pipeline_barrier(
+        &self,
+        src_stage_mask: impl Into<PipelineStageFlags>,
+        dst_stage_mask: impl Into<PipelineStageFlags>,
+        dependency_flags: impl Into<DependencyFlags>,
+        memory_barriers: &[VkMemoryBarrier],
+        buffer_memory_barriers: &[VkBufferMemoryBarrier],
+        image_memory_barriers: &[VkImageMemoryBarrier],
+    );
+    fn set_event(&self, event: VkEvent, stage_mask: impl Into<PipelineStageFlags>);
+    fn reset_event(&self, event: VkEvent, stage_mask: impl Into<PipelineStageFlags>);
+    fn wait_events(
+        &self,
+        events: &[VkEvent],
+        src_stage_mask: impl Into<PipelineStageFlags>,
+        dst_stage_mask: impl Into<PipelineStageFlags>,
+        memory_barriers: &[VkMemoryBarrier],
+        buffer_memory_barriers: &[VkBufferMemoryBarrier],
+        image_memory_barriers: &[VkImageMemoryBarrier],
+    );
+    // 1.3
+    fn pipeline_barrier2(&self, dependency_info: &VkDependencyInfo);
+    fn set_event2(&self, event: VkEvent, dependency_info: &VkDependencyInfo);
+    fn reset_event2(&self, event: VkEvent, stage_mask: impl Into<PipelineStageFlags2>);
+    // one dependency info per event
+    fn wait_events2(&self, events: &[VkEvent], dependency_infos: &[VkDependencyInfo]);
+
+    // queries
+    fn begin_query(&self, query_pool: VkQueryPool, query: u32, flags: impl Into<QueryControlFlags>);
+    fn end_query(&self, query_pool: VkQueryPool, query: u32);
+    fn reset_query_pool(&self, query_pool: VkQueryPool, first_query: u32, query_count: u32);
+    // `pipeline_stage` is a single stage
+    fn write_timestamp(&self, pipeline_stage: impl Into<PipelineStageFlags>, query_pool: VkQueryPool, query: u32);
+    // the parameters of vkCmdCopyQueryPoolResults
+    #[allow(clippy::too_many_arguments)]
+    fn copy_query_pool_results(&self, query_pool: VkQueryPool, first_query: u32, query_count: u32, dst_buffer: VkBuffer, dst_offset: VkDeviceSize, stride: VkDeviceSize, flags: impl Into<QueryResultFlags>);
+    // 1.3
+    fn write_timestamp2(&self, stage: impl Into<PipelineStageFlags2>, query_pool: VkQueryPool, query: u32);
+
+    // render pass
+    fn begin_render_pass(&self, render_pass_begin: &VkRenderPassBeginInfo, contents: VkSubpassContents);
+    fn next_subpass(&self, contents: VkSubpassContents);
+    fn end_render_pass(&self);
+    fn execute_commands(&self, command_buffers: &[VkCommandBuffer]);
+    // 1.2
+    fn begin_render_pass2(&self, render_pass_begin: &VkRenderPassBeginInfo, subpass_begin_info: &VkSubpassBeginInfo);
+    fn next_subpass2(&self, subpass_begin_info: &VkSubpassBeginInfo, subpass_end_info: &VkSubpassEndInfo);
+    fn end_render_pass2(&self, subpass_end_info: &VkSubpassEndInfo);
+    // 1.3 dynamic rendering
+    fn begin_rendering(&self, rendering_info: &VkRenderingInfo);
+    fn end_rendering(&self);
+
+    // VK_EXT_debug_utils labels, skipped when the extension is not enabled
+    fn begin_label(&self, name: &str, color: [f32; 4]);
+    fn end_label(&self);
+    fn insert_label(&self, name: &str, color: [f32; 4]);
+}
+
+// the handles are only passed on to vulkan
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+impl VkCommandBufferFunctions for VkCommandBuffer {
+    fn begin(&self, flags: impl Into<CommandBufferUsageFlags>) -> Result<(), VkError> {
+        let begin_info = VkCommandBufferBeginInfoBuilder::new().flags(flags).build();
+        self.begin_with(&begin_info)
+    }
+
+    fn begin_with(&self, begin_info: &VkCommandBufferBeginInfo) -> Result<(), VkError> {
+        unsafe {
+            vk_check(vkBeginCommandBuffer(*self, begin_info))?;
+        }
+        Ok(())
+    }
+
+    fn end(&self) -> Result<(), VkError> {
+        unsafe {
+            vk_check(vkEndCommandBuffer(*self))?;
+        }
+        Ok(())
+    }
+
+    fn reset(&self, flags: impl Into<CommandBufferResetFlags>) -> Result<(), VkError> {
+        unsafe {
+            vk_check(vkResetCommandBuffer(*self, flags.into().bits()))?;
+        }
+        Ok(())
+    }
+
+    // pipeline and bindings
+    fn bind_pipeline(&self, pipeline_bind_point: VkPipelineBindPoint, pipeline: VkPipeline) {
+        unsafe { vkCmdBindPipeline(*self, pipeline_bind_point, pipeline) }
+    }
+
+    fn bind_descriptor_sets(&self, pipeline_bind_point: VkPipelineBindPoint, layout: VkPipelineLayout, first_set: u32, descriptor_sets: &[VkDescriptorSet], dynamic_offsets: &[u32]) {
+        unsafe {
+            vkCmdBindDescriptorSets(
+                *self,
+                pipeline_bind_point,
+                layout,
+                first_set,
+                descriptor_sets.len() as u32,
+                descriptor_sets.as_ptr(),
+                dynamic_offsets.len() as u32,
+                dynamic_offsets.as_ptr(),
+            )
+        }
+    }
+
+    fn bind_index_buffer(&self, buffer: VkBuffer, offset: VkDeviceSize, index_type: VkIndexType) {
+        unsafe { vkCmdBindIndexBuffer(*self, buffer, offset, index_type) }
+    }
+
+    fn bind_vertex_buffers(&self, first_binding: u32, buffers: &[VkBuffer], offsets: &[VkDeviceSize]) {
+        assert_eq!(buffers.len(), offsets.len(), "[vrx] one offset per vertex buffer");
+        unsafe {
+            vkCmdBindVertexBuffers(
+                *self,
+                first_binding,
+                buffers.len() as u32,
+                buffers.as_ptr(),
+                offsets.as_ptr(),
+            )
+        }
+    }
+
+    fn push_constants<T: Copy>(&self, layout: VkPipelineLayout, stage_flags: impl Into<ShaderStageFlags>, offset: u32, values: &[T]) {
+        unsafe {
+            vkCmdPushConstants(
+                *self,
+                layout,
+                stage_flags.into().bits(),
+                offset,
+                std::mem::size_of_val(values) as u32,
+                values.as_ptr() as *const c_void,
+            )
+        }
+    }
+
+    fn bind_vertex_buffers2(&self, first_binding: u32, buffers: &[VkBuffer], offsets: &[VkDeviceSize], sizes: &[VkDeviceSize], strides: &[VkDeviceSize]) {
+        assert_eq!(buffers.len(), offsets.len(), "[vrx] one offset per vertex buffer");
+        assert!(sizes.is_empty() || sizes.len() == buffers.len(), "[vrx] no size or one size per vertex buffer");
+        assert!(strides.is_empty() || strides.len() == buffers.len(), "[vrx] no stride or one stride per vertex buffer");
+        // an empty slice is passed as null
+        let or_null = |values: &[VkDeviceSize]| if values.is_empty() { null() } else { values.as_ptr() };
+        unsafe {
+            vkCmdBindVertexBuffers2(
+                *self,
+                first_binding,
+                buffers.len() as u32,
+                buffers.as_ptr(),
+                offsets.as_ptr(),
+                or_null(sizes),
+                or_null(strides),
+            )
+        }
+    }
+
+    // dynamic state
+    fn set_viewport(&self, first_viewport: u32, viewports: &[VkViewport]) {
+        unsafe { vkCmdSetViewport(*self, first_viewport, viewports.len() as u32, viewports.as_ptr()) }
+    }
+
+    fn set_scissor(&self, first_scissor: u32, scissors: &[VkRect2D]) {
+        unsafe { vkCmdSetScissor(*self, first_scissor, scissors.len() as u32, scissors.as_ptr()) }
+    }
+
+    fn set_line_width(&self, line_width: f32) {
+        unsafe { vkCmdSetLineWidth(*self, line_width) }
+    }
+
+    fn set_depth_bias(&self, constant_factor: f32, clamp: f32, slope_factor: f32) {
+        unsafe { vkCmdSetDepthBias(*self, constant_factor, clamp, slope_factor) }
+    }
+
+    fn set_blend_constants(&self, blend_constants: [f32; 4]) {
+        unsafe { vkCmdSetBlendConstants(*self, blend_constants.as_ptr()) }
+    }
+
+    fn set_depth_bounds(&self, min_depth_bounds: f32, max_depth_bounds: f32) {
+        unsafe { vkCmdSetDepthBounds(*self, min_depth_bounds, max_depth_bounds) }
+    }
+
+    fn set_stencil_compare_mask(&self, face_mask: impl Into<StencilFaceFlags>, compare_mask: u32) {
+        unsafe { vkCmdSetStencilCompareMask(*self, face_mask.into().bits(), compare_mask) }
+    }
+
+    fn set_stencil_write_mask(&self, face_mask: impl Into<StencilFaceFlags>, write_mask: u32) {
+        unsafe { vkCmdSetStencilWriteMask(*self, face_mask.into().bits(), write_mask) }
+    }
+
+    fn set_stencil_reference(&self, face_mask: impl Into<StencilFaceFlags>, reference: u32) {
+        unsafe { vkCmdSetStencilReference(*self, face_mask.into().bits(), reference) }
+    }
+
+    fn set_device_mask(&self, device_mask: u32) {
+        unsafe { vkCmdSetDeviceMask(*self, device_mask) }
+    }
+
+    fn set_cull_mode(&self, cull_mode: impl Into<CullModeFlags>) {
+        unsafe { vkCmdSetCullMode(*self, cull_mode.into().bits()) }
+    }
+
+    fn set_front_face(&self, front_face: VkFrontFace) {
+        unsafe { vkCmdSetFrontFace(*self, front_face) }
+    }
+
+    fn set_primitive_topology(&self, primitive_topology: VkPrimitiveTopology) {
+        unsafe { vkCmdSetPrimitiveTopology(*self, primitive_topology) }
+    }
+
+    fn set_viewport_with_count(&self, viewports: &[VkViewport]) {
+        unsafe { vkCmdSetViewportWithCount(*self, viewports.len() as u32, viewports.as_ptr()) }
+    }
+
+    fn set_scissor_with_count(&self, scissors: &[VkRect2D]) {
+        unsafe { vkCmdSetScissorWithCount(*self, scissors.len() as u32, scissors.as_ptr()) }
+    }
+
+    fn set_depth_test_enable(&self, depth_test_enable: bool) {
+        unsafe { vkCmdSetDepthTestEnable(*self, depth_test_enable as VkBool32) }
+    }
+
+    fn set_depth_write_enable(&self, depth_write_enable: bool) {
+        unsafe { vkCmdSetDepthWriteEnable(*self, depth_write_enable as VkBool32) }
+    }
+
+    fn set_depth_compare_op(&self, depth_compare_op: VkCompareOp) {
+        unsafe { vkCmdSetDepthCompareOp(*self, depth_compare_op) }
+    }
+
+    fn set_depth_bounds_test_enable(&self, depth_bounds_test_enable: bool) {
+        unsafe { vkCmdSetDepthBoundsTestEnable(*self, depth_bounds_test_enable as VkBool32) }
+    }
+
+    fn set_stencil_test_enable(&self, stencil_test_enable: bool) {
+        unsafe { vkCmdSetStencilTestEnable(*self, stencil_test_enable as VkBool32) }
+    }
+
+    fn set_stencil_op(&self, face_mask: impl Into<StencilFaceFlags>, fail_op: VkStencilOp, pass_op: VkStencilOp, depth_fail_op: VkStencilOp, compare_op: VkCompareOp) {
+        unsafe {
+            vkCmdSetStencilOp(
+                *self,
+                face_mask.into().bits(),
+                fail_op,
+                pass_op,
+                depth_fail_op,
+                compare_op,
+            )
+        }
+    }
+
+    fn set_rasterizer_discard_enable(&self, rasterizer_discard_enable: bool) {
+        unsafe { vkCmdSetRasterizerDiscardEnable(*self, rasterizer_discard_enable as VkBool32) }
+    }
+
+    fn set_depth_bias_enable(&self, depth_bias_enable: bool) {
+        unsafe { vkCmdSetDepthBiasEnable(*self, depth_bias_enable as VkBool32) }
+    }
+
+    fn set_primitive_restart_enable(&self, primitive_restart_enable: bool) {
+        unsafe { vkCmdSetPrimitiveRestartEnable(*self, primitive_restart_enable as VkBool32) }
+    }
+
+    // draw and dispatch
+    fn draw(&self, vertex_count: u32, instance_count: u32, first_vertex: u32, first_instance: u32) {
+        unsafe { vkCmdDraw(*self, vertex_count, instance_count, first_vertex, first_instance) }
+    }
+
+    fn draw_indexed(&self, index_count: u32, instance_count: u32, first_index: u32, vertex_offset: i32, first_instance: u32) {
+        unsafe {
+            vkCmdDrawIndexed(
+                *self,
+                index_count,
+                instance_count,
+                first_index,
+                vertex_offset,
+                first_instance,
+            )
+        }
+    }
+
+    fn draw_indirect(&self, buffer: VkBuffer, offset: VkDeviceSize, draw_count: u32, stride: u32) {
+        unsafe { vkCmdDrawIndirect(*self, buffer, offset, draw_count, stride) }
+    }
+
+    fn draw_indexed_indirect(&self, buffer: VkBuffer, offset: VkDeviceSize, draw_count: u32, stride: u32) {
+        unsafe { vkCmdDrawIndexedIndirect(*self, buffer, offset, draw_count, stride) }
+    }
+
+    fn dispatch(&self, group_count_x: u32, group_count_y: u32, group_count_z: u32) {
+        unsafe { vkCmdDispatch(*self, group_count_x, group_count_y, group_count_z) }
+    }
+
+    fn dispatch_indirect(&self, buffer: VkBuffer, offset: VkDeviceSize) {
+        unsafe { vkCmdDispatchIndirect(*self, buffer, offset) }
+    }
+
+    fn dispatch_base(&self, base_group_x: u32, base_group_y: u32, base_group_z: u32, group_count_x: u32, group_count_y: u32, group_count_z: u32) {
+        unsafe {
+            vkCmdDispatchBase(
+                *self,
+                base_group_x,
+                base_group_y,
+                base_group_z,
+                group_count_x,
+                group_count_y,
+                group_count_z,
+            )
+        }
+    }
+
+    fn draw_indirect_count(&self, buffer: VkBuffer, offset: VkDeviceSize, count_buffer: VkBuffer, count_buffer_offset: VkDeviceSize, max_draw_count: u32, stride: u32) {
+        unsafe {
+            vkCmdDrawIndirectCount(
+                *self,
+                buffer,
+                offset,
+                count_buffer,
+                count_buffer_offset,
+                max_draw_count,
+                stride,
+            )
+        }
+    }
+
+    fn draw_indexed_indirect_count(&self, buffer: VkBuffer, offset: VkDeviceSize, count_buffer: VkBuffer, count_buffer_offset: VkDeviceSize, max_draw_count: u32, stride: u32) {
+        unsafe {
+            vkCmdDrawIndexedIndirectCount(
+                *self,
+                buffer,
+                offset,
+                count_buffer,
+                count_buffer_offset,
+                max_draw_count,
+                stride,
+            )
+        }
+    }
+
+    // transfer
+    fn copy_buffer(&self, src_buffer: VkBuffer, dst_buffer: VkBuffer, regions: &[VkBufferCopy]) {
+        unsafe { vkCmdCopyBuffer(*self, src_buffer, dst_buffer, regions.len() as u32, regions.as_ptr()) }
+    }
+
+    fn copy_image(&self, src_image: VkImage, src_image_layout: VkImageLayout, dst_image: VkImage, dst_image_layout: VkImageLayout, regions: &[VkImageCopy]) {
+        unsafe {
+            vkCmdCopyImage(
+                *self,
+                src_image,
+                src_image_layout,
+                dst_image,
+                dst_image_layout,
+                regions.len() as u32,
+                regions.as_ptr(),
+            )
+        }
+    }
+
+    fn blit_image(&self, src_image: VkImage, src_image_layout: VkImageLayout, dst_image: VkImage, dst_image_layout: VkImageLayout, regions: &[VkImageBlit], filter: VkFilter) {
+        unsafe {
+            vkCmdBlitImage(
+                *self,
+                src_image,
+                src_image_layout,
+                dst_image,
+                dst_image_layout,
+                regions.len() as u32,
+                regions.as_ptr(),
+                filter,
+            )
+        }
+    }
+
+    fn copy_buffer_to_image(&self, src_buffer: VkBuffer, dst_image: VkImage, dst_image_layout: VkImageLayout, regions: &[VkBufferImageCopy]) {
+        unsafe {
+            vkCmdCopyBufferToImage(
+                *self,
+                src_buffer,
+                dst_image,
+                dst_image_layout,
+                regions.len() as u32,
+                regions.as_ptr(),
+            )
+        }
+    }
+
+    fn copy_image_to_buffer(&self, src_image: VkImage, src_image_layout: VkImageLayout, dst_buffer: VkBuffer, regions: &[VkBufferImageCopy]) {
+        unsafe {
+            vkCmdCopyImageToBuffer(
+                *self,
+                src_image,
+                src_image_layout,
+                dst_buffer,
+                regions.len() as u32,
+                regions.as_ptr(),
+            )
+        }
+    }
+
+    fn resolve_image(&self, src_image: VkImage, src_image_layout: VkImageLayout, dst_image: VkImage, dst_image_layout: VkImageLayout, regions: &[VkImageResolve]) {
+        unsafe {
+            vkCmdResolveImage(
+                *self,
+                src_image,
+                src_image_layout,
+                dst_image,
+                dst_image_layout,
+                regions.len() as u32,
+                regions.as_ptr(),
+            )
+        }
+    }
+
+    fn update_buffer<T: Copy>(&self, dst_buffer: VkBuffer, dst_offset: VkDeviceSize, data: &[T]) {
+        unsafe {
+            vkCmdUpdateBuffer(
+                *self,
+                dst_buffer,
+                dst_offset,
+                std::mem::size_of_val(data) as VkDeviceSize,
+                data.as_ptr() as *const c_void,
+            )
+        }
+    }
+
+    fn fill_buffer(&self, dst_buffer: VkBuffer, dst_offset: VkDeviceSize, size: VkDeviceSize, data: u32) {
+        unsafe { vkCmdFillBuffer(*self, dst_buffer, dst_offset, size, data) }
+    }
+
+    fn clear_color_image(&self, image: VkImage, image_layout: VkImageLayout, color: &VkClearColorValue, ranges: &[VkImageSubresourceRange]) {
+        unsafe { vkCmdClearColorImage(*self, image, image_layout, color, ranges.len() as u32, ranges.as_ptr()) }
+    }
+
+    fn clear_depth_stencil_image(&self, image: VkImage, image_layout: VkImageLayout, depth_stencil: &VkClearDepthStencilValue, ranges: &[VkImageSubresourceRange]) {
+        unsafe {
+            vkCmdClearDepthStencilImage(
+                *self,
+                image,
+                image_layout,
+                depth_stencil,
+                ranges.len() as u32,
+                ranges.as_ptr(),
+            )
+        }
+    }
+
+    fn clear_attachments(&self, attachments: &[VkClearAttachment], rects: &[VkClearRect]) {
+        unsafe {
+            vkCmdClearAttachments(
+                *self,
+                attachments.len() as u32,
+                attachments.as_ptr(),
+                rects.len() as u32,
+                rects.as_ptr(),
+            )
+        }
+    }
+
+    fn copy_buffer2(&self, copy_buffer_info: &VkCopyBufferInfo2) {
+        unsafe { vkCmdCopyBuffer2(*self, copy_buffer_info) }
+    }
+
+    fn copy_image2(&self, copy_image_info: &VkCopyImageInfo2) {
+        unsafe { vkCmdCopyImage2(*self, copy_image_info) }
+    }
+
+    fn copy_buffer_to_image2(&self, copy_buffer_to_image_info: &VkCopyBufferToImageInfo2) {
+        unsafe { vkCmdCopyBufferToImage2(*self, copy_buffer_to_image_info) }
+    }
+
+    fn copy_image_to_buffer2(&self, copy_image_to_buffer_info: &VkCopyImageToBufferInfo2) {
+        unsafe { vkCmdCopyImageToBuffer2(*self, copy_image_to_buffer_info) }
+    }
+
+    fn blit_image2(&self, blit_image_info: &VkBlitImageInfo2) {
+        unsafe { vkCmdBlitImage2(*self, blit_image_info) }
+    }
+
+    fn resolve_image2(&self, resolve_image_info: &VkResolveImageInfo2) {
+        unsafe { vkCmdResolveImage2(*self, resolve_image_info) }
+    }
+
+    // synchronization
+    fn pipeline_barrier(
+        &self,
+        src_stage_mask: impl Into<PipelineStageFlags>,
+        dst_stage_mask: impl Into<PipelineStageFlags>,
+        dependency_flags: impl Into<DependencyFlags>,
+        memory_barriers: &[VkMemoryBarrier],
+        buffer_memory_barriers: &[VkBufferMemoryBarrier],
+        image_memory_barriers: &[VkImageMemoryBarrier],
+    ) {
+        unsafe {
+            vkCmdPipelineBarrier(
+                *self,
+                src_stage_mask.into().bits(),
+                dst_stage_mask.into().bits(),
+                dependency_flags.into().bits(),
+                memory_barriers.len() as u32,
+                memory_barriers.as_ptr(),
+                buffer_memory_barriers.len() as u32,
+                buffer_memory_barriers.as_ptr(),
+                image_memory_barriers.len() as u32,
+                image_memory_barriers.as_ptr(),
+            )
+        }
+    }
+
+    fn set_event(&self, event: VkEvent, stage_mask: impl Into<PipelineStageFlags>) {
+        unsafe { vkCmdSetEvent(*self, event, stage_mask.into().bits()) }
+    }
+
+    fn reset_event(&self, event: VkEvent, stage_mask: impl Into<PipelineStageFlags>) {
+        unsafe { vkCmdResetEvent(*self, event, stage_mask.into().bits()) }
+    }
+
+    fn wait_events(
+        &self,
+        events: &[VkEvent],
+        src_stage_mask: impl Into<PipelineStageFlags>,
+        dst_stage_mask: impl Into<PipelineStageFlags>,
+        memory_barriers: &[VkMemoryBarrier],
+        buffer_memory_barriers: &[VkBufferMemoryBarrier],
+        image_memory_barriers: &[VkImageMemoryBarrier],
     ) {
         unsafe {
-        if let Some(fence) = opt_fence {
-            vk_assert(vkQueueSubmit(*self, infos.len() as u32, infos.as_ptr(), fence));
-        } else {
-            vk_assert(vkQueueSubmit(*self, infos.len() as u32, infos.as_ptr(), std::ptr::null_mut()));
+            vkCmdWaitEvents(
+                *self,
+                events.len() as u32,
+                events.as_ptr(),
+                src_stage_mask.into().bits(),
+                dst_stage_mask.into().bits(),
+                memory_barriers.len() as u32,
+                memory_barriers.as_ptr(),
+                buffer_memory_barriers.len() as u32,
+                buffer_memory_barriers.as_ptr(),
+                image_memory_barriers.len() as u32,
+                image_memory_barriers.as_ptr(),
+            )
         }
+    }
+
+    fn pipeline_barrier2(&self, dependency_info: &VkDependencyInfo) {
+        unsafe { vkCmdPipelineBarrier2(*self, dependency_info) }
+    }
+
+    fn set_event2(&self, event: VkEvent, dependency_info: &VkDependencyInfo) {
+        unsafe { vkCmdSetEvent2(*self, event, dependency_info) }
+    }
+
+    fn reset_event2(&self, event: VkEvent, stage_mask: impl Into<PipelineStageFlags2>) {
+        unsafe { vkCmdResetEvent2(*self, event, stage_mask.into().bits()) }
+    }
+
+    fn wait_events2(&self, events: &[VkEvent], dependency_infos: &[VkDependencyInfo]) {
+        assert_eq!(events.len(), dependency_infos.len(), "[vrx] one dependency info per event");
+        unsafe { vkCmdWaitEvents2(*self, events.len() as u32, events.as_ptr(), dependency_infos.as_ptr()) }
+    }
+
+    // queries
+    fn begin_query(&self, query_pool: VkQueryPool, query: u32, flags: impl Into<QueryControlFlags>) {
+        unsafe { vkCmdBeginQuery(*self, query_pool, query, flags.into().bits()) }
+    }
+
+    fn end_query(&self, query_pool: VkQueryPool, query: u32) {
+        unsafe { vkCmdEndQuery(*self, query_pool, query) }
+    }
+
+    fn reset_query_pool(&self, query_pool: VkQueryPool, first_query: u32, query_count: u32) {
+        unsafe { vkCmdResetQueryPool(*self, query_pool, first_query, query_count) }
+    }
+
+    fn write_timestamp(&self, pipeline_stage: impl Into<PipelineStageFlags>, query_pool: VkQueryPool, query: u32) {
+        unsafe {
+            vkCmdWriteTimestamp(
+                *self,
+                pipeline_stage.into().bits() as VkPipelineStageFlagBits,
+                query_pool,
+                query,
+            )
         }
     }
 
-    fn wait_idle(&self) {
+    fn copy_query_pool_results(&self, query_pool: VkQueryPool, first_query: u32, query_count: u32, dst_buffer: VkBuffer, dst_offset: VkDeviceSize, stride: VkDeviceSize, flags: impl Into<QueryResultFlags>) {
         unsafe {
-            vkQueueWaitIdle(*self);
+            vkCmdCopyQueryPoolResults(
+                *self,
+                query_pool,
+                first_query,
+                query_count,
+                dst_buffer,
+                dst_offset,
+                stride,
+                flags.into().bits(),
+            )
         }
     }
 
-    fn present_khr(&self, index: usize, present_info: &VkPresentInfoKHR) -> VkResult {
-        let mut result = VkResult::VK_SUCCESS;
-        unsafe { result = vkQueuePresentKHR(*self, present_info) }
-        result
+    fn write_timestamp2(&self, stage: impl Into<PipelineStageFlags2>, query_pool: VkQueryPool, query: u32) {
+        unsafe { vkCmdWriteTimestamp2(*self, stage.into().bits(), query_pool, query) }
+    }
+
+    // render pass
+    fn begin_render_pass(&self, render_pass_begin: &VkRenderPassBeginInfo, contents: VkSubpassContents) {
+        unsafe { vkCmdBeginRenderPass(*self, render_pass_begin, contents) }
+    }
+
+    fn next_subpass(&self, contents: VkSubpassContents) {
+        unsafe { vkCmdNextSubpass(*self, contents) }
+    }
+
+    fn end_render_pass(&self) {
+        unsafe { vkCmdEndRenderPass(*self) }
     }
-}
\ No newline at end of file
+
+    fn execute_commands(&self, command_buffers: &[VkCommandBuffer]) {
+        unsafe { vkCmdExecuteCommands(*self, command_buffers.len() as u32, command_buffers.as_ptr()) }
+    }
+
+    fn begin_render_pass2(&self, render_pass_begin: &VkRenderPassBeginInfo, subpass_begin_info: &VkSubpassBeginInfo) {
+        unsafe { vkCmdBeginRenderPass2(*self, render_pass_begin, subpass_begin_info) }
+    }
+
+    fn next_subpass2(&self, subpass_begin_info: &VkSubpassBeginInfo, subpass_end_info: &VkSubpassEndInfo) {
+        unsafe { vkCmdNextSubpass2(*self, subpass_begin_info, subpass_end_info) }
+    }
+
+    fn end_render_pass2(&self, subpass_end_info: &VkSubpassEndInfo) {
+        unsafe { vkCmdEndRenderPass2(*self, subpass_end_info) }
+    }
+
+    fn begin_rendering(&self, rendering_info: &VkRenderingInfo) {
+        unsafe { vkCmdBeginRendering(*self, rendering_info) }
+    }
+
+    fn end_rendering(&self) {
+        unsafe { vkCmdEndRendering(*self) }
+    }
+
+    // labels
+    fn begin_label(&self, name: &str, color: [f32; 4]) {
+        cmd_begin_label(*self, name, color);
+    }
+
+    fn end_label(&self) {
+        cmd_end_label(*self);
+    }
+
+    fn insert_label(&self, name: &str, color: [f32; 4]) {
+        cmd_insert_label(*self, name, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shader_code_has_to_be_whole_words() {
+        // rejected before the driver sees it
+        let device: VkDevice = null_mut();
+        assert_eq!(
+            device.create_shader_module(&[0x03, 0x02, 0x23, 0x07, 0x00, 0x00], None),
+            Err(VkError::InvalidShaderCode(6))
+        );
+    }
+
+    #[test]
+    fn later_core_commands_need_a_device_that_loads_them() {
+        // not created through the loader, nothing is loaded for it
+        static KEY: usize = 0x2001;
+        let device = &KEY as *const usize as VkDevice;
+
+        assert_eq!(device.trim_command_pool(null_mut()), Err(VkError::FeatureNotPresent));
+        assert_eq!(device.reset_query_pool(null_mut(), 0, 1), Err(VkError::FeatureNotPresent));
+        assert_eq!(device.get_private_data(device, null_mut()), Err(VkError::FeatureNotPresent));
+        assert_eq!(
+            device.create_private_data_slot(&VkPrivateDataSlotCreateInfo::default(), None),
+            Err(VkError::FeatureNotPresent)
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//
+// build/ generators against tests/registry/excerpt.xml instead of the full registry
+//
+#[allow(dead_code)]
+#[path = "../build/builders.rs"]
+mod builders;
+#[allow(dead_code)]
+#[path = "../build/commands.rs"]
+mod commands;
+#[allow(dead_code)]
+#[path = "../build/flags.rs"]
+mod flags;
+
+use std::collections::HashMap;
+
+use roxmltree::Document;
+
+// read by builders::generate
+const VK_HEADER_VERSION: u32 = 251;
+
+const EXCERPT: &str = include_str!("registry/excerpt.xml");
+
+fn generated(generate: impl FnOnce(&Document, &HashMap<&str, String>) -> String) -> String {
+    let registry = Document::parse(EXCERPT).unwrap();
+    let typed = flags::typed_flags(&registry);
+    generate(&registry, &typed)
+}
+
+#[test]
+fn builders_take_stype_and_pnext_bases_from_the_registry() {
+    let out = generated(builders::builders);
+
+    assert!(out.contains(
+        "impl_builder_for_vk_structure_t!(
+    VK_STRUCTURE_TYPE_IMAGE_FORMAT_LIST_CREATE_INFO,
+    extends [VkImageCreateInfo],
+    pub struct VkImageFormatListCreateInfo {"
+    ));
+    // VkPhysicalDeviceImageFormatInfo2 is not part of the excerpt
+    assert!(!out.contains("VkPhysicalDeviceImageFormatInfo2"));
+    assert!(out.contains(
+        "pub type VkImageFormatListCreateInfoKHRBuilder<'a> = VkImageFormatListCreateInfoBuilder<'a>;"
+    ));
+    assert!(out.contains(
+        "impl_builder_for_vk_none_structure_t!(
+    pub struct VkExtent2D {
+        pub width: u32,
+        pub height: u32,
+    }
+);"
+    ));
+}
+
+#[test]
+fn builder_fields_match_bindgen() {
+    let out = generated(builders::builders);
+
+    assert!(out.contains("        pub pNext: *const ::std::os::raw::c_void,\n"));
+    assert!(out.contains("        pub pQueueFamilyIndices: *const u32,\n"));
+    assert!(out.contains("        pub matrix: [[f32; 4]; 3],\n"));
+    assert!(out.contains("        pub type_: VkGeometryTypeKHR,\n"));
+    // bitfields are packed by bindgen and get no setter
+    assert!(out.contains(
+        "    pub struct VkAccelerationStructureInstanceKHR {
+        pub transform: VkTransformMatrixKHR,
+    }"
+    ));
+    // plain flag members take the typed flags, flags without bits stay raw
+    assert!(out.contains("        pub usage: VkImageUsageFlags as ImageUsageFlags,\n"));
+    assert!(
+        out.contains("        pub srcStageMask: VkPipelineStageFlags2 as PipelineStageFlags2,\n")
+    );
+    assert!(out.contains("        pub flags: VkImageCreateFlags,\n"));
+}
+
+#[test]
+fn builders_skip_provisional_and_other_platforms() {
+    let out = generated(builders::builders);
+
+    assert!(!out.contains("VkPortabilitySubsetFeaturesKHR"));
+    assert!(!out.contains("VkAndroidSurfaceCreateInfoKHR"));
+}
+
+#[test]
+fn flags_collect_bits_of_versions_and_extensions() {
+    let out = generated(|registry, _| flags::flags(registry));
+
+    assert!(out.contains(
+        "vk_flags! {
+    pub struct ImageUsageFlags(VkFlags) from i32 {
+        TRANSFER_SRC = 0x1,
+        TRANSFER_DST = 0x2,
+        STORAGE = 0x8,
+        FRAGMENT_DENSITY_MAP_EXT = 0x200,
+    }
+}"
+    ));
+    // 64-bit bits, aliases resolve to the value they name
+    assert!(out.contains(
+        "vk_flags! {
+    pub struct PipelineStageFlags2(VkFlags64) {
+        NONE = 0x0,
+        NONE_KHR = 0x0,
+        COMPUTE_SHADER = 0x800,
+        COPY = 0x100000000,
+    }
+}"
+    ));
+}
+
+#[test]
+fn typed_flags_cover_bits_masks_and_aliases() {
+    let registry = Document::parse(EXCERPT).unwrap();
+    let typed = flags::typed_flags(&registry);
+
+    assert_eq!(typed["VkImageUsageFlagBits"], "ImageUsageFlags");
+    assert_eq!(typed["VkImageUsageFlags"], "ImageUsageFlags");
+    assert_eq!(typed["VkPipelineStageFlags2KHR"], "PipelineStageFlags2");
+    assert!(!typed.contains_key("VkImageCreateFlags"));
+}
+
+#[test]
+fn commands_pass_flags_through_their_typed_wrapper() {
+    let out = generated(commands::commands);
+
+    assert!(out.contains(
+        "    ($cmd:expr, DRAW($vertex_count:expr, $instance_count:expr, $first_vertex:expr, $first_instance:expr)) => {
+        vkCmdDraw($cmd, $vertex_count, $instance_count, $first_vertex, $first_instance);
+    };"
+    ));
+    assert!(out.contains(
+        "vkCmdPipelineBarrier($cmd, PipelineStageFlags::from($src_stage_mask).bits(), PipelineStageFlags::from($dst_stage_mask).bits());"
+    ));
+    // arrays are passed as they are
+    assert!(out.contains("vkCmdSetBlendConstants($cmd, $blend_constants);"));
+}
+
+#[test]
+fn commands_cover_later_versions_but_not_their_aliases() {
+    let out = generated(commands::commands);
+
+    assert!(out.contains(
+        "    ($cmd:expr, WRITE_TIMESTAMP2($stage:expr)) => {
+        vkCmdWriteTimestamp2($cmd, PipelineStageFlags2::from($stage).bits());
+    };"
+    ));
+    assert!(!out.contains("vkCmdWriteTimestamp2KHR"));
+}
+
+#[test]
+fn table_has_the_parameters_and_render_pass_scope_of_the_arms() {
+    let registry = Document::parse(EXCERPT).unwrap();
+    let out = commands::table(&registry);
+
+    assert!(out.contains(
+        "    (\"DRAW\", &[\"vertex_count\", \"instance_count\", \"first_vertex\", \"first_instance\"], \"inside\"),\n"
+    ));
+    assert!(out.contains("    (\"FILL_BUFFER\", &[\"dst_buffer\", \"dst_offset\", \"size\", \"data\"], \"outside\"),\n"));
+    assert!(out.contains("    (\"WRITE_TIMESTAMP2\", &[\"stage\"], \"both\"),\n"));
+}
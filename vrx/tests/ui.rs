@@ -0,0 +1,8 @@
+//
+// vkCmdBlock! invocations that have to compile, nothing is recorded when they run
+//
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+}
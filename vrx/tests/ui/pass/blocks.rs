@@ -0,0 +1,59 @@
+// RENDER_PASS and LABEL blocks, and render passes begun and ended by hand
+#![allow(dead_code)]
+
+use vrx::*;
+
+fn blocks(
+    command: VkCommandBuffer,
+    render_pass_begin_info: &VkRenderPassBeginInfo,
+    pipeline: VkPipeline,
+) -> Result<(), VkError> {
+    vkCmdBlock! {
+        THIS command;
+        LABEL("shadow pass", [1.0, 0.5, 0.0, 1.0]) {
+            RENDER_PASS(render_pass_begin_info, INLINE) {
+                BIND_PIPELINE(VkPipelineBindPoint::VK_PIPELINE_BIND_POINT_GRAPHICS, pipeline);
+                LABEL("draw") {
+                    DRAW(3, 1, 0, 0);
+                }
+            }
+        }
+        DISPATCH(1, 1, 1);
+    }
+}
+
+fn begin_and_end(
+    command: VkCommandBuffer,
+    render_pass_begin_info: &VkRenderPassBeginInfo,
+) -> Result<(), VkError> {
+    vkCmdBlock! {
+        THIS command;
+        LABEL("copy") {
+            DISPATCH(1, 1, 1);
+        }
+        BEGIN_RENDER_PASS(render_pass_begin_info, VkSubpassContents::VK_SUBPASS_CONTENTS_INLINE);
+        LABEL("draw") {
+            DRAW(3, 1, 0, 0);
+        }
+        NEXT_SUBPASS(VkSubpassContents::VK_SUBPASS_CONTENTS_INLINE);
+        END_RENDER_PASS();
+        BEGIN_LABEL("after");
+        INSERT_LABEL("marker", [0.0, 1.0, 0.0, 1.0]);
+        END_LABEL();
+    }
+}
+
+fn secondary(
+    secondary: VkCommandBuffer,
+    render_pass: VkRenderPass,
+    framebuffer: VkFramebuffer,
+) -> Result<(), VkError> {
+    vkCmdBlock! {
+        THIS secondary SECONDARY (render_pass, 0, framebuffer);
+        LABEL("draw") {
+            DRAW(3, 1, 0, 0);
+        }
+    }
+}
+
+fn main() {}
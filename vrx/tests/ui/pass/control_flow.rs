@@ -0,0 +1,72 @@
+// for, if/else and { } bodies, plain statements and helper calls between the commands
+#![allow(dead_code)]
+
+use vrx::*;
+
+struct Mesh {
+    hidden: bool,
+    vertices: VkBuffer,
+    index_count: u32,
+}
+
+fn draw_overlay(command: VkCommandBuffer, count: u32) {
+    vkCmdBlock! {
+        INTO command;
+        DRAW(count, 1, 0, 0);
+    }
+}
+
+fn meshes(
+    command: VkCommandBuffer,
+    render_pass_begin_info: &VkRenderPassBeginInfo,
+    meshes: &[Mesh],
+) -> Result<(), VkError> {
+    vkCmdBlock! {
+        THIS command;
+        RENDER_PASS(render_pass_begin_info, INLINE) {
+            let mut drawn = 0;
+            for mesh in meshes {
+                if mesh.hidden {
+                    continue;
+                }
+                BIND_VERTEX_BUFFERS(0, 1, &mesh.vertices, &0);
+                DRAW_INDEXED(mesh.index_count, 1, 0, 0, 0);
+                drawn += 1;
+            }
+            if drawn == 0 {
+                DRAW(3, 1, 0, 0);
+            } else if drawn == 1 {
+                LABEL("single") {
+                    draw_overlay(command, 3);
+                }
+            } else {
+                draw_overlay(command, 6);
+            }
+        }
+    }
+}
+
+fn passes(
+    command: VkCommandBuffer,
+    render_pass_begin_info: &VkRenderPassBeginInfo,
+    count: u32,
+) -> Result<(), VkError> {
+    vkCmdBlock! {
+        THIS command;
+        for _ in 0..count {
+            RENDER_PASS(render_pass_begin_info, INLINE) {
+                DRAW(3, 1, 0, 0);
+            }
+            DISPATCH(1, 1, 1);
+        }
+        {
+            BEGIN_RENDER_PASS(render_pass_begin_info, VkSubpassContents::VK_SUBPASS_CONTENTS_INLINE);
+            if count > 1 {
+                DRAW(3, 1, 0, 0);
+            }
+            END_RENDER_PASS();
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,54 @@
+// every header of vkCmdBlock!, compiled only as there is no device to record on,
+// THIS blocks evaluate to the Result of begin and end
+#![allow(dead_code)]
+
+use vrx::*;
+
+fn this(
+    command: VkCommandBuffer,
+    region: &VkBufferCopy,
+    src: VkBuffer,
+    dst: VkBuffer,
+) -> Result<(), VkError> {
+    vkCmdBlock! {
+        THIS command;
+        COPY_BUFFER(src, dst, 1, region);
+    }
+}
+
+fn usage(
+    upload: VkCommandBuffer,
+    region: &VkBufferCopy,
+    staging: VkBuffer,
+    vertices: VkBuffer,
+) -> Result<(), VkError> {
+    vkCmdBlock! {
+        THIS upload USAGE ONE_TIME_SUBMIT | SIMULTANEOUS_USE;
+        COPY_BUFFER(staging, vertices, 1, region);
+    }
+}
+
+fn secondary(
+    secondary: VkCommandBuffer,
+    render_pass: VkRenderPass,
+    framebuffer: VkFramebuffer,
+) -> Result<(), VkError> {
+    vkCmdBlock! {
+        THIS secondary USAGE SIMULTANEOUS_USE SECONDARY (render_pass, 0, framebuffer);
+        DRAW(3, 1, 0, 0);
+    }?;
+    vkCmdBlock! {
+        THIS secondary SECONDARY;
+        DISPATCH(1, 1, 1);
+    }
+}
+
+fn into(command: VkCommandBuffer, secondary: VkCommandBuffer) {
+    vkCmdBlock! {
+        INTO command;
+        let count = 1;
+        EXECUTE_COMMANDS(count, &secondary);
+    }
+}
+
+fn main() {}